@@ -0,0 +1,317 @@
+//! Stateful decoding of the control changes that span several messages:
+//! the 14-bit controllers sent as MSB/LSB pairs, and the registered and non-registered parameters.
+
+use crate::types::{U14, U4, U7};
+
+const NUM_CHANNELS: usize = 16;
+
+/// The controllers from 0 to 31 are the MSB of the controllers from 32 to 63
+pub const NUM_CONTROLLERS_14BIT: U7 = 32;
+
+const DATA_ENTRY_MSB: U7 = 6;
+const DATA_ENTRY_LSB: U7 = 38;
+const DATA_INCREMENT: U7 = 96;
+const DATA_DECREMENT: U7 = 97;
+const NRPN_LSB: U7 = 98;
+const NRPN_MSB: U7 = 99;
+const RPN_LSB: U7 = 100;
+const RPN_MSB: U7 = 101;
+
+/// The parameter number that deselects any parameter, so the data entry is ignored
+pub const PARAMETER_NULL: U14 = 0x3fff;
+
+pub const RPN_PITCH_BEND_SENSITIVITY: U14 = 0x0000;
+pub const RPN_FINE_TUNING: U14 = 0x0001;
+pub const RPN_COARSE_TUNING: U14 = 0x0002;
+pub const RPN_MPE_CONFIGURATION: U14 = 0x0006;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ParameterChange {
+  /// The data entry with the MSB in the upper 7 bits and the LSB in the lower 7 bits
+  Value(U14),
+  /// The data increment, with the value of the controller (usually ignored)
+  Increment(U7),
+  /// The data decrement, with the value of the controller (usually ignored)
+  Decrement(U7),
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ControllerMessage {
+  /// A controller from 0 to 31 combined with its LSB from 32 to 63.
+  /// It is sent when any of the two parts change, and the LSB is reset to zero when the MSB changes.
+  Controller14 {
+    channel: U4,
+    controller: U7,
+    value: U14,
+  },
+
+  /// A change of a Registered Parameter Number
+  Rpn {
+    channel: U4,
+    parameter: U14,
+    change: ParameterChange,
+  },
+
+  /// A change of a Non-Registered Parameter Number
+  Nrpn {
+    channel: U4,
+    parameter: U14,
+    change: ParameterChange,
+  },
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum ParameterKind {
+  Registered,
+  NonRegistered,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ChannelState {
+  msb: [U7; NUM_CONTROLLERS_14BIT as usize],
+  lsb: [U7; NUM_CONTROLLERS_14BIT as usize],
+  parameter_kind: ParameterKind,
+  parameter_msb: U7,
+  parameter_lsb: U7,
+}
+
+impl ChannelState {
+  fn new() -> Self {
+    ChannelState {
+      msb: [0; NUM_CONTROLLERS_14BIT as usize],
+      lsb: [0; NUM_CONTROLLERS_14BIT as usize],
+      parameter_kind: ParameterKind::Registered,
+      parameter_msb: 0x7f,
+      parameter_lsb: 0x7f,
+    }
+  }
+
+  fn parameter(&self) -> U14 {
+    (U14::from(self.parameter_msb) << 7) | U14::from(self.parameter_lsb)
+  }
+
+  fn value(&self, controller: U7) -> U14 {
+    let index = usize::from(controller);
+    (U14::from(self.msb[index]) << 7) | U14::from(self.lsb[index])
+  }
+}
+
+/// Keeps the state of the controllers of every channel, and turns the control changes
+/// into 14-bit controller values and parameter changes.
+///
+/// The control changes for the 7-bit controllers (from 64) are not tracked,
+/// so they can be used directly from the `Message::ControlChange`.
+#[derive(Debug, Clone)]
+pub struct Controllers {
+  channels: [ChannelState; NUM_CHANNELS],
+}
+
+impl Controllers {
+  pub fn new() -> Self {
+    Controllers {
+      channels: [ChannelState::new(); NUM_CHANNELS],
+    }
+  }
+
+  /// Resets the 14-bit controllers of a channel to zero and deselects its parameter,
+  /// as expected when a Reset All Controllers is received.
+  pub fn reset(&mut self, channel: U4) {
+    if let Some(state) = self.channels.get_mut(usize::from(channel)) {
+      *state = ChannelState::new();
+    }
+  }
+
+  /// The current value of a 14-bit controller (from 0 to 31)
+  pub fn controller14(&self, channel: U4, controller: U7) -> Option<U14> {
+    let state = self.channels.get(usize::from(channel))?;
+    Some(controller)
+      .filter(|controller| *controller < NUM_CONTROLLERS_14BIT)
+      .map(|controller| state.value(controller))
+  }
+
+  /// Process a control change and return the high-level message that it completes, if any.
+  ///
+  /// Selecting a parameter does not return anything, and neither does the data entry
+  /// while no parameter is selected (or after it is deselected with the null parameter).
+  pub fn process(&mut self, channel: U4, controller: U7, value: U7) -> Option<ControllerMessage> {
+    let channel = channel & 0x0f;
+    let state = &mut self.channels[usize::from(channel)];
+    let value = value & 0x7f;
+
+    match controller {
+      DATA_ENTRY_MSB => {
+        state.msb[usize::from(controller)] = value;
+        state.lsb[usize::from(controller)] = 0;
+        Self::parameter_change(channel, state, |state| {
+          ParameterChange::Value(state.value(DATA_ENTRY_MSB))
+        })
+      }
+      DATA_ENTRY_LSB => {
+        state.lsb[usize::from(DATA_ENTRY_MSB)] = value;
+        Self::parameter_change(channel, state, |state| {
+          ParameterChange::Value(state.value(DATA_ENTRY_MSB))
+        })
+      }
+      DATA_INCREMENT => {
+        Self::parameter_change(channel, state, |_| ParameterChange::Increment(value))
+      }
+      DATA_DECREMENT => {
+        Self::parameter_change(channel, state, |_| ParameterChange::Decrement(value))
+      }
+      NRPN_MSB | NRPN_LSB | RPN_MSB | RPN_LSB => {
+        let kind = match controller {
+          NRPN_MSB | NRPN_LSB => ParameterKind::NonRegistered,
+          _ => ParameterKind::Registered,
+        };
+        if kind != state.parameter_kind {
+          state.parameter_kind = kind;
+          state.parameter_msb = 0x7f;
+          state.parameter_lsb = 0x7f;
+        }
+        match controller {
+          NRPN_MSB | RPN_MSB => state.parameter_msb = value,
+          _ => state.parameter_lsb = value,
+        }
+        None
+      }
+      controller if controller < NUM_CONTROLLERS_14BIT => {
+        state.msb[usize::from(controller)] = value;
+        state.lsb[usize::from(controller)] = 0;
+        Some(ControllerMessage::Controller14 {
+          channel,
+          controller,
+          value: state.value(controller),
+        })
+      }
+      controller if controller < 2 * NUM_CONTROLLERS_14BIT => {
+        let controller = controller - NUM_CONTROLLERS_14BIT;
+        state.lsb[usize::from(controller)] = value;
+        Some(ControllerMessage::Controller14 {
+          channel,
+          controller,
+          value: state.value(controller),
+        })
+      }
+      _ => None,
+    }
+  }
+
+  fn parameter_change<C>(channel: U4, state: &ChannelState, change: C) -> Option<ControllerMessage>
+  where
+    C: FnOnce(&ChannelState) -> ParameterChange,
+  {
+    let parameter = state.parameter();
+    if parameter == PARAMETER_NULL {
+      return None;
+    }
+
+    let change = change(state);
+    Some(match state.parameter_kind {
+      ParameterKind::Registered => ControllerMessage::Rpn {
+        channel,
+        parameter,
+        change,
+      },
+      ParameterKind::NonRegistered => ControllerMessage::Nrpn {
+        channel,
+        parameter,
+        change,
+      },
+    })
+  }
+}
+
+impl Default for Controllers {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn controller14_msb_and_lsb() {
+    let mut controllers = Controllers::new();
+    assert_eq!(
+      controllers.process(2, 1, 0x40),
+      Some(ControllerMessage::Controller14 {
+        channel: 2,
+        controller: 1,
+        value: 0x2000,
+      })
+    );
+    assert_eq!(
+      controllers.process(2, 33, 0x01),
+      Some(ControllerMessage::Controller14 {
+        channel: 2,
+        controller: 1,
+        value: 0x2001,
+      })
+    );
+    assert_eq!(controllers.controller14(2, 1), Some(0x2001));
+    assert_eq!(controllers.controller14(3, 1), Some(0));
+    assert_eq!(controllers.controller14(2, 64), None);
+    assert_eq!(controllers.process(2, 64, 0x7f), None);
+
+    controllers.process(2, 1, 0x41);
+    assert_eq!(controllers.controller14(2, 1), Some(0x2080));
+  }
+
+  #[test]
+  fn rpn_data_entry() {
+    let mut controllers = Controllers::new();
+    assert_eq!(controllers.process(0, DATA_ENTRY_MSB, 12), None);
+    assert_eq!(controllers.process(0, RPN_MSB, 0), None);
+    assert_eq!(controllers.process(0, RPN_LSB, 0), None);
+    assert_eq!(
+      controllers.process(0, DATA_ENTRY_MSB, 12),
+      Some(ControllerMessage::Rpn {
+        channel: 0,
+        parameter: RPN_PITCH_BEND_SENSITIVITY,
+        change: ParameterChange::Value(12 << 7),
+      })
+    );
+    assert_eq!(
+      controllers.process(0, DATA_ENTRY_LSB, 50),
+      Some(ControllerMessage::Rpn {
+        channel: 0,
+        parameter: RPN_PITCH_BEND_SENSITIVITY,
+        change: ParameterChange::Value((12 << 7) | 50),
+      })
+    );
+    assert_eq!(
+      controllers.process(0, DATA_INCREMENT, 0),
+      Some(ControllerMessage::Rpn {
+        channel: 0,
+        parameter: RPN_PITCH_BEND_SENSITIVITY,
+        change: ParameterChange::Increment(0),
+      })
+    );
+
+    controllers.process(0, RPN_MSB, 0x7f);
+    controllers.process(0, RPN_LSB, 0x7f);
+    assert_eq!(controllers.process(0, DATA_DECREMENT, 0), None);
+  }
+
+  #[test]
+  fn nrpn_deselects_rpn() {
+    let mut controllers = Controllers::new();
+    controllers.process(5, RPN_MSB, 0);
+    controllers.process(5, RPN_LSB, 2);
+    controllers.process(5, NRPN_MSB, 0x12);
+    controllers.process(5, NRPN_LSB, 0x34);
+    assert_eq!(
+      controllers.process(5, DATA_ENTRY_MSB, 1),
+      Some(ControllerMessage::Nrpn {
+        channel: 5,
+        parameter: (0x12 << 7) | 0x34,
+        change: ParameterChange::Value(1 << 7),
+      })
+    );
+
+    controllers.reset(5);
+    assert_eq!(controllers.process(5, DATA_ENTRY_MSB, 1), None);
+  }
+}
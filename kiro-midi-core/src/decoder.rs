@@ -1,28 +1,28 @@
 use crate::messages::Message;
 use crate::types::{U14, U4, U7};
 
-const NOTE_OFF_MASK: u8 = 0b1000_0000;
-const NOTE_ON_MASK: u8 = 0b1001_0000;
-const POLYPHONIC_KEY_PRESSURE_MASK: u8 = 0b1010_0000;
-const CONTROL_CHANGE_MASK: u8 = 0b1011_0000;
-const PROGRAM_CHANGE_MASK: u8 = 0b1100_0000;
-const CHANNEL_PRESSURE_MASK: u8 = 0b1101_0000;
-const PITCH_BEND_MASK: u8 = 0b1110_0000;
-const SYSTEM_MASK: u8 = 0b1111_0000;
-
-const SYSEX_START_CODE: u8 = 0b1111_0000;
-const MTC_QUARTER_FRAME_CODE: u8 = 0b1111_0001;
-const SONG_POSITION_POINTER_CODE: u8 = 0b1111_0010;
-const SONG_SELECT_CODE: u8 = 0b1111_0011;
-const TUNE_REQUEST_CODE: u8 = 0b1111_0110;
-const SYSEX_END_CODE: u8 = 0b1111_0111;
-
-const TIMING_CLOCK_CODE: u8 = 0b1111_1000;
-const START_CODE: u8 = 0b1111_1010;
-const CONTINUE_CODE: u8 = 0b1111_1011;
-const STOP_CODE: u8 = 0b1111_1100;
-const ACTIVE_SENSING_CODE: u8 = 0b1111_1110;
-const SYSTEM_RESET_CODE: u8 = 0b1111_1111;
+pub(crate) const NOTE_OFF_MASK: u8 = 0b1000_0000;
+pub(crate) const NOTE_ON_MASK: u8 = 0b1001_0000;
+pub(crate) const POLYPHONIC_KEY_PRESSURE_MASK: u8 = 0b1010_0000;
+pub(crate) const CONTROL_CHANGE_MASK: u8 = 0b1011_0000;
+pub(crate) const PROGRAM_CHANGE_MASK: u8 = 0b1100_0000;
+pub(crate) const CHANNEL_PRESSURE_MASK: u8 = 0b1101_0000;
+pub(crate) const PITCH_BEND_MASK: u8 = 0b1110_0000;
+pub(crate) const SYSTEM_MASK: u8 = 0b1111_0000;
+
+pub(crate) const SYSEX_START_CODE: u8 = 0b1111_0000;
+pub(crate) const MTC_QUARTER_FRAME_CODE: u8 = 0b1111_0001;
+pub(crate) const SONG_POSITION_POINTER_CODE: u8 = 0b1111_0010;
+pub(crate) const SONG_SELECT_CODE: u8 = 0b1111_0011;
+pub(crate) const TUNE_REQUEST_CODE: u8 = 0b1111_0110;
+pub(crate) const SYSEX_END_CODE: u8 = 0b1111_0111;
+
+pub(crate) const TIMING_CLOCK_CODE: u8 = 0b1111_1000;
+pub(crate) const START_CODE: u8 = 0b1111_1010;
+pub(crate) const CONTINUE_CODE: u8 = 0b1111_1011;
+pub(crate) const STOP_CODE: u8 = 0b1111_1100;
+pub(crate) const ACTIVE_SENSING_CODE: u8 = 0b1111_1110;
+pub(crate) const SYSTEM_RESET_CODE: u8 = 0b1111_1111;
 
 pub type Result<T> = core::result::Result<T, Error>;
 
@@ -337,7 +337,7 @@ impl<'a> Decoder<'a> {
     }
   }
 
-  fn decode_realtime_message(status: u8) -> Result<Message> {
+  pub(crate) fn decode_realtime_message(status: u8) -> Result<Message> {
     match status {
       TIMING_CLOCK_CODE => Ok(Message::TimingClock),
       START_CODE => Ok(Message::Start),
@@ -349,7 +349,7 @@ impl<'a> Decoder<'a> {
     }
   }
 
-  fn decode_channel_message(status: u8, data: &[U7]) -> Result<Message> {
+  pub(crate) fn decode_channel_message(status: u8, data: &[U7]) -> Result<Message> {
     let channel = status & 0x0f;
     match status & 0xf0 {
       NOTE_OFF_MASK => Self::note_off(channel, data),
@@ -364,7 +364,7 @@ impl<'a> Decoder<'a> {
   }
 
   // SysEx messages follow a different path through the internal state machine
-  fn decode_system_common_message(status: u8, data: &[U7]) -> Result<Message> {
+  pub(crate) fn decode_system_common_message(status: u8, data: &[U7]) -> Result<Message> {
     match status {
       MTC_QUARTER_FRAME_CODE => Self::mtc_quarter_frame(data),
       SONG_POSITION_POINTER_CODE => Self::song_position_pointer(data),
@@ -0,0 +1,386 @@
+use crate::decoder::{
+  ACTIVE_SENSING_CODE, CHANNEL_PRESSURE_MASK, CONTINUE_CODE, CONTROL_CHANGE_MASK,
+  MTC_QUARTER_FRAME_CODE, NOTE_OFF_MASK, NOTE_ON_MASK, PITCH_BEND_MASK,
+  POLYPHONIC_KEY_PRESSURE_MASK, PROGRAM_CHANGE_MASK, SONG_POSITION_POINTER_CODE, SONG_SELECT_CODE,
+  START_CODE, STOP_CODE, SYSEX_END_CODE, SYSEX_START_CODE, SYSTEM_RESET_CODE, TIMING_CLOCK_CODE,
+  TUNE_REQUEST_CODE,
+};
+use crate::messages::Message;
+use crate::types::{U14, U4, U7};
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Error {
+  /// The buffer does not have enough capacity for the encoded message.
+  /// The error includes the number of bytes required.
+  BufferOverflow(usize),
+
+  /// A `Message::SysEx` only has the length of the data, so it needs to be encoded with `encode_sysex`
+  MissingSysExData,
+
+  /// The SysEx data contains a byte with the most significant bit set
+  InvalidSysExData(u8),
+}
+
+/// Encodes messages into bytes without allocating memory.
+///
+/// When the running status is enabled, the status of a channel message is omitted
+/// if it is the same as the one of the previous channel message.
+/// The real-time messages keep the running status, but the System Common ones cancel it.
+#[derive(Debug, Clone)]
+pub struct Encoder {
+  running_status_enabled: bool,
+  running_status: Option<u8>,
+}
+
+impl Encoder {
+  pub fn new() -> Self {
+    Encoder {
+      running_status_enabled: false,
+      running_status: None,
+    }
+  }
+
+  pub fn set_running_status(&mut self, enabled: bool) {
+    self.running_status_enabled = enabled;
+    self.running_status = None;
+  }
+
+  /// Forgets the last status, so the next channel message will include it.
+  /// It should be called whenever the output is interrupted or shared with another source.
+  pub fn reset(&mut self) {
+    self.running_status = None;
+  }
+
+  /// Encodes a message into the buffer and returns the number of bytes written
+  pub fn encode(&mut self, message: &Message, buffer: &mut [u8]) -> Result<usize> {
+    match *message {
+      Message::NoteOff {
+        channel,
+        key,
+        velocity,
+      } => self.channel_message(NOTE_OFF_MASK, channel, &[key, velocity], buffer),
+      Message::NoteOn {
+        channel,
+        key,
+        velocity,
+      } => self.channel_message(NOTE_ON_MASK, channel, &[key, velocity], buffer),
+      Message::PolyphonicKeyPressure {
+        channel,
+        key,
+        value,
+      } => self.channel_message(POLYPHONIC_KEY_PRESSURE_MASK, channel, &[key, value], buffer),
+      Message::ControlChange {
+        channel,
+        controller,
+        value,
+      } => self.channel_message(CONTROL_CHANGE_MASK, channel, &[controller, value], buffer),
+      Message::ProgramChange { channel, value } => {
+        self.channel_message(PROGRAM_CHANGE_MASK, channel, &[value], buffer)
+      }
+      Message::ChannelPressure { channel, value } => {
+        self.channel_message(CHANNEL_PRESSURE_MASK, channel, &[value], buffer)
+      }
+      Message::PitchBend { channel, value } => {
+        self.channel_message(PITCH_BEND_MASK, channel, &u7_parts_from_u14(value), buffer)
+      }
+      Message::AllSoundOff { channel } => self.channel_mode(channel, 120, 0, buffer),
+      Message::ResetAllControllers { channel } => self.channel_mode(channel, 121, 0, buffer),
+      Message::LocalControlOff { channel } => self.channel_mode(channel, 122, 0, buffer),
+      Message::LocalControlOn { channel } => self.channel_mode(channel, 122, 127, buffer),
+      Message::AllNotesOff { channel } => self.channel_mode(channel, 123, 0, buffer),
+      Message::OmniModeOff { channel } => self.channel_mode(channel, 124, 0, buffer),
+      Message::OmniModeOn { channel } => self.channel_mode(channel, 125, 0, buffer),
+      Message::MonoModeOn {
+        channel,
+        num_channels,
+      } => self.channel_mode(channel, 126, num_channels, buffer),
+      Message::PolyModeOn { channel } => self.channel_mode(channel, 127, 0, buffer),
+      Message::MTCQuarterFrame { msg_type, value } => {
+        let data = ((msg_type & 0x07) << 4) | (value & 0x0f);
+        self.system_common(MTC_QUARTER_FRAME_CODE, &[data], buffer)
+      }
+      Message::SongPositionPointer { beats } => self.system_common(
+        SONG_POSITION_POINTER_CODE,
+        &u7_parts_from_u14(beats),
+        buffer,
+      ),
+      Message::SongSelect { song } => self.system_common(SONG_SELECT_CODE, &[song], buffer),
+      Message::TuneRequest => self.system_common(TUNE_REQUEST_CODE, &[], buffer),
+      Message::SysEx { .. } => Err(Error::MissingSysExData),
+      Message::TimingClock => Self::realtime(TIMING_CLOCK_CODE, buffer),
+      Message::Start => Self::realtime(START_CODE, buffer),
+      Message::Continue => Self::realtime(CONTINUE_CODE, buffer),
+      Message::Stop => Self::realtime(STOP_CODE, buffer),
+      Message::ActiveSensing => Self::realtime(ACTIVE_SENSING_CODE, buffer),
+      Message::SystemReset => Self::realtime(SYSTEM_RESET_CODE, buffer),
+    }
+  }
+
+  /// Encodes a SysEx message with its start and end bytes, from the data in between,
+  /// and returns the number of bytes written
+  pub fn encode_sysex(&mut self, data: &[U7], buffer: &mut [u8]) -> Result<usize> {
+    if let Some(invalid) = data.iter().find(|value| **value & 0x80 != 0) {
+      return Err(Error::InvalidSysExData(*invalid));
+    }
+
+    let length = data.len() + 2;
+    if buffer.len() < length {
+      return Err(Error::BufferOverflow(length));
+    }
+
+    buffer[0] = SYSEX_START_CODE;
+    buffer[1..=data.len()].copy_from_slice(data);
+    buffer[length - 1] = SYSEX_END_CODE;
+    self.running_status = None;
+    Ok(length)
+  }
+
+  fn channel_message(
+    &mut self,
+    mask: u8,
+    channel: U4,
+    data: &[U7],
+    buffer: &mut [u8],
+  ) -> Result<usize> {
+    let status = mask | (channel & 0x0f);
+    let skip_status = self.running_status_enabled && self.running_status == Some(status);
+    let status = if skip_status { None } else { Some(status) };
+    let length = Self::write(status, data, buffer)?;
+    if self.running_status_enabled {
+      self.running_status = status.or(self.running_status);
+    }
+    Ok(length)
+  }
+
+  fn channel_mode(
+    &mut self,
+    channel: U4,
+    controller: U7,
+    value: U7,
+    buffer: &mut [u8],
+  ) -> Result<usize> {
+    self.channel_message(CONTROL_CHANGE_MASK, channel, &[controller, value], buffer)
+  }
+
+  fn system_common(&mut self, status: u8, data: &[U7], buffer: &mut [u8]) -> Result<usize> {
+    let length = Self::write(Some(status), data, buffer)?;
+    self.running_status = None;
+    Ok(length)
+  }
+
+  fn realtime(status: u8, buffer: &mut [u8]) -> Result<usize> {
+    Self::write(Some(status), &[], buffer)
+  }
+
+  fn write(status: Option<u8>, data: &[U7], buffer: &mut [u8]) -> Result<usize> {
+    let status_length = if status.is_some() { 1 } else { 0 };
+    let length = status_length + data.len();
+    if buffer.len() < length {
+      return Err(Error::BufferOverflow(length));
+    }
+
+    if let Some(status) = status {
+      buffer[0] = status;
+    }
+    for (byte, value) in buffer[status_length..length].iter_mut().zip(data) {
+      *byte = *value & 0x7f;
+    }
+    Ok(length)
+  }
+}
+
+impl Default for Encoder {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+fn u7_parts_from_u14(value: U14) -> [U7; 2] {
+  [(value & 0x7f) as U7, ((value >> 7) & 0x7f) as U7]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::decoder::{CallbackResult, Decoder, DecoderCallbacks};
+
+  #[derive(Default)]
+  struct Collector {
+    messages: Vec<Message>,
+    sysex: Vec<Vec<U7>>,
+  }
+
+  impl DecoderCallbacks for Collector {
+    fn on_message(&mut self, message: Message) -> CallbackResult {
+      self.messages.push(message);
+      CallbackResult::Continue
+    }
+
+    fn on_sysex(&mut self, data: &[U7]) -> CallbackResult {
+      self.sysex.push(data.to_vec());
+      CallbackResult::Continue
+    }
+  }
+
+  fn encode_all(encoder: &mut Encoder, messages: &[Message]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut buffer = [0u8; 3];
+    for message in messages {
+      let length = encoder.encode(message, &mut buffer).unwrap();
+      bytes.extend_from_slice(&buffer[..length]);
+    }
+    bytes
+  }
+
+  #[test]
+  fn encode_decode_round_trip() {
+    let messages = vec![
+      Message::NoteOff {
+        channel: 1,
+        key: 60,
+        velocity: 64,
+      },
+      Message::NoteOn {
+        channel: 15,
+        key: 127,
+        velocity: 1,
+      },
+      Message::PolyphonicKeyPressure {
+        channel: 2,
+        key: 40,
+        value: 90,
+      },
+      Message::ControlChange {
+        channel: 3,
+        controller: 74,
+        value: 12,
+      },
+      Message::ProgramChange {
+        channel: 4,
+        value: 5,
+      },
+      Message::ChannelPressure {
+        channel: 5,
+        value: 100,
+      },
+      Message::PitchBend {
+        channel: 6,
+        value: 0x2001,
+      },
+      Message::AllSoundOff { channel: 0 },
+      Message::ResetAllControllers { channel: 0 },
+      Message::LocalControlOff { channel: 0 },
+      Message::LocalControlOn { channel: 0 },
+      Message::AllNotesOff { channel: 0 },
+      Message::OmniModeOff { channel: 0 },
+      Message::OmniModeOn { channel: 0 },
+      Message::MonoModeOn {
+        channel: 0,
+        num_channels: 4,
+      },
+      Message::PolyModeOn { channel: 0 },
+      Message::MTCQuarterFrame {
+        msg_type: 7,
+        value: 3,
+      },
+      Message::SongPositionPointer { beats: 0x1234 },
+      Message::SongSelect { song: 9 },
+      Message::TuneRequest,
+      Message::TimingClock,
+      Message::Start,
+      Message::Continue,
+      Message::Stop,
+      Message::ActiveSensing,
+      Message::SystemReset,
+    ];
+
+    let bytes = encode_all(&mut Encoder::new(), &messages);
+    let mut collector = Collector::default();
+    let mut data_buffer = [0u8; 2];
+    let mut decoder = Decoder::new(&mut data_buffer);
+    assert_eq!(decoder.decode(&mut bytes.iter(), &mut collector), Ok(()));
+    assert_eq!(collector.messages, messages);
+  }
+
+  #[test]
+  fn encode_with_running_status() {
+    let mut encoder = Encoder::new();
+    encoder.set_running_status(true);
+    let note_on = |key| Message::NoteOn {
+      channel: 0,
+      key,
+      velocity: 100,
+    };
+    let messages = [
+      note_on(60),
+      note_on(64),
+      Message::TimingClock,
+      note_on(67),
+      Message::NoteOn {
+        channel: 1,
+        key: 60,
+        velocity: 0,
+      },
+      Message::TuneRequest,
+      Message::NoteOn {
+        channel: 1,
+        key: 64,
+        velocity: 0,
+      },
+    ];
+    assert_eq!(
+      encode_all(&mut encoder, &messages),
+      vec![0x90, 60, 100, 64, 100, 0xf8, 67, 100, 0x91, 60, 0, 0xf6, 0x91, 64, 0]
+    );
+  }
+
+  #[test]
+  fn encode_sysex() {
+    let mut encoder = Encoder::new();
+    let mut buffer = [0u8; 6];
+    assert_eq!(encoder.encode_sysex(&[1, 2, 3], &mut buffer), Ok(5));
+    assert_eq!(buffer[..5], [0xf0, 1, 2, 3, 0xf7]);
+
+    let mut collector = Collector::default();
+    let mut data_buffer = [0u8; 8];
+    let mut decoder = Decoder::new(&mut data_buffer);
+    assert_eq!(
+      decoder.decode(&mut buffer[..5].iter(), &mut collector),
+      Ok(())
+    );
+    assert_eq!(collector.sysex, vec![vec![1, 2, 3]]);
+
+    assert_eq!(
+      encoder.encode_sysex(&[1, 2, 3, 4, 5], &mut buffer),
+      Err(Error::BufferOverflow(7))
+    );
+    assert_eq!(
+      encoder.encode_sysex(&[1, 0x80], &mut buffer),
+      Err(Error::InvalidSysExData(0x80))
+    );
+    assert_eq!(
+      encoder.encode(&Message::SysEx { length: 3 }, &mut buffer),
+      Err(Error::MissingSysExData)
+    );
+  }
+
+  #[test]
+  fn encode_buffer_overflow() {
+    let mut encoder = Encoder::new();
+    encoder.set_running_status(true);
+    let message = Message::ControlChange {
+      channel: 0,
+      controller: 1,
+      value: 2,
+    };
+    assert_eq!(
+      encoder.encode(&message, &mut [0u8; 2]),
+      Err(Error::BufferOverflow(3))
+    );
+    let mut buffer = [0u8; 3];
+    assert_eq!(encoder.encode(&message, &mut buffer), Ok(3));
+    assert_eq!(buffer, [0xb0, 1, 2]);
+  }
+}
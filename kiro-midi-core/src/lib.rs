@@ -1,3 +1,8 @@
+pub mod controllers;
 pub mod decoder;
+pub mod encoder;
 pub mod messages;
+pub mod smf;
+pub mod tuning;
 pub mod types;
+pub mod ump;
@@ -0,0 +1,756 @@
+//! Standard MIDI Files (SMF) of type 0 (a single track) and type 1 (multiple simultaneous tracks).
+//!
+//! The events of the tracks are kept with their absolute time in ticks,
+//! and the `TempoMap` converts them into seconds or samples.
+
+use std::io;
+
+use crate::decoder::{
+  Decoder, MTC_QUARTER_FRAME_CODE, SONG_POSITION_POINTER_CODE, SONG_SELECT_CODE, SYSEX_END_CODE,
+  SYSEX_START_CODE, SYSTEM_MASK, TIMING_CLOCK_CODE, TUNE_REQUEST_CODE,
+};
+use crate::encoder::Encoder;
+use crate::messages::Message;
+use crate::types::U7;
+
+const HEADER_CHUNK: &[u8; 4] = b"MThd";
+const TRACK_CHUNK: &[u8; 4] = b"MTrk";
+const HEADER_LENGTH: usize = 6;
+
+const META_EVENT: u8 = 0xff;
+const META_TRACK_NAME: u8 = 0x03;
+const META_END_OF_TRACK: u8 = 0x2f;
+const META_TEMPO: u8 = 0x51;
+const META_TIME_SIGNATURE: u8 = 0x58;
+
+/// The tempo until the first tempo change, 120 bpm
+pub const DEFAULT_TEMPO: u32 = 500_000;
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Error {
+  /// The data does not start with a valid header chunk
+  InvalidHeader,
+
+  /// Only the formats 0 and 1 are supported
+  UnsupportedFormat(u16),
+
+  /// The data ended in the middle of a chunk or an event.
+  /// The error includes the offset where more data was expected.
+  MissingData(usize),
+
+  /// The track does not start with a track chunk. The error includes the index of the track.
+  InvalidTrack(usize),
+
+  /// The event does not have a valid status. The error includes its offset.
+  InvalidEvent(usize),
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Format {
+  /// A single track with all the events (type 0)
+  SingleTrack,
+  /// Multiple tracks played at the same time (type 1)
+  MultipleTracks,
+}
+
+/// How the ticks of the events relate to time
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Timing {
+  /// The ticks are relative to the tempo
+  TicksPerBeat(u16),
+  /// The ticks are absolute, with the frames per second (24, 25, 29 for 29.97 or 30)
+  Smpte { fps: u8, ticks_per_frame: u8 },
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum MetaEvent {
+  TrackName(String),
+  /// The duration of a beat in microseconds
+  Tempo(u32),
+  TimeSignature {
+    numerator: u8,
+    /// The denominator as a power of 2
+    denominator: u8,
+    clocks_per_click: u8,
+    notes_32nd_per_beat: u8,
+  },
+  EndOfTrack,
+  Other {
+    kind: u8,
+    data: Vec<u8>,
+  },
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum TrackEvent {
+  Midi(Message),
+  /// The data of a SysEx message without the start and end bytes
+  SysEx(Vec<U7>),
+  Meta(MetaEvent),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Event {
+  /// The absolute time since the start of the track
+  pub ticks: u64,
+  pub event: TrackEvent,
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Track {
+  /// The events sorted by time
+  pub events: Vec<Event>,
+}
+
+impl Track {
+  pub fn new() -> Self {
+    Track { events: Vec::new() }
+  }
+
+  /// Adds an event keeping the order by time, after any other event with the same time
+  pub fn push(&mut self, ticks: u64, event: TrackEvent) {
+    let index = self
+      .events
+      .iter()
+      .rposition(|event| event.ticks <= ticks)
+      .map_or(0, |index| index + 1);
+    self.events.insert(index, Event { ticks, event });
+  }
+}
+
+/// A MIDI message with the time when it has to be played
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct TimedMessage {
+  pub ticks: u64,
+  pub seconds: f64,
+  pub message: Message,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct MidiFile {
+  pub format: Format,
+  pub timing: Timing,
+  pub tracks: Vec<Track>,
+}
+
+impl MidiFile {
+  pub fn new(format: Format, timing: Timing) -> Self {
+    MidiFile {
+      format,
+      timing,
+      tracks: Vec::new(),
+    }
+  }
+
+  pub fn parse(data: &[u8]) -> Result<Self> {
+    let mut reader = Reader::new(data);
+
+    if reader.bytes(4).ok() != Some(&HEADER_CHUNK[..]) {
+      return Err(Error::InvalidHeader);
+    }
+    let header_length = reader.u32().map_err(|_| Error::InvalidHeader)? as usize;
+    if header_length < HEADER_LENGTH {
+      return Err(Error::InvalidHeader);
+    }
+    let header = reader
+      .bytes(header_length)
+      .map_err(|_| Error::InvalidHeader)?;
+
+    let format = match u16::from_be_bytes([header[0], header[1]]) {
+      0 => Format::SingleTrack,
+      1 => Format::MultipleTracks,
+      format => return Err(Error::UnsupportedFormat(format)),
+    };
+    let num_tracks = usize::from(u16::from_be_bytes([header[2], header[3]]));
+    let timing = if header[4] & 0x80 == 0 {
+      Timing::TicksPerBeat(u16::from_be_bytes([header[4], header[5]]))
+    } else {
+      Timing::Smpte {
+        fps: (header[4] as i8).wrapping_neg() as u8,
+        ticks_per_frame: header[5],
+      }
+    };
+
+    let mut tracks = Vec::with_capacity(num_tracks);
+    for index in 0..num_tracks {
+      if reader.bytes(4).ok() != Some(&TRACK_CHUNK[..]) {
+        return Err(Error::InvalidTrack(index));
+      }
+      let length = reader.u32()? as usize;
+      let offset = reader.offset;
+      let data = reader.bytes(length)?;
+      tracks.push(Self::parse_track(data, offset)?);
+    }
+
+    Ok(MidiFile {
+      format,
+      timing,
+      tracks,
+    })
+  }
+
+  fn parse_track(data: &[u8], offset: usize) -> Result<Track> {
+    let mut reader = Reader::new(data);
+    let mut events = Vec::new();
+    let mut ticks = 0u64;
+    let mut running_status = None;
+
+    while !reader.is_empty() {
+      ticks += u64::from(reader.var_len()?);
+      let event_offset = offset + reader.offset;
+      let mut status = reader.u8()?;
+      let event = match status {
+        META_EVENT => {
+          let kind = reader.u8()?;
+          let length = reader.var_len()? as usize;
+          TrackEvent::Meta(Self::meta_event(kind, reader.bytes(length)?))
+        }
+        SYSEX_START_CODE => {
+          running_status = None;
+          let length = reader.var_len()? as usize;
+          let data = reader.bytes(length)?;
+          let data = match data.split_last() {
+            Some((&SYSEX_END_CODE, data)) => data,
+            _ => data,
+          };
+          TrackEvent::SysEx(data.to_vec())
+        }
+        SYSEX_END_CODE => {
+          // the escaped system messages are supported,
+          // but any other escaped data (SysEx continuations or raw bytes) is skipped
+          running_status = None;
+          let length = reader.var_len()? as usize;
+          match Self::escaped_message(reader.bytes(length)?) {
+            Some(message) => TrackEvent::Midi(message),
+            None => continue,
+          }
+        }
+        _ => {
+          if status & 0x80 == 0 {
+            status = running_status.ok_or(Error::InvalidEvent(event_offset))?;
+            reader.offset -= 1;
+          }
+          let length = match status & 0xf0 {
+            0xc0 | 0xd0 => 1,
+            0x80..=0xe0 => 2,
+            _ => return Err(Error::InvalidEvent(event_offset)),
+          };
+          running_status = Some(status);
+          let data = reader.bytes(length)?;
+          let message = Decoder::decode_channel_message(status, data)
+            .map_err(|_| Error::InvalidEvent(event_offset))?;
+          TrackEvent::Midi(message)
+        }
+      };
+
+      let end_of_track = event == TrackEvent::Meta(MetaEvent::EndOfTrack);
+      events.push(Event { ticks, event });
+      if end_of_track {
+        break;
+      }
+    }
+
+    Ok(Track { events })
+  }
+
+  /// A single system common or real-time message, as they are escaped in a track
+  fn escaped_message(data: &[u8]) -> Option<Message> {
+    let (&status, data) = data.split_first()?;
+    if data.iter().any(|byte| byte & 0x80 != 0) {
+      return None;
+    }
+    match (status, data.len()) {
+      (MTC_QUARTER_FRAME_CODE, 1) | (SONG_POSITION_POINTER_CODE, 2) | (SONG_SELECT_CODE, 1) => {
+        Decoder::decode_system_common_message(status, data).ok()
+      }
+      (TUNE_REQUEST_CODE, 0) => Some(Message::TuneRequest),
+      (status, 0) if status >= TIMING_CLOCK_CODE => Decoder::decode_realtime_message(status).ok(),
+      _ => None,
+    }
+  }
+
+  fn meta_event(kind: u8, data: &[u8]) -> MetaEvent {
+    match (kind, data.len()) {
+      (META_TRACK_NAME, _) => MetaEvent::TrackName(String::from_utf8_lossy(data).into_owned()),
+      (META_END_OF_TRACK, _) => MetaEvent::EndOfTrack,
+      (META_TEMPO, 3) => MetaEvent::Tempo(u32::from_be_bytes([0, data[0], data[1], data[2]])),
+      (META_TIME_SIGNATURE, 4) => MetaEvent::TimeSignature {
+        numerator: data[0],
+        denominator: data[1],
+        clocks_per_click: data[2],
+        notes_32nd_per_beat: data[3],
+      },
+      _ => MetaEvent::Other {
+        kind,
+        data: data.to_vec(),
+      },
+    }
+  }
+
+  /// Writes the file, adding the end of track to the tracks that do not have it
+  pub fn write<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+    let format: u16 = match self.format {
+      Format::SingleTrack => 0,
+      Format::MultipleTracks => 1,
+    };
+    let division = match self.timing {
+      Timing::TicksPerBeat(ticks) => ticks & 0x7fff,
+      Timing::Smpte {
+        fps,
+        ticks_per_frame,
+      } => u16::from_be_bytes([(fps as i8).wrapping_neg() as u8, ticks_per_frame]),
+    };
+
+    writer.write_all(HEADER_CHUNK)?;
+    writer.write_all(&(HEADER_LENGTH as u32).to_be_bytes())?;
+    writer.write_all(&format.to_be_bytes())?;
+    writer.write_all(&(self.tracks.len() as u16).to_be_bytes())?;
+    writer.write_all(&division.to_be_bytes())?;
+
+    for track in self.tracks.iter() {
+      let data = Self::track_data(track)?;
+      writer.write_all(TRACK_CHUNK)?;
+      writer.write_all(&(data.len() as u32).to_be_bytes())?;
+      writer.write_all(&data)?;
+    }
+
+    Ok(())
+  }
+
+  fn track_data(track: &Track) -> io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    let mut encoder = Encoder::new();
+    encoder.set_running_status(true);
+    let mut last_ticks = 0u64;
+    let mut end_of_track = false;
+
+    for Event { ticks, event } in track.events.iter() {
+      let delta = ticks.saturating_sub(last_ticks);
+      if delta > u64::from(MAX_VAR_LEN) {
+        let message = format!("The time between events is too long: {} ticks", delta);
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, message));
+      }
+      write_var_len(&mut data, delta as u32);
+      last_ticks = last_ticks.max(*ticks);
+
+      match event {
+        TrackEvent::Midi(message) => {
+          let encode = |encoder: &mut Encoder, buffer: &mut [u8]| {
+            encoder
+              .encode(message, buffer)
+              .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, format!("{:?}", err)))
+          };
+          let mut buffer = [0u8; 3];
+          let length = encode(&mut Encoder::new(), &mut buffer)?;
+          if buffer[0] & SYSTEM_MASK != SYSTEM_MASK {
+            let length = encode(&mut encoder, &mut buffer)?;
+            data.extend_from_slice(&buffer[..length]);
+          } else {
+            // the system messages are not valid events in a track, so they need to be escaped
+            encoder.reset();
+            data.push(SYSEX_END_CODE);
+            write_var_len(&mut data, length as u32);
+            data.extend_from_slice(&buffer[..length]);
+          }
+        }
+        TrackEvent::SysEx(sysex) => {
+          encoder.reset();
+          data.push(SYSEX_START_CODE);
+          write_var_len(&mut data, sysex.len() as u32 + 1);
+          data.extend_from_slice(sysex);
+          data.push(SYSEX_END_CODE);
+        }
+        TrackEvent::Meta(meta) => {
+          encoder.reset();
+          end_of_track = *meta == MetaEvent::EndOfTrack;
+          Self::write_meta_event(&mut data, meta);
+        }
+      }
+
+      if end_of_track {
+        break;
+      }
+    }
+
+    if !end_of_track {
+      write_var_len(&mut data, 0);
+      Self::write_meta_event(&mut data, &MetaEvent::EndOfTrack);
+    }
+
+    Ok(data)
+  }
+
+  fn write_meta_event(data: &mut Vec<u8>, meta: &MetaEvent) {
+    let (kind, payload) = match meta {
+      MetaEvent::TrackName(name) => (META_TRACK_NAME, name.as_bytes().to_vec()),
+      MetaEvent::Tempo(tempo) => (META_TEMPO, tempo.to_be_bytes()[1..].to_vec()),
+      MetaEvent::TimeSignature {
+        numerator,
+        denominator,
+        clocks_per_click,
+        notes_32nd_per_beat,
+      } => (
+        META_TIME_SIGNATURE,
+        vec![
+          *numerator,
+          *denominator,
+          *clocks_per_click,
+          *notes_32nd_per_beat,
+        ],
+      ),
+      MetaEvent::EndOfTrack => (META_END_OF_TRACK, Vec::new()),
+      MetaEvent::Other { kind, data } => (*kind, data.clone()),
+    };
+    data.push(META_EVENT);
+    data.push(kind);
+    write_var_len(data, payload.len() as u32);
+    data.extend_from_slice(&payload);
+  }
+
+  /// The tempo changes from all the tracks
+  pub fn tempo_map(&self) -> TempoMap {
+    let tempos: Vec<(u64, u32)> = self
+      .tracks
+      .iter()
+      .flat_map(|track| track.events.iter())
+      .filter_map(|event| match event.event {
+        TrackEvent::Meta(MetaEvent::Tempo(tempo)) => Some((event.ticks, tempo)),
+        _ => None,
+      })
+      .collect();
+    TempoMap::new(self.timing, &tempos)
+  }
+
+  /// The MIDI messages from all the tracks merged by time, with their time in seconds.
+  /// The SysEx and meta events are not included.
+  pub fn messages(&self) -> Vec<TimedMessage> {
+    let tempo_map = self.tempo_map();
+    let mut messages: Vec<TimedMessage> = self
+      .tracks
+      .iter()
+      .flat_map(|track| track.events.iter())
+      .filter_map(|event| match event.event {
+        TrackEvent::Midi(message) => Some(TimedMessage {
+          ticks: event.ticks,
+          seconds: tempo_map.seconds(event.ticks),
+          message,
+        }),
+        _ => None,
+      })
+      .collect();
+    // the sort is stable, so the order of the events with the same time is kept
+    messages.sort_by_key(|message| message.ticks);
+    messages
+  }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+struct TempoChange {
+  ticks: u64,
+  seconds: f64,
+  /// Duration of a beat in microseconds
+  tempo: u32,
+}
+
+/// Converts the time in ticks into seconds or samples
+#[derive(Debug, PartialEq, Clone)]
+pub struct TempoMap {
+  timing: Timing,
+  changes: Vec<TempoChange>,
+}
+
+impl TempoMap {
+  /// Creates the tempo map from the tempo changes (ticks, microseconds per beat).
+  /// The changes are sorted by time, keeping the order of the ones at the same time.
+  pub fn new(timing: Timing, tempos: &[(u64, u32)]) -> Self {
+    let mut tempos = tempos.to_vec();
+    tempos.sort_by_key(|(ticks, _)| *ticks);
+
+    let mut changes = vec![TempoChange {
+      ticks: 0,
+      seconds: 0.0,
+      tempo: DEFAULT_TEMPO,
+    }];
+    for (ticks, tempo) in tempos.iter() {
+      let seconds = Self::seconds_from(timing, changes.last().unwrap(), *ticks);
+      if let Some(last) = changes.last_mut().filter(|last| last.ticks == *ticks) {
+        last.tempo = *tempo;
+      } else {
+        changes.push(TempoChange {
+          ticks: *ticks,
+          seconds,
+          tempo: *tempo,
+        });
+      }
+    }
+    TempoMap { timing, changes }
+  }
+
+  pub fn seconds(&self, ticks: u64) -> f64 {
+    let index = self
+      .changes
+      .iter()
+      .rposition(|change| change.ticks <= ticks)
+      .unwrap_or(0);
+    Self::seconds_from(self.timing, &self.changes[index], ticks)
+  }
+
+  pub fn samples(&self, ticks: u64, sample_rate: f64) -> u64 {
+    (self.seconds(ticks) * sample_rate).round() as u64
+  }
+
+  fn seconds_from(timing: Timing, change: &TempoChange, ticks: u64) -> f64 {
+    match timing {
+      Timing::TicksPerBeat(ticks_per_beat) => {
+        let beats = (ticks - change.ticks) as f64 / f64::from(ticks_per_beat.max(1));
+        change.seconds + beats * f64::from(change.tempo) * 1e-6
+      }
+      Timing::Smpte {
+        fps,
+        ticks_per_frame,
+      } => {
+        let fps = if fps == 29 {
+          30.0 / 1.001
+        } else {
+          f64::from(fps)
+        };
+        ticks as f64 / (fps * f64::from(ticks_per_frame.max(1)))
+      }
+    }
+  }
+}
+
+/// The maximum value of a variable length quantity
+const MAX_VAR_LEN: u32 = 0x0fff_ffff;
+
+fn write_var_len(data: &mut Vec<u8>, value: u32) {
+  debug_assert!(
+    value <= MAX_VAR_LEN,
+    "Variable length quantity too big: {}",
+    value
+  );
+  let mut shift = 21;
+  while shift > 0 && (value >> shift) == 0 {
+    shift -= 7;
+  }
+  while shift > 0 {
+    data.push(0x80 | ((value >> shift) & 0x7f) as u8);
+    shift -= 7;
+  }
+  data.push((value & 0x7f) as u8);
+}
+
+struct Reader<'a> {
+  data: &'a [u8],
+  offset: usize,
+}
+
+impl<'a> Reader<'a> {
+  fn new(data: &'a [u8]) -> Self {
+    Reader { data, offset: 0 }
+  }
+
+  fn is_empty(&self) -> bool {
+    self.offset >= self.data.len()
+  }
+
+  fn bytes(&mut self, length: usize) -> Result<&'a [u8]> {
+    let bytes = self
+      .data
+      .get(self.offset..self.offset + length)
+      .ok_or(Error::MissingData(self.offset))?;
+    self.offset += length;
+    Ok(bytes)
+  }
+
+  fn u8(&mut self) -> Result<u8> {
+    self.bytes(1).map(|bytes| bytes[0])
+  }
+
+  fn u32(&mut self) -> Result<u32> {
+    self
+      .bytes(4)
+      .map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+  }
+
+  fn var_len(&mut self) -> Result<u32> {
+    let mut value = 0u32;
+    for _ in 0..4 {
+      let byte = self.u8()?;
+      value = (value << 7) | u32::from(byte & 0x7f);
+      if byte & 0x80 == 0 {
+        return Ok(value);
+      }
+    }
+    Err(Error::InvalidEvent(self.offset))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::float_cmp)]
+
+  use super::*;
+
+  fn note_on(key: U7, velocity: U7) -> TrackEvent {
+    TrackEvent::Midi(Message::NoteOn {
+      channel: 0,
+      key,
+      velocity,
+    })
+  }
+
+  #[test]
+  fn parse_single_track_with_running_status() {
+    let mut data = Vec::new();
+    data.extend_from_slice(b"MThd\x00\x00\x00\x06\x00\x00\x00\x01\x00\x60");
+    let track = [
+      0x00, 0xff, 0x51, 0x03, 0x07, 0xa1, 0x20, // tempo 500000
+      0x00, 0x90, 60, 100, // note on
+      0x60, 60, 0, // running status note on with velocity 0 after 96 ticks
+      0x81, 0x40, 0xf0, 0x03, 0x01, 0x02, 0xf7, // sysex after 192 ticks
+      0x00, 0xff, 0x2f, 0x00,
+    ];
+    data.extend_from_slice(b"MTrk");
+    data.extend_from_slice(&(track.len() as u32).to_be_bytes());
+    data.extend_from_slice(&track);
+
+    let file = MidiFile::parse(&data).unwrap();
+    assert_eq!(file.format, Format::SingleTrack);
+    assert_eq!(file.timing, Timing::TicksPerBeat(96));
+    let events = &file.tracks[0].events;
+    assert_eq!(events.len(), 5);
+    assert_eq!(events[0].event, TrackEvent::Meta(MetaEvent::Tempo(500_000)));
+    assert_eq!(events[2].ticks, 96);
+    assert_eq!(events[2].event, note_on(60, 0));
+    assert_eq!(events[3].ticks, 288);
+    assert_eq!(events[3].event, TrackEvent::SysEx(vec![1, 2]));
+
+    let messages = file.messages();
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[1].seconds, 0.5);
+  }
+
+  #[test]
+  fn write_and_parse_multiple_tracks() {
+    let mut file = MidiFile::new(Format::MultipleTracks, Timing::TicksPerBeat(480));
+    let mut tempo = Track::new();
+    tempo.push(
+      0,
+      TrackEvent::Meta(MetaEvent::TrackName("tempo".to_string())),
+    );
+    tempo.push(960, TrackEvent::Meta(MetaEvent::Tempo(250_000)));
+    let mut notes = Track::new();
+    notes.push(480, note_on(64, 90));
+    notes.push(0, note_on(60, 100));
+    notes.push(1920, note_on(64, 0));
+    notes.push(100_000, TrackEvent::SysEx(vec![0x7e, 0x7f, 0x09, 0x01]));
+    file.tracks = vec![tempo, notes];
+
+    let mut data = Vec::new();
+    file.write(&mut data).unwrap();
+    let parsed = MidiFile::parse(&data).unwrap();
+    assert_eq!(parsed.format, Format::MultipleTracks);
+    assert_eq!(parsed.tracks.len(), 2);
+    assert_eq!(parsed.tracks[1].events[0].event, note_on(60, 100));
+    assert_eq!(
+      parsed.tracks[1].events[3].event,
+      TrackEvent::SysEx(vec![0x7e, 0x7f, 0x09, 0x01])
+    );
+    assert_eq!(
+      parsed.tracks[0].events.last().unwrap().event,
+      TrackEvent::Meta(MetaEvent::EndOfTrack)
+    );
+
+    let messages = parsed.messages();
+    let seconds: Vec<f64> = messages.iter().map(|message| message.seconds).collect();
+    assert_eq!(seconds, vec![0.0, 0.5, 1.5]);
+  }
+
+  #[test]
+  fn write_and_parse_escaped_system_messages() {
+    let mut track = Track::new();
+    track.push(0, note_on(60, 100));
+    track.push(10, TrackEvent::Midi(Message::TimingClock));
+    track.push(10, note_on(62, 100));
+    track.push(
+      20,
+      TrackEvent::Midi(Message::SongPositionPointer { beats: 0x1234 }),
+    );
+    track.push(20, note_on(64, 100));
+    let mut file = MidiFile::new(Format::SingleTrack, Timing::TicksPerBeat(96));
+    file.tracks = vec![track.clone()];
+
+    let mut data = Vec::new();
+    file.write(&mut data).unwrap();
+    assert_eq!(
+      &data[22..38],
+      &[
+        0x00, 0x90, 60, 100, // note on
+        0x0a, 0xf7, 0x01, 0xf8, // escaped timing clock
+        0x00, 0x90, 62, 100, // note on without running status after the escape
+        0x0a, 0xf7, 0x03, 0xf2,
+      ]
+    );
+
+    let parsed = MidiFile::parse(&data).unwrap();
+    let mut expected = track.events;
+    expected.push(Event {
+      ticks: 20,
+      event: TrackEvent::Meta(MetaEvent::EndOfTrack),
+    });
+    assert_eq!(parsed.tracks[0].events, expected);
+  }
+
+  #[test]
+  fn tempo_map_to_samples() {
+    let tempo_map = TempoMap::new(Timing::TicksPerBeat(100), &[(0, 1_000_000), (200, 500_000)]);
+    assert_eq!(tempo_map.seconds(100), 1.0);
+    assert_eq!(tempo_map.seconds(300), 2.5);
+    assert_eq!(tempo_map.samples(300, 48000.0), 120_000);
+
+    let smpte = TempoMap::new(
+      Timing::Smpte {
+        fps: 25,
+        ticks_per_frame: 40,
+      },
+      &[(0, 1_000_000)],
+    );
+    assert_eq!(smpte.seconds(1000), 1.0);
+  }
+
+  #[test]
+  fn tempo_map_with_unsorted_tempos() {
+    let tempos = [(200, 500_000), (0, 1_000_000), (200, 250_000)];
+    let tempo_map = TempoMap::new(Timing::TicksPerBeat(100), &tempos);
+    assert_eq!(tempo_map.seconds(100), 1.0);
+    assert_eq!(tempo_map.seconds(300), 2.25);
+  }
+
+  #[test]
+  fn write_too_long_delta() {
+    let mut track = Track::new();
+    track.push(0, note_on(60, 100));
+    track.push(u64::from(MAX_VAR_LEN) + 1, note_on(60, 0));
+    let mut file = MidiFile::new(Format::SingleTrack, Timing::TicksPerBeat(96));
+    file.tracks = vec![track];
+
+    let err = file.write(&mut Vec::new()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+  }
+
+  #[test]
+  fn parse_errors() {
+    assert_eq!(MidiFile::parse(b"RIFF"), Err(Error::InvalidHeader));
+    assert_eq!(
+      MidiFile::parse(b"MThd\x00\x00\x00\x06\x00\x02\x00\x01\x00\x60"),
+      Err(Error::UnsupportedFormat(2))
+    );
+    assert_eq!(
+      MidiFile::parse(b"MThd\x00\x00\x00\x06\x00\x00\x00\x01\x00\x60MTrk\x00\x00\x00\x02\x00\x40"),
+      Err(Error::InvalidEvent(23))
+    );
+  }
+}
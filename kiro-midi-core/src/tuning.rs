@@ -0,0 +1,262 @@
+//! MIDI Tuning Standard (MTS) messages sent as System Exclusive.
+//!
+//! Only the messages that change the tuning are supported:
+//! the single note tuning change (with and without bank) and the scale/octave tuning (1 and 2 bytes forms).
+
+use crate::types::{U14, U7};
+
+const NON_REAL_TIME: U7 = 0x7e;
+const REAL_TIME: U7 = 0x7f;
+const MIDI_TUNING: U7 = 0x08;
+
+const SINGLE_NOTE: U7 = 0x02;
+const SINGLE_NOTE_WITH_BANK: U7 = 0x07;
+const SCALE_OCTAVE_1_BYTE: U7 = 0x08;
+const SCALE_OCTAVE_2_BYTES: U7 = 0x09;
+
+/// The value of a frequency that means that it should not be changed
+const NO_CHANGE: (U7, U14) = (0x7f, 0x3fff);
+
+/// The frequency of a key expressed as the number of the MIDI note
+/// of the equal temperament just below it, plus a fraction of semitone in 1/16384 units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NoteFrequency {
+  pub semitone: U7,
+  pub fraction: U14,
+}
+
+impl NoteFrequency {
+  /// The number of semitones from the MIDI note 0, or None if the frequency should not change
+  pub fn semitones(&self) -> Option<f64> {
+    if (self.semitone, self.fraction) == NO_CHANGE {
+      None
+    } else {
+      Some(f64::from(self.semitone) + f64::from(self.fraction) / 16384.0)
+    }
+  }
+
+  /// The frequency in Hz, or None if the frequency should not change
+  pub fn frequency(&self) -> Option<f64> {
+    self
+      .semitones()
+      .map(|semitones| 440.0 * 2.0f64.powf((semitones - 69.0) / 12.0))
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NoteTuning {
+  pub key: U7,
+  pub frequency: NoteFrequency,
+}
+
+/// Iterator over the keys changed by a single note tuning change
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoteTunings<'a> {
+  data: &'a [U7],
+}
+
+impl<'a> Iterator for NoteTunings<'a> {
+  type Item = NoteTuning;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.data.len() < 4 {
+      return None;
+    }
+
+    let (entry, rest) = self.data.split_at(4);
+    self.data = rest;
+    Some(NoteTuning {
+      key: entry[0],
+      frequency: NoteFrequency {
+        semitone: entry[1],
+        fraction: (U14::from(entry[2]) << 7) | U14::from(entry[3]),
+      },
+    })
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TuningMessage<'a> {
+  /// Changes the frequency of some keys.
+  /// The bank is zero for the message without bank.
+  SingleNote {
+    realtime: bool,
+    device: U7,
+    bank: U7,
+    program: U7,
+    notes: NoteTunings<'a>,
+  },
+
+  /// Changes the twelve notes of every octave by an offset in cents from the equal temperament,
+  /// starting from C. The channels are a bitmap where the bit 0 is the first channel.
+  ScaleOctave {
+    realtime: bool,
+    device: U7,
+    channels: u16,
+    offsets: [f64; 12],
+  },
+}
+
+impl<'a> TuningMessage<'a> {
+  /// Decodes the data of a SysEx message (without the start and end bytes),
+  /// or returns None if it is not a supported MIDI tuning message.
+  pub fn decode(data: &'a [U7]) -> Option<Self> {
+    if data.len() < 4 || data[2] != MIDI_TUNING {
+      return None;
+    }
+
+    let realtime = match data[0] {
+      NON_REAL_TIME => false,
+      REAL_TIME => true,
+      _ => return None,
+    };
+    let device = data[1];
+
+    match data[3] {
+      SINGLE_NOTE if realtime => Self::single_note(realtime, device, 0, &data[4..]),
+      SINGLE_NOTE_WITH_BANK => {
+        let bank = *data.get(4)?;
+        Self::single_note(realtime, device, bank, &data[5..])
+      }
+      SCALE_OCTAVE_1_BYTE => {
+        let values = data.get(7..19)?;
+        let mut offsets = [0.0; 12];
+        for (offset, value) in offsets.iter_mut().zip(values) {
+          *offset = f64::from(*value) - 64.0;
+        }
+        Some(Self::scale_octave(realtime, device, &data[4..7], offsets))
+      }
+      SCALE_OCTAVE_2_BYTES => {
+        let values = data.get(7..31)?;
+        let mut offsets = [0.0; 12];
+        for (offset, value) in offsets.iter_mut().zip(values.chunks(2)) {
+          let value = (U14::from(value[0]) << 7) | U14::from(value[1]);
+          *offset = (f64::from(value) - 8192.0) * 100.0 / 8192.0;
+        }
+        Some(Self::scale_octave(realtime, device, &data[4..7], offsets))
+      }
+      _ => None,
+    }
+  }
+
+  fn single_note(realtime: bool, device: U7, bank: U7, data: &'a [U7]) -> Option<Self> {
+    let program = *data.first()?;
+    let count = usize::from(*data.get(1)?);
+    let entries = &data[2..];
+    let length = (count * 4).min(entries.len() - entries.len() % 4);
+    Some(TuningMessage::SingleNote {
+      realtime,
+      device,
+      bank,
+      program,
+      notes: NoteTunings {
+        data: &entries[..length],
+      },
+    })
+  }
+
+  fn scale_octave(realtime: bool, device: U7, channels: &[U7], offsets: [f64; 12]) -> Self {
+    let channels = (u16::from(channels[0] & 0x03) << 14)
+      | (u16::from(channels[1] & 0x7f) << 7)
+      | u16::from(channels[2] & 0x7f);
+    TuningMessage::ScaleOctave {
+      realtime,
+      device,
+      channels,
+      offsets,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::float_cmp)]
+
+  use super::*;
+
+  #[test]
+  fn decode_single_note() {
+    let data = [
+      0x7f, 0x7f, 0x08, 0x02, 0x00, 0x02, 69, 69, 0x00, 0x00, 60, 60, 0x40, 0x00, 0x7f,
+    ];
+    let message = TuningMessage::decode(&data).unwrap();
+    if let TuningMessage::SingleNote {
+      realtime, notes, ..
+    } = message
+    {
+      assert!(realtime);
+      let notes: Vec<NoteTuning> = notes.collect();
+      assert_eq!(notes.len(), 2);
+      assert_eq!(notes[0].key, 69);
+      assert_eq!(notes[0].frequency.frequency(), Some(440.0));
+      assert_eq!(notes[1].key, 60);
+      assert_eq!(notes[1].frequency.semitones(), Some(60.5));
+    } else {
+      panic!("unexpected message {:?}", message);
+    }
+  }
+
+  #[test]
+  fn decode_single_note_with_bank() {
+    let data = [
+      0x7e, 0x00, 0x08, 0x07, 0x03, 0x05, 0x01, 64, 0x7f, 0x7f, 0x7f,
+    ];
+    let message = TuningMessage::decode(&data).unwrap();
+    if let TuningMessage::SingleNote {
+      realtime,
+      bank,
+      program,
+      mut notes,
+      ..
+    } = message
+    {
+      assert!(!realtime);
+      assert_eq!(bank, 3);
+      assert_eq!(program, 5);
+      assert_eq!(notes.next().unwrap().frequency.frequency(), None);
+      assert_eq!(notes.next(), None);
+    } else {
+      panic!("unexpected message {:?}", message);
+    }
+  }
+
+  #[test]
+  fn decode_scale_octave() {
+    let mut data = vec![0x7e, 0x7f, 0x08, 0x08, 0x03, 0x7f, 0x7f];
+    data.extend_from_slice(&[64, 50, 64, 64, 78, 64, 64, 64, 64, 0, 64, 127]);
+    let message = TuningMessage::decode(&data).unwrap();
+    assert_eq!(
+      message,
+      TuningMessage::ScaleOctave {
+        realtime: false,
+        device: 0x7f,
+        channels: 0xffff,
+        offsets: [0.0, -14.0, 0.0, 0.0, 14.0, 0.0, 0.0, 0.0, 0.0, -64.0, 0.0, 63.0],
+      }
+    );
+
+    let mut data = vec![0x7f, 0x7f, 0x08, 0x09, 0x00, 0x00, 0x01];
+    for _ in 0..11 {
+      data.extend_from_slice(&[0x40, 0x00]);
+    }
+    data.extend_from_slice(&[0x00, 0x00]);
+    if let Some(TuningMessage::ScaleOctave {
+      channels, offsets, ..
+    }) = TuningMessage::decode(&data)
+    {
+      assert_eq!(channels, 1);
+      assert_eq!(offsets[0], 0.0);
+      assert_eq!(offsets[11], -100.0);
+    } else {
+      panic!("unexpected message");
+    }
+  }
+
+  #[test]
+  fn decode_other_sysex() {
+    assert_eq!(TuningMessage::decode(&[]), None);
+    assert_eq!(TuningMessage::decode(&[0x7e, 0x7f, 0x06, 0x01]), None);
+    assert_eq!(TuningMessage::decode(&[0x43, 0x10, 0x08, 0x02]), None);
+    assert_eq!(TuningMessage::decode(&[0x7e, 0x7f, 0x08, 0x08, 0x7f]), None);
+  }
+}
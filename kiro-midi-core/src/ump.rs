@@ -0,0 +1,1008 @@
+//! Universal MIDI Packets (UMP) from MIDI 2.0.
+//!
+//! Only the channel voice messages (for both MIDI 1.0 and MIDI 2.0) and the system messages are supported.
+//! The MIDI 2.0 messages can be converted to and from the MIDI 1.0 `Message`s,
+//! scaling the values as described by the MIDI 2.0 specification.
+
+use heapless::consts;
+use heapless::Vec;
+
+use crate::controllers::{ControllerMessage, ParameterChange};
+use crate::decoder::{Decoder, TUNE_REQUEST_CODE};
+use crate::encoder::Encoder;
+use crate::messages::Message;
+use crate::types::{U14, U4, U7};
+
+const MESSAGE_TYPE_SYSTEM: u8 = 0x1;
+const MESSAGE_TYPE_MIDI1_CHANNEL_VOICE: u8 = 0x2;
+const MESSAGE_TYPE_MIDI2_CHANNEL_VOICE: u8 = 0x4;
+
+const REGISTERED_PER_NOTE_CONTROLLER: u8 = 0x0;
+const ASSIGNABLE_PER_NOTE_CONTROLLER: u8 = 0x1;
+const REGISTERED_CONTROLLER: u8 = 0x2;
+const ASSIGNABLE_CONTROLLER: u8 = 0x3;
+const RELATIVE_REGISTERED_CONTROLLER: u8 = 0x4;
+const RELATIVE_ASSIGNABLE_CONTROLLER: u8 = 0x5;
+const PER_NOTE_PITCH_BEND: u8 = 0x6;
+const NOTE_OFF: u8 = 0x8;
+const NOTE_ON: u8 = 0x9;
+const POLY_PRESSURE: u8 = 0xa;
+const CONTROL_CHANGE: u8 = 0xb;
+const PROGRAM_CHANGE: u8 = 0xc;
+const CHANNEL_PRESSURE: u8 = 0xd;
+const PITCH_BEND: u8 = 0xe;
+const PER_NOTE_MANAGEMENT: u8 = 0xf;
+
+/// The velocity for the note on and off messages that do not have it
+pub const DEFAULT_VELOCITY: u16 = 0x8000;
+
+/// The value of the pitch bend without any change
+pub const PITCH_BEND_CENTER: u32 = 0x8000_0000;
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Error {
+  /// There are less words than the size of the packet
+  MissingData,
+
+  /// The message type is not supported.
+  /// The error includes the size of the packet in words, so it can be skipped.
+  UnsupportedMessageType { message_type: u8, size: usize },
+
+  /// The status of the message is not valid for its message type
+  InvalidStatus(u8),
+
+  /// The buffer does not have enough capacity for the packet.
+  /// The error includes the number of words required.
+  BufferOverflow(usize),
+
+  /// The message can not be encoded as an UMP (for example the SysEx)
+  UnsupportedMessage,
+}
+
+/// The MIDI 2.0 channel voice messages
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Midi2Message {
+  NoteOff {
+    channel: U4,
+    note: U7,
+    velocity: u16,
+    attribute_type: u8,
+    attribute: u16,
+  },
+
+  NoteOn {
+    channel: U4,
+    note: U7,
+    velocity: u16,
+    attribute_type: u8,
+    attribute: u16,
+  },
+
+  PolyPressure {
+    channel: U4,
+    note: U7,
+    value: u32,
+  },
+
+  RegisteredPerNoteController {
+    channel: U4,
+    note: U7,
+    index: u8,
+    value: u32,
+  },
+
+  AssignablePerNoteController {
+    channel: U4,
+    note: U7,
+    index: u8,
+    value: u32,
+  },
+
+  PerNoteManagement {
+    channel: U4,
+    note: U7,
+    detach: bool,
+    reset: bool,
+  },
+
+  ControlChange {
+    channel: U4,
+    index: U7,
+    value: u32,
+  },
+
+  /// Registered Parameter Number (RPN), with the bank and index as its MSB and LSB
+  RegisteredController {
+    channel: U4,
+    bank: U7,
+    index: U7,
+    value: u32,
+  },
+
+  /// Non-Registered Parameter Number (NRPN), with the bank and index as its MSB and LSB
+  AssignableController {
+    channel: U4,
+    bank: U7,
+    index: U7,
+    value: u32,
+  },
+
+  RelativeRegisteredController {
+    channel: U4,
+    bank: U7,
+    index: U7,
+    value: i32,
+  },
+
+  RelativeAssignableController {
+    channel: U4,
+    bank: U7,
+    index: U7,
+    value: i32,
+  },
+
+  ProgramChange {
+    channel: U4,
+    program: U7,
+    /// The bank as MSB and LSB, when the bank should change too
+    bank: Option<U14>,
+  },
+
+  ChannelPressure {
+    channel: U4,
+    value: u32,
+  },
+
+  /// The pitch bend, centered at `PITCH_BEND_CENTER`
+  PitchBend {
+    channel: U4,
+    value: u32,
+  },
+
+  /// The pitch bend of a single note, centered at `PITCH_BEND_CENTER`
+  PerNotePitchBend {
+    channel: U4,
+    note: U7,
+    value: u32,
+  },
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum UmpMessage {
+  /// A MIDI 1.0 channel voice or system message (message types 0x2 and 0x1)
+  Midi1 { group: U4, message: Message },
+
+  /// A MIDI 2.0 channel voice message (message type 0x4)
+  Midi2 { group: U4, message: Midi2Message },
+}
+
+/// The number of 32 bits words of a packet from its first word
+pub fn packet_size(word: u32) -> usize {
+  match word >> 28 {
+    0x0 | 0x1 | 0x2 | 0x6 | 0x7 => 1,
+    0x3 | 0x4 | 0x8 | 0x9 | 0xa => 2,
+    0xb | 0xc => 3,
+    _ => 4,
+  }
+}
+
+impl UmpMessage {
+  /// Decodes the packet at the beginning of the words, and returns the message and the size of the packet
+  pub fn decode(words: &[u32]) -> Result<(UmpMessage, usize)> {
+    let word = *words.first().ok_or(Error::MissingData)?;
+    let size = packet_size(word);
+    if words.len() < size {
+      return Err(Error::MissingData);
+    }
+
+    let message_type = (word >> 28) as u8;
+    let group = ((word >> 24) & 0x0f) as U4;
+    let status = (word >> 16) as u8;
+    let data1 = ((word >> 8) & 0x7f) as U7;
+    let data2 = (word & 0x7f) as U7;
+
+    let message = match message_type {
+      MESSAGE_TYPE_SYSTEM => {
+        let message = match status {
+          TUNE_REQUEST_CODE => Ok(Message::TuneRequest),
+          status if status >= 0xf8 => Decoder::decode_realtime_message(status),
+          status => Decoder::decode_system_common_message(status, &[data1, data2]),
+        };
+        let message = message.map_err(|_| Error::InvalidStatus(status))?;
+        UmpMessage::Midi1 { group, message }
+      }
+      MESSAGE_TYPE_MIDI1_CHANNEL_VOICE if (0x80..0xf0).contains(&status) => {
+        let message = Decoder::decode_channel_message(status, &[data1, data2])
+          .map_err(|_| Error::InvalidStatus(status))?;
+        UmpMessage::Midi1 { group, message }
+      }
+      MESSAGE_TYPE_MIDI2_CHANNEL_VOICE => UmpMessage::Midi2 {
+        group,
+        message: Midi2Message::decode(word, words[1])?,
+      },
+      MESSAGE_TYPE_MIDI1_CHANNEL_VOICE => return Err(Error::InvalidStatus(status)),
+      _ => return Err(Error::UnsupportedMessageType { message_type, size }),
+    };
+
+    Ok((message, size))
+  }
+
+  /// Encodes the message into the buffer and returns the number of words written
+  pub fn encode(&self, buffer: &mut [u32]) -> Result<usize> {
+    let (words, size) = match self {
+      UmpMessage::Midi1 { group, message } => {
+        let mut bytes = [0u8; 3];
+        let length = Encoder::new()
+          .encode(message, &mut bytes)
+          .map_err(|_| Error::UnsupportedMessage)?;
+        let message_type = if bytes[0] >= 0xf0 {
+          MESSAGE_TYPE_SYSTEM
+        } else {
+          MESSAGE_TYPE_MIDI1_CHANNEL_VOICE
+        };
+        let mut word = (u32::from(message_type) << 28) | (u32::from(group & 0x0f) << 24);
+        for (index, byte) in bytes[..length].iter().enumerate() {
+          word |= u32::from(*byte) << (16 - 8 * index);
+        }
+        ([word, 0], 1)
+      }
+      UmpMessage::Midi2 { group, message } => {
+        let (word0, word1) = message.encode();
+        ([word0 | (u32::from(group & 0x0f) << 24), word1], 2)
+      }
+    };
+
+    if buffer.len() < size {
+      return Err(Error::BufferOverflow(size));
+    }
+    buffer[..size].copy_from_slice(&words[..size]);
+    Ok(size)
+  }
+}
+
+impl Midi2Message {
+  fn decode(word0: u32, word1: u32) -> Result<Self> {
+    let opcode = ((word0 >> 20) & 0x0f) as u8;
+    let channel = ((word0 >> 16) & 0x0f) as U4;
+    let note = ((word0 >> 8) & 0x7f) as U7;
+    let byte3 = (word0 >> 8) as u8 & 0x7f;
+    let byte4 = word0 as u8;
+
+    let message = match opcode {
+      REGISTERED_PER_NOTE_CONTROLLER => Midi2Message::RegisteredPerNoteController {
+        channel,
+        note,
+        index: byte4,
+        value: word1,
+      },
+      ASSIGNABLE_PER_NOTE_CONTROLLER => Midi2Message::AssignablePerNoteController {
+        channel,
+        note,
+        index: byte4,
+        value: word1,
+      },
+      REGISTERED_CONTROLLER => Midi2Message::RegisteredController {
+        channel,
+        bank: byte3,
+        index: byte4 & 0x7f,
+        value: word1,
+      },
+      ASSIGNABLE_CONTROLLER => Midi2Message::AssignableController {
+        channel,
+        bank: byte3,
+        index: byte4 & 0x7f,
+        value: word1,
+      },
+      RELATIVE_REGISTERED_CONTROLLER => Midi2Message::RelativeRegisteredController {
+        channel,
+        bank: byte3,
+        index: byte4 & 0x7f,
+        value: word1 as i32,
+      },
+      RELATIVE_ASSIGNABLE_CONTROLLER => Midi2Message::RelativeAssignableController {
+        channel,
+        bank: byte3,
+        index: byte4 & 0x7f,
+        value: word1 as i32,
+      },
+      PER_NOTE_PITCH_BEND => Midi2Message::PerNotePitchBend {
+        channel,
+        note,
+        value: word1,
+      },
+      NOTE_OFF => Midi2Message::NoteOff {
+        channel,
+        note,
+        velocity: (word1 >> 16) as u16,
+        attribute_type: byte4,
+        attribute: word1 as u16,
+      },
+      NOTE_ON => Midi2Message::NoteOn {
+        channel,
+        note,
+        velocity: (word1 >> 16) as u16,
+        attribute_type: byte4,
+        attribute: word1 as u16,
+      },
+      POLY_PRESSURE => Midi2Message::PolyPressure {
+        channel,
+        note,
+        value: word1,
+      },
+      CONTROL_CHANGE => Midi2Message::ControlChange {
+        channel,
+        index: byte3,
+        value: word1,
+      },
+      PROGRAM_CHANGE => Midi2Message::ProgramChange {
+        channel,
+        program: ((word1 >> 24) & 0x7f) as U7,
+        bank: Some((((word1 >> 1) & 0x3f80) | (word1 & 0x7f)) as U14).filter(|_| byte4 & 0x01 != 0),
+      },
+      CHANNEL_PRESSURE => Midi2Message::ChannelPressure {
+        channel,
+        value: word1,
+      },
+      PITCH_BEND => Midi2Message::PitchBend {
+        channel,
+        value: word1,
+      },
+      PER_NOTE_MANAGEMENT => Midi2Message::PerNoteManagement {
+        channel,
+        note,
+        detach: byte4 & 0x02 != 0,
+        reset: byte4 & 0x01 != 0,
+      },
+      _ => return Err(Error::InvalidStatus((opcode << 4) | channel)),
+    };
+
+    Ok(message)
+  }
+
+  fn encode(&self) -> (u32, u32) {
+    let header = |opcode: u8, channel: U4, byte3: u8, byte4: u8| {
+      (u32::from(MESSAGE_TYPE_MIDI2_CHANNEL_VOICE) << 28)
+        | (u32::from(opcode & 0x0f) << 20)
+        | (u32::from(channel & 0x0f) << 16)
+        | (u32::from(byte3 & 0x7f) << 8)
+        | u32::from(byte4)
+    };
+
+    match *self {
+      Midi2Message::NoteOff {
+        channel,
+        note,
+        velocity,
+        attribute_type,
+        attribute,
+      } => (
+        header(NOTE_OFF, channel, note, attribute_type),
+        (u32::from(velocity) << 16) | u32::from(attribute),
+      ),
+      Midi2Message::NoteOn {
+        channel,
+        note,
+        velocity,
+        attribute_type,
+        attribute,
+      } => (
+        header(NOTE_ON, channel, note, attribute_type),
+        (u32::from(velocity) << 16) | u32::from(attribute),
+      ),
+      Midi2Message::PolyPressure {
+        channel,
+        note,
+        value,
+      } => (header(POLY_PRESSURE, channel, note, 0), value),
+      Midi2Message::RegisteredPerNoteController {
+        channel,
+        note,
+        index,
+        value,
+      } => (
+        header(REGISTERED_PER_NOTE_CONTROLLER, channel, note, index),
+        value,
+      ),
+      Midi2Message::AssignablePerNoteController {
+        channel,
+        note,
+        index,
+        value,
+      } => (
+        header(ASSIGNABLE_PER_NOTE_CONTROLLER, channel, note, index),
+        value,
+      ),
+      Midi2Message::PerNoteManagement {
+        channel,
+        note,
+        detach,
+        reset,
+      } => {
+        let flags = (u8::from(detach) << 1) | u8::from(reset);
+        (header(PER_NOTE_MANAGEMENT, channel, note, flags), 0)
+      }
+      Midi2Message::ControlChange {
+        channel,
+        index,
+        value,
+      } => (header(CONTROL_CHANGE, channel, index, 0), value),
+      Midi2Message::RegisteredController {
+        channel,
+        bank,
+        index,
+        value,
+      } => (
+        header(REGISTERED_CONTROLLER, channel, bank, index & 0x7f),
+        value,
+      ),
+      Midi2Message::AssignableController {
+        channel,
+        bank,
+        index,
+        value,
+      } => (
+        header(ASSIGNABLE_CONTROLLER, channel, bank, index & 0x7f),
+        value,
+      ),
+      Midi2Message::RelativeRegisteredController {
+        channel,
+        bank,
+        index,
+        value,
+      } => (
+        header(RELATIVE_REGISTERED_CONTROLLER, channel, bank, index & 0x7f),
+        value as u32,
+      ),
+      Midi2Message::RelativeAssignableController {
+        channel,
+        bank,
+        index,
+        value,
+      } => (
+        header(RELATIVE_ASSIGNABLE_CONTROLLER, channel, bank, index & 0x7f),
+        value as u32,
+      ),
+      Midi2Message::ProgramChange {
+        channel,
+        program,
+        bank,
+      } => {
+        let bank_valid = if bank.is_some() { 0x01 } else { 0x00 };
+        let bank = bank.map_or(0, |bank| {
+          ((u32::from(bank) & 0x3f80) << 1) | (u32::from(bank) & 0x7f)
+        });
+        (
+          header(PROGRAM_CHANGE, channel, 0, bank_valid),
+          (u32::from(program & 0x7f) << 24) | bank,
+        )
+      }
+      Midi2Message::ChannelPressure { channel, value } => {
+        (header(CHANNEL_PRESSURE, channel, 0, 0), value)
+      }
+      Midi2Message::PitchBend { channel, value } => (header(PITCH_BEND, channel, 0, 0), value),
+      Midi2Message::PerNotePitchBend {
+        channel,
+        note,
+        value,
+      } => (header(PER_NOTE_PITCH_BEND, channel, note, 0), value),
+    }
+  }
+
+  /// Converts a MIDI 1.0 channel voice message into MIDI 2.0.
+  ///
+  /// The controllers that select the parameters (RPN/NRPN) and the data entry are kept as control changes,
+  /// use `from_controller_message` with the messages from `Controllers` to convert them.
+  pub fn from_midi1(message: &Message) -> Option<Self> {
+    let control_change = |channel: U4, index: U7, value: U7| Midi2Message::ControlChange {
+      channel,
+      index,
+      value: scale_up(u32::from(value), 7, 32),
+    };
+
+    let message = match *message {
+      Message::NoteOff {
+        channel,
+        key,
+        velocity,
+      } => Midi2Message::NoteOff {
+        channel,
+        note: key,
+        velocity: scale_up(u32::from(velocity), 7, 16) as u16,
+        attribute_type: 0,
+        attribute: 0,
+      },
+      Message::NoteOn {
+        channel,
+        key,
+        velocity: 0,
+      } => Midi2Message::NoteOff {
+        channel,
+        note: key,
+        velocity: DEFAULT_VELOCITY,
+        attribute_type: 0,
+        attribute: 0,
+      },
+      Message::NoteOn {
+        channel,
+        key,
+        velocity,
+      } => Midi2Message::NoteOn {
+        channel,
+        note: key,
+        velocity: scale_up(u32::from(velocity), 7, 16) as u16,
+        attribute_type: 0,
+        attribute: 0,
+      },
+      Message::PolyphonicKeyPressure {
+        channel,
+        key,
+        value,
+      } => Midi2Message::PolyPressure {
+        channel,
+        note: key,
+        value: scale_up(u32::from(value), 7, 32),
+      },
+      Message::ControlChange {
+        channel,
+        controller,
+        value,
+      } => control_change(channel, controller, value),
+      Message::ProgramChange { channel, value } => Midi2Message::ProgramChange {
+        channel,
+        program: value,
+        bank: None,
+      },
+      Message::ChannelPressure { channel, value } => Midi2Message::ChannelPressure {
+        channel,
+        value: scale_up(u32::from(value), 7, 32),
+      },
+      Message::PitchBend { channel, value } => Midi2Message::PitchBend {
+        channel,
+        value: scale_up(u32::from(value), 14, 32),
+      },
+      Message::AllSoundOff { channel } => control_change(channel, 120, 0),
+      Message::ResetAllControllers { channel } => control_change(channel, 121, 0),
+      Message::LocalControlOff { channel } => control_change(channel, 122, 0),
+      Message::LocalControlOn { channel } => control_change(channel, 122, 127),
+      Message::AllNotesOff { channel } => control_change(channel, 123, 0),
+      Message::OmniModeOff { channel } => control_change(channel, 124, 0),
+      Message::OmniModeOn { channel } => control_change(channel, 125, 0),
+      Message::MonoModeOn {
+        channel,
+        num_channels,
+      } => control_change(channel, 126, num_channels),
+      Message::PolyModeOn { channel } => control_change(channel, 127, 0),
+      _ => return None,
+    };
+
+    Some(message)
+  }
+
+  /// Converts the parameter changes from `Controllers` into MIDI 2.0 controllers.
+  /// The increments and decrements are converted into relative controllers of one unit.
+  pub fn from_controller_message(message: &ControllerMessage) -> Option<Self> {
+    let (channel, parameter, change, registered) = match *message {
+      ControllerMessage::Rpn {
+        channel,
+        parameter,
+        change,
+      } => (channel, parameter, change, true),
+      ControllerMessage::Nrpn {
+        channel,
+        parameter,
+        change,
+      } => (channel, parameter, change, false),
+      ControllerMessage::Controller14 { .. } => return None,
+    };
+
+    let bank = ((parameter >> 7) & 0x7f) as U7;
+    let index = (parameter & 0x7f) as U7;
+    let message = match (change, registered) {
+      (ParameterChange::Value(value), true) => Midi2Message::RegisteredController {
+        channel,
+        bank,
+        index,
+        value: scale_up(u32::from(value), 14, 32),
+      },
+      (ParameterChange::Value(value), false) => Midi2Message::AssignableController {
+        channel,
+        bank,
+        index,
+        value: scale_up(u32::from(value), 14, 32),
+      },
+      (change, true) => Midi2Message::RelativeRegisteredController {
+        channel,
+        bank,
+        index,
+        value: Self::relative_change(change),
+      },
+      (change, false) => Midi2Message::RelativeAssignableController {
+        channel,
+        bank,
+        index,
+        value: Self::relative_change(change),
+      },
+    };
+
+    Some(message)
+  }
+
+  fn relative_change(change: ParameterChange) -> i32 {
+    match change {
+      ParameterChange::Decrement(_) => -1,
+      _ => 1,
+    }
+  }
+
+  /// Converts the message into the MIDI 1.0 messages that have the same effect.
+  ///
+  /// The messages without an equivalent in MIDI 1.0 (like the per-note and relative controllers)
+  /// return no messages.
+  pub fn to_midi1(&self) -> Vec<Message, consts::U4> {
+    let mut messages = Vec::new();
+    let control_change = |channel: U4, controller: U7, value: U7| Message::ControlChange {
+      channel,
+      controller,
+      value,
+    };
+
+    match *self {
+      Midi2Message::NoteOff {
+        channel,
+        note,
+        velocity,
+        ..
+      } => {
+        let velocity = scale_down(u32::from(velocity), 16, 7) as U7;
+        messages
+          .push(Message::NoteOff {
+            channel,
+            key: note,
+            velocity,
+          })
+          .ok();
+      }
+      Midi2Message::NoteOn {
+        channel,
+        note,
+        velocity,
+        ..
+      } => {
+        // the velocity 0 would be a note off in MIDI 1.0
+        let velocity = scale_down(u32::from(velocity), 16, 7).max(1) as U7;
+        messages
+          .push(Message::NoteOn {
+            channel,
+            key: note,
+            velocity,
+          })
+          .ok();
+      }
+      Midi2Message::PolyPressure {
+        channel,
+        note,
+        value,
+      } => {
+        messages
+          .push(Message::PolyphonicKeyPressure {
+            channel,
+            key: note,
+            value: scale_down(value, 32, 7) as U7,
+          })
+          .ok();
+      }
+      Midi2Message::ControlChange {
+        channel,
+        index,
+        value,
+      } => {
+        let status = 0xb0 | (channel & 0x0f);
+        let data = [index & 0x7f, scale_down(value, 32, 7) as U7];
+        if let Ok(message) = Decoder::decode_channel_message(status, &data) {
+          messages.push(message).ok();
+        }
+      }
+      Midi2Message::RegisteredController {
+        channel,
+        bank,
+        index,
+        value,
+      }
+      | Midi2Message::AssignableController {
+        channel,
+        bank,
+        index,
+        value,
+      } => {
+        let (msb, lsb) = match self {
+          Midi2Message::RegisteredController { .. } => (101, 100),
+          _ => (99, 98),
+        };
+        let value = scale_down(value, 32, 14);
+        messages.push(control_change(channel, msb, bank)).ok();
+        messages.push(control_change(channel, lsb, index)).ok();
+        messages
+          .push(control_change(channel, 6, ((value >> 7) & 0x7f) as U7))
+          .ok();
+        messages
+          .push(control_change(channel, 38, (value & 0x7f) as U7))
+          .ok();
+      }
+      Midi2Message::ProgramChange {
+        channel,
+        program,
+        bank,
+      } => {
+        if let Some(bank) = bank {
+          messages
+            .push(control_change(channel, 0, ((bank >> 7) & 0x7f) as U7))
+            .ok();
+          messages
+            .push(control_change(channel, 32, (bank & 0x7f) as U7))
+            .ok();
+        }
+        messages
+          .push(Message::ProgramChange {
+            channel,
+            value: program,
+          })
+          .ok();
+      }
+      Midi2Message::ChannelPressure { channel, value } => {
+        messages
+          .push(Message::ChannelPressure {
+            channel,
+            value: scale_down(value, 32, 7) as U7,
+          })
+          .ok();
+      }
+      Midi2Message::PitchBend { channel, value } => {
+        messages
+          .push(Message::PitchBend {
+            channel,
+            value: scale_down(value, 32, 14) as U14,
+          })
+          .ok();
+      }
+      Midi2Message::RegisteredPerNoteController { .. }
+      | Midi2Message::AssignablePerNoteController { .. }
+      | Midi2Message::PerNoteManagement { .. }
+      | Midi2Message::RelativeRegisteredController { .. }
+      | Midi2Message::RelativeAssignableController { .. }
+      | Midi2Message::PerNotePitchBend { .. } => {}
+    }
+
+    messages
+  }
+}
+
+/// Scales a value to more bits keeping the minimum, the center and the maximum (Min-Center-Max scaling)
+pub fn scale_up(value: u32, source_bits: u32, target_bits: u32) -> u32 {
+  let scale_bits = target_bits - source_bits;
+  let center = 1u64 << (source_bits - 1);
+  let value = u64::from(value) & ((1u64 << source_bits) - 1);
+  let scaled = value << scale_bits;
+  if value <= center {
+    return scaled as u32;
+  }
+
+  // repeat the bits below the most significant one to fill the lower bits up to the maximum
+  let repeat_bits = source_bits - 1;
+  let mut repeat = value & ((1u64 << repeat_bits) - 1);
+  repeat = if scale_bits > repeat_bits {
+    repeat << (scale_bits - repeat_bits)
+  } else {
+    repeat >> (repeat_bits - scale_bits)
+  };
+
+  let mut result = scaled;
+  while repeat != 0 {
+    result |= repeat;
+    repeat >>= repeat_bits;
+  }
+  result as u32
+}
+
+/// Scales a value to less bits
+pub fn scale_down(value: u32, source_bits: u32, target_bits: u32) -> u32 {
+  value >> (source_bits - target_bits)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn scale_values() {
+    assert_eq!(scale_up(0, 7, 16), 0);
+    assert_eq!(scale_up(64, 7, 16), 0x8000);
+    assert_eq!(scale_up(127, 7, 16), 0xffff);
+    assert_eq!(scale_up(127, 7, 32), 0xffff_ffff);
+    assert_eq!(scale_up(0x2000, 14, 32), PITCH_BEND_CENTER);
+    assert_eq!(scale_up(0x3fff, 14, 32), 0xffff_ffff);
+    assert_eq!(scale_down(0xffff_ffff, 32, 7), 127);
+    for value in 0..128 {
+      assert_eq!(scale_down(scale_up(value, 7, 16), 16, 7), value);
+    }
+  }
+
+  #[test]
+  fn decode_encode_midi1_packets() {
+    let words = [0x2391_3c64, 0x10f8_0000, 0x10f2_0102];
+    let (message, size) = UmpMessage::decode(&words).unwrap();
+    assert_eq!(size, 1);
+    assert_eq!(
+      message,
+      UmpMessage::Midi1 {
+        group: 3,
+        message: Message::NoteOn {
+          channel: 1,
+          key: 60,
+          velocity: 100,
+        },
+      }
+    );
+    let mut buffer = [0u32; 2];
+    assert_eq!(message.encode(&mut buffer), Ok(1));
+    assert_eq!(buffer[0], words[0]);
+
+    let (message, _) = UmpMessage::decode(&words[1..]).unwrap();
+    assert_eq!(
+      message,
+      UmpMessage::Midi1 {
+        group: 0,
+        message: Message::TimingClock,
+      }
+    );
+
+    let (message, _) = UmpMessage::decode(&words[2..]).unwrap();
+    assert_eq!(
+      message,
+      UmpMessage::Midi1 {
+        group: 0,
+        message: Message::SongPositionPointer { beats: 0x101 },
+      }
+    );
+    assert_eq!(message.encode(&mut buffer), Ok(1));
+    assert_eq!(buffer[0], words[2]);
+  }
+
+  #[test]
+  fn decode_encode_midi2_packets() {
+    let messages = [
+      Midi2Message::NoteOn {
+        channel: 2,
+        note: 60,
+        velocity: 0x1234,
+        attribute_type: 3,
+        attribute: 0x5678,
+      },
+      Midi2Message::ControlChange {
+        channel: 0,
+        index: 74,
+        value: 0x8765_4321,
+      },
+      Midi2Message::RegisteredController {
+        channel: 15,
+        bank: 0,
+        index: 0,
+        value: 0x0600_0000,
+      },
+      Midi2Message::RelativeAssignableController {
+        channel: 1,
+        bank: 2,
+        index: 3,
+        value: -5,
+      },
+      Midi2Message::ProgramChange {
+        channel: 4,
+        program: 10,
+        bank: Some(0x0102),
+      },
+      Midi2Message::PerNotePitchBend {
+        channel: 5,
+        note: 64,
+        value: PITCH_BEND_CENTER,
+      },
+      Midi2Message::PerNoteManagement {
+        channel: 6,
+        note: 1,
+        detach: true,
+        reset: false,
+      },
+    ];
+
+    for message in messages.iter() {
+      let ump = UmpMessage::Midi2 {
+        group: 7,
+        message: *message,
+      };
+      let mut buffer = [0u32; 2];
+      assert_eq!(ump.encode(&mut buffer), Ok(2));
+      assert_eq!(UmpMessage::decode(&buffer), Ok((ump, 2)));
+    }
+
+    assert_eq!(UmpMessage::decode(&[0x4090_3c00]), Err(Error::MissingData));
+    assert_eq!(
+      UmpMessage::decode(&[0x3000_0000, 0]),
+      Err(Error::UnsupportedMessageType {
+        message_type: 3,
+        size: 2,
+      })
+    );
+  }
+
+  #[test]
+  fn convert_midi1_and_midi2() {
+    let note_on = Message::NoteOn {
+      channel: 0,
+      key: 60,
+      velocity: 127,
+    };
+    let midi2 = Midi2Message::from_midi1(&note_on).unwrap();
+    assert_eq!(
+      midi2,
+      Midi2Message::NoteOn {
+        channel: 0,
+        note: 60,
+        velocity: 0xffff,
+        attribute_type: 0,
+        attribute: 0,
+      }
+    );
+    assert_eq!(&midi2.to_midi1()[..], &[note_on]);
+
+    let note_on_zero = Message::NoteOn {
+      channel: 0,
+      key: 60,
+      velocity: 0,
+    };
+    assert!(matches!(
+      Midi2Message::from_midi1(&note_on_zero),
+      Some(Midi2Message::NoteOff { .. })
+    ));
+
+    let all_notes_off = Message::AllNotesOff { channel: 3 };
+    let midi2 = Midi2Message::from_midi1(&all_notes_off).unwrap();
+    assert_eq!(&midi2.to_midi1()[..], &[all_notes_off]);
+
+    let pitch_bend = Message::PitchBend {
+      channel: 1,
+      value: 0x2000,
+    };
+    let midi2 = Midi2Message::from_midi1(&pitch_bend).unwrap();
+    assert_eq!(
+      midi2,
+      Midi2Message::PitchBend {
+        channel: 1,
+        value: PITCH_BEND_CENTER,
+      }
+    );
+    assert_eq!(&midi2.to_midi1()[..], &[pitch_bend]);
+
+    let rpn = ControllerMessage::Rpn {
+      channel: 0,
+      parameter: 0,
+      change: ParameterChange::Value(12 << 7),
+    };
+    let midi2 = Midi2Message::from_controller_message(&rpn).unwrap();
+    let cc = |controller, value| Message::ControlChange {
+      channel: 0,
+      controller,
+      value,
+    };
+    assert_eq!(
+      &midi2.to_midi1()[..],
+      &[cc(101, 0), cc(100, 0), cc(6, 12), cc(38, 0)]
+    );
+
+    let per_note = Midi2Message::PerNotePitchBend {
+      channel: 0,
+      note: 60,
+      value: 0,
+    };
+    assert!(per_note.to_midi1().is_empty());
+  }
+}
@@ -0,0 +1,166 @@
+use crate::effects::delay::InterpolatedDelayLine;
+use crate::float::Float;
+use crate::funcs::signal_polarity::bipolar_to_unipolar;
+use crate::oscillators::lfo::Lfo;
+use crate::oscillators::osc_waveform::OscWaveform;
+
+pub const MAX_VOICES: usize = 4;
+
+/// Maximum base delay in seconds
+pub const MAX_DELAY: f64 = 0.05;
+
+/// Maximum modulation of the delay in seconds for a depth of 1.0
+const MAX_DEPTH: f64 = 0.01;
+
+#[derive(Debug)]
+struct Voice<F: Float> {
+  lfo_left: Lfo<F>,
+  lfo_right: Lfo<F>,
+}
+
+/// Multi-voice stereo chorus. Every voice reads the delay lines at a different
+/// position modulated by its own pair of LFOs, that are spread across the cycle.
+///
+/// The memory for the delay lines is allocated on construction.
+#[derive(Debug)]
+pub struct Chorus<F: Float> {
+  sample_rate: F,
+  voices: [Voice<F>; MAX_VOICES],
+  num_voices: usize,
+  /// Base delay in seconds
+  delay: F,
+  /// Modulation of the delay. Values from 0.0 to 1.0
+  depth: F,
+  /// Phase offset between the left and right LFOs. Values from 0.0 to 1.0 (half a cycle)
+  spread: F,
+  /// The dry/wet proportion. Values from 0.0 (dry) to 1.0 (wet)
+  mix: F,
+  left: InterpolatedDelayLine<F>,
+  right: InterpolatedDelayLine<F>,
+}
+
+impl<F: Float> Chorus<F> {
+  pub fn new(sample_rate: F) -> Self {
+    let max_delay_samples = (F::val(MAX_DELAY + MAX_DEPTH) * sample_rate)
+      .ceil()
+      .to_usize()
+      .unwrap_or(1);
+
+    let voice = || Voice {
+      lfo_left: Lfo::new(sample_rate, 0),
+      lfo_right: Lfo::new(sample_rate, 1),
+    };
+
+    let mut chorus = Chorus {
+      sample_rate,
+      voices: [voice(), voice(), voice(), voice()],
+      num_voices: 3,
+      delay: F::val(0.015),
+      depth: F::val(0.5),
+      spread: F::val(0.5),
+      mix: F::val(0.5),
+      left: InterpolatedDelayLine::new(max_delay_samples),
+      right: InterpolatedDelayLine::new(max_delay_samples),
+    };
+    chorus.set_rate(F::val(0.8));
+    chorus.update_phases();
+    chorus
+  }
+
+  pub fn set_waveform(&mut self, waveform: OscWaveform<F>) {
+    for voice in self.voices.iter_mut() {
+      voice.lfo_left.set_waveform(waveform.clone());
+      voice.lfo_right.set_waveform(waveform.clone());
+    }
+  }
+
+  /// Rate of the LFOs in Hz
+  pub fn set_rate(&mut self, rate: F) {
+    for voice in self.voices.iter_mut() {
+      voice.lfo_left.set_rate(rate);
+      voice.lfo_right.set_rate(rate);
+    }
+  }
+
+  pub fn set_depth(&mut self, depth: F) {
+    self.depth = depth.max(F::zero()).min(F::one());
+  }
+
+  /// Base delay in seconds
+  pub fn set_delay(&mut self, seconds: F) {
+    self.delay = seconds.max(F::zero()).min(F::val(MAX_DELAY));
+  }
+
+  /// Number of voices between 1 and `MAX_VOICES`
+  pub fn set_voices(&mut self, voices: usize) {
+    self.num_voices = voices.max(1).min(MAX_VOICES);
+    self.update_phases();
+  }
+
+  pub fn set_spread(&mut self, spread: F) {
+    self.spread = spread.max(F::zero()).min(F::one());
+    self.update_phases();
+  }
+
+  pub fn set_mix(&mut self, mix: F) {
+    self.mix = mix.max(F::zero()).min(F::one());
+  }
+
+  pub fn reset(&mut self) {
+    for voice in self.voices.iter_mut() {
+      voice.lfo_left.reset();
+      voice.lfo_right.reset();
+    }
+    self.left.reset();
+    self.right.reset();
+  }
+
+  pub fn process(&mut self, left: F, right: F) -> (F, F) {
+    self.left.update(left);
+    self.right.update(right);
+
+    let base = self.delay * self.sample_rate;
+    let depth = self.depth * F::val(MAX_DEPTH) * self.sample_rate;
+
+    let mut wet_left = F::zero();
+    let mut wet_right = F::zero();
+    for voice in self.voices.iter_mut().take(self.num_voices) {
+      let mod_left = bipolar_to_unipolar(voice.lfo_left.generate());
+      let mod_right = bipolar_to_unipolar(voice.lfo_right.generate());
+      wet_left = wet_left + self.left.get(base + depth * mod_left);
+      wet_right = wet_right + self.right.get(base + depth * mod_right);
+    }
+
+    let gain = F::val(self.num_voices).recip();
+    let dry = F::one() - self.mix;
+    let wet = self.mix * gain;
+    (left * dry + wet_left * wet, right * dry + wet_right * wet)
+  }
+
+  fn update_phases(&mut self) {
+    let num_voices = F::val(self.num_voices);
+    let offset = self.spread * F::val(0.5);
+    for (index, voice) in self.voices.iter_mut().enumerate() {
+      let phase = F::val(index) / num_voices;
+      voice.lfo_left.set_phase(phase.fract());
+      voice.lfo_right.set_phase((phase + offset).fract());
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use assert_approx_eq::assert_approx_eq;
+
+  #[test]
+  fn chorus_dry() {
+    let mut chorus = Chorus::<f64>::new(44100.0);
+    chorus.set_mix(0.0);
+    for _ in 0..1000 {
+      let (left, right) = chorus.process(0.5, -0.25);
+      assert_approx_eq!(left, 0.5);
+      assert_approx_eq!(right, -0.25);
+    }
+  }
+}
@@ -0,0 +1,148 @@
+use crate::float::Float;
+use crate::funcs::decibels::Decibels;
+
+/// Level used instead of silence to avoid infinite values in dB
+const MIN_LEVEL_DB: f64 = -120.0;
+
+/// Feed-forward stereo-linked compressor with a soft knee.
+///
+/// The gain reduction is computed in dB from the peak of both channels,
+/// and smoothed with different attack and release times.
+#[derive(Debug, Clone)]
+pub struct Compressor<F: Float> {
+  sample_rate: F,
+  /// Level in dB above which the gain is reduced
+  threshold: F,
+  /// Input/output ratio above the threshold. Values from 1.0 (no compression)
+  ratio: F,
+  /// Width in dB of the transition around the threshold
+  knee: F,
+  attack_coeff: F,
+  release_coeff: F,
+  /// Gain applied after the compression
+  makeup_gain: F,
+  /// Current gain reduction in dB (zero or negative)
+  reduction: F,
+}
+
+impl<F: Float> Compressor<F> {
+  pub fn new(sample_rate: F) -> Self {
+    let mut compressor = Compressor {
+      sample_rate,
+      threshold: F::zero(),
+      ratio: F::val(4.0),
+      knee: F::val(6.0),
+      attack_coeff: F::zero(),
+      release_coeff: F::zero(),
+      makeup_gain: F::one(),
+      reduction: F::zero(),
+    };
+    compressor.set_attack(F::val(0.01));
+    compressor.set_release(F::val(0.1));
+    compressor
+  }
+
+  /// Threshold in dB
+  pub fn set_threshold(&mut self, threshold: F) {
+    self.threshold = threshold;
+  }
+
+  pub fn set_ratio(&mut self, ratio: F) {
+    self.ratio = ratio.max(F::one());
+  }
+
+  /// Attack time in seconds
+  pub fn set_attack(&mut self, attack: F) {
+    self.attack_coeff = Self::coeff(self.sample_rate, attack);
+  }
+
+  /// Release time in seconds
+  pub fn set_release(&mut self, release: F) {
+    self.release_coeff = Self::coeff(self.sample_rate, release);
+  }
+
+  /// Knee width in dB
+  pub fn set_knee(&mut self, knee: F) {
+    self.knee = knee.max(F::zero());
+  }
+
+  /// Makeup gain in dB
+  pub fn set_makeup(&mut self, makeup: F) {
+    self.makeup_gain = Decibels::new(makeup).to_amplitude();
+  }
+
+  /// The current gain reduction in dB (zero or negative)
+  pub fn get_gain_reduction(&self) -> F {
+    self.reduction
+  }
+
+  pub fn reset(&mut self) {
+    self.reduction = F::zero();
+  }
+
+  pub fn process(&mut self, left: F, right: F) -> (F, F) {
+    let peak = left.abs().max(right.abs());
+    let level = Decibels::from_amplitude(peak)
+      .value()
+      .max(F::val(MIN_LEVEL_DB));
+
+    let target = self.gain_computer(level) - level;
+    let coeff = if target < self.reduction {
+      self.attack_coeff
+    } else {
+      self.release_coeff
+    };
+    self.reduction = target + (self.reduction - target) * coeff;
+
+    let gain = Decibels::new(self.reduction).to_amplitude() * self.makeup_gain;
+    (left * gain, right * gain)
+  }
+
+  /// The output level in dB for a given input level in dB
+  fn gain_computer(&self, level: F) -> F {
+    let two = F::val(2.0);
+    let overshoot = level - self.threshold;
+    let slope = self.ratio.recip() - F::one();
+
+    if two * overshoot < self.knee.neg() {
+      level
+    } else if two * overshoot.abs() <= self.knee && self.knee > F::zero() {
+      let knee_overshoot = overshoot + self.knee / two;
+      level + slope * knee_overshoot * knee_overshoot / (two * self.knee)
+    } else {
+      self.threshold + overshoot / self.ratio
+    }
+  }
+
+  fn coeff(sample_rate: F, time: F) -> F {
+    let samples = time * sample_rate;
+    if samples > F::zero() {
+      (F::one().neg() / samples).exp()
+    } else {
+      F::zero()
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use assert_approx_eq::assert_approx_eq;
+
+  #[test]
+  fn compressor_gain_computer() {
+    let mut compressor = Compressor::<f64>::new(44100.0);
+    compressor.set_threshold(-20.0);
+    compressor.set_ratio(4.0);
+    compressor.set_knee(0.0);
+
+    assert_approx_eq!(compressor.gain_computer(-30.0), -30.0);
+    assert_approx_eq!(compressor.gain_computer(-20.0), -20.0);
+    assert_approx_eq!(compressor.gain_computer(0.0), -15.0);
+
+    compressor.set_knee(10.0);
+    assert_approx_eq!(compressor.gain_computer(-30.0), -30.0);
+    assert_approx_eq!(compressor.gain_computer(-20.0), -20.9375);
+    assert_approx_eq!(compressor.gain_computer(0.0), -15.0);
+  }
+}
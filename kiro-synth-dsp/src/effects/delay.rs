@@ -1,4 +1,5 @@
 use crate::float::Float;
+use crate::funcs::interpolation::linear_interpolation;
 
 struct DelayLine<'a, F: Float> {
   head: usize,
@@ -85,6 +86,63 @@ impl<'a, F: Float> Delay<'a, F> {
   }
 }
 
+/// Delay line that owns its buffer and supports fractional delays using linear interpolation.
+/// The buffer is allocated on construction and never reallocated.
+#[derive(Debug, Clone)]
+pub struct InterpolatedDelayLine<F: Float> {
+  head: usize,
+  buffer: Vec<F>,
+}
+
+impl<F: Float> InterpolatedDelayLine<F> {
+  pub fn new(max_delay_samples: usize) -> Self {
+    Self {
+      head: 0,
+      buffer: vec![F::zero(); max_delay_samples.max(1) + 1],
+    }
+  }
+
+  /// The maximum delay in samples that can be read
+  pub fn max_delay(&self) -> usize {
+    self.buffer.len() - 1
+  }
+
+  pub fn reset(&mut self) {
+    for sample in self.buffer.iter_mut() {
+      *sample = F::zero();
+    }
+    self.head = 0;
+  }
+
+  pub fn update(&mut self, input: F) {
+    self.buffer[self.head] = input;
+    self.head = (self.head + 1) % self.buffer.len();
+  }
+
+  /// Read the sample at a fractional delay between 1 and `max_delay`.
+  /// A delay of 1 returns the last sample written.
+  pub fn get(&self, delay_samples: F) -> F {
+    let max_delay = F::val(self.max_delay());
+    let delay = delay_samples.max(F::one()).min(max_delay);
+    let offset = delay.floor();
+    let fraction = delay - offset;
+    let offset = offset.to_usize().unwrap_or(1);
+
+    let y1 = self.get_sample(offset);
+    if fraction > F::zero() {
+      let y2 = self.get_sample(offset + 1);
+      linear_interpolation(F::zero(), F::one(), y1, y2, fraction)
+    } else {
+      y1
+    }
+  }
+
+  fn get_sample(&self, offset: usize) -> F {
+    let len = self.buffer.len();
+    self.buffer[(self.head + len - offset.min(len)) % len]
+  }
+}
+
 #[cfg(test)]
 mod test {
   use super::*;
@@ -127,4 +185,20 @@ mod test {
         assert_approx_eq!(a, b);
       });
   }
+
+  #[test]
+  fn interpolated_delayline_get() {
+    let mut delayline = InterpolatedDelayLine::<f64>::new(4);
+    for sample in [1.0, 2.0, 3.0, 4.0, 5.0].iter() {
+      delayline.update(*sample);
+    }
+
+    assert_approx_eq!(delayline.get(1.0), 5.0f64);
+    assert_approx_eq!(delayline.get(2.0), 4.0f64);
+    assert_approx_eq!(delayline.get(1.5), 4.5f64);
+    assert_approx_eq!(delayline.get(3.25), 2.75f64);
+    assert_approx_eq!(delayline.get(4.0), 2.0f64);
+    assert_approx_eq!(delayline.get(6.0), 2.0f64);
+    assert_approx_eq!(delayline.get(0.0), 5.0f64);
+  }
 }
@@ -0,0 +1,111 @@
+use crate::filters::biquad::{Biquad, Mode};
+use crate::float::Float;
+
+pub const NUM_BANDS: usize = 4;
+
+/// The filter used by every band, from low to high
+const BAND_MODES: [Mode; NUM_BANDS] = [
+  Mode::LowShelf,
+  Mode::Peaking,
+  Mode::Peaking,
+  Mode::HighShelf,
+];
+
+/// The initial frequency of every band in Hz
+pub const BAND_FREQUENCIES: [f64; NUM_BANDS] = [100.0, 500.0, 2000.0, 8000.0];
+
+#[derive(Debug, Clone)]
+struct Band<F: Float> {
+  left: Biquad<F>,
+  right: Biquad<F>,
+}
+
+/// Stereo parametric equalizer with a low shelf, two peaking bands and a high shelf.
+///
+/// All the bands start flat (0 dB), so it does not change the signal until a gain is set.
+#[derive(Debug, Clone)]
+pub struct Equalizer<F: Float> {
+  bands: [Band<F>; NUM_BANDS],
+}
+
+impl<F: Float> Equalizer<F> {
+  pub fn new(sample_rate: F) -> Self {
+    let q = F::val(0.5).sqrt();
+    let band = |index: usize| {
+      let frequency = F::val(BAND_FREQUENCIES[index]);
+      let filter = Biquad::new(sample_rate, BAND_MODES[index], frequency, q);
+      Band {
+        left: filter.clone(),
+        right: filter,
+      }
+    };
+
+    Equalizer {
+      bands: [band(0), band(1), band(2), band(3)],
+    }
+  }
+
+  /// Cutoff frequency for the shelves or center frequency for the peaks, in Hz
+  pub fn set_frequency(&mut self, band: usize, frequency: F) {
+    if let Some(band) = self.bands.get_mut(band) {
+      band.left.set_frequency(frequency);
+      band.right.set_frequency(frequency);
+    }
+  }
+
+  /// Gain in dB
+  pub fn set_gain(&mut self, band: usize, gain: F) {
+    if let Some(band) = self.bands.get_mut(band) {
+      band.left.set_gain(gain);
+      band.right.set_gain(gain);
+    }
+  }
+
+  pub fn set_q(&mut self, band: usize, q: F) {
+    if let Some(band) = self.bands.get_mut(band) {
+      band.left.set_q(q);
+      band.right.set_q(q);
+    }
+  }
+
+  /// The magnitude of the frequency response of all the bands at the given frequency in Hz
+  pub fn magnitude(&self, frequency: F) -> F {
+    self.bands.iter().fold(F::one(), |magnitude, band| {
+      magnitude * band.left.magnitude(frequency)
+    })
+  }
+
+  pub fn reset(&mut self) {
+    for band in self.bands.iter_mut() {
+      band.left.reset();
+      band.right.reset();
+    }
+  }
+
+  pub fn process(&mut self, left: F, right: F) -> (F, F) {
+    self
+      .bands
+      .iter_mut()
+      .fold((left, right), |(left, right), band| {
+        (band.left.process(left), band.right.process(right))
+      })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use assert_approx_eq::assert_approx_eq;
+
+  #[test]
+  fn equalizer_flat_by_default() {
+    let mut equalizer = Equalizer::<f64>::new(44100.0);
+    for frequency in [20.0, 100.0, 1000.0, 10000.0, 20000.0].iter() {
+      assert_approx_eq!(equalizer.magnitude(*frequency), 1.0);
+    }
+
+    let (left, right) = equalizer.process(0.5, -0.25);
+    assert_approx_eq!(left, 0.5);
+    assert_approx_eq!(right, -0.25);
+  }
+}
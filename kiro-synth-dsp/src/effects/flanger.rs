@@ -0,0 +1,138 @@
+use crate::effects::delay::InterpolatedDelayLine;
+use crate::float::Float;
+use crate::funcs::signal_polarity::bipolar_to_unipolar;
+use crate::oscillators::lfo::Lfo;
+use crate::oscillators::osc_waveform::OscWaveform;
+
+/// Maximum base delay in seconds
+pub const MAX_DELAY: f64 = 0.01;
+
+/// Maximum sweep of the delay in seconds for a depth of 1.0
+const MAX_DEPTH: f64 = 0.005;
+
+/// Limit for the feedback to keep it stable
+const MAX_FEEDBACK: f64 = 0.95;
+
+#[derive(Debug)]
+struct Channel<F: Float> {
+  lfo: Lfo<F>,
+  delayline: InterpolatedDelayLine<F>,
+  last_output: F,
+}
+
+impl<F: Float> Channel<F> {
+  fn new(sample_rate: F, max_delay_samples: usize) -> Self {
+    Channel {
+      lfo: Lfo::new(sample_rate, 0),
+      delayline: InterpolatedDelayLine::new(max_delay_samples),
+      last_output: F::zero(),
+    }
+  }
+
+  fn reset(&mut self) {
+    self.lfo.reset();
+    self.delayline.reset();
+    self.last_output = F::zero();
+  }
+
+  fn process(&mut self, input: F, base: F, depth: F, feedback: F) -> F {
+    self.delayline.update(input + self.last_output * feedback);
+    let modulation = bipolar_to_unipolar(self.lfo.generate());
+    self.last_output = self.delayline.get(base + depth * modulation);
+    self.last_output
+  }
+}
+
+/// Stereo flanger with feedback. A short delay line per channel is swept by an LFO,
+/// and the right LFO can be offset in phase from the left one.
+///
+/// The memory for the delay lines is allocated on construction.
+#[derive(Debug)]
+pub struct Flanger<F: Float> {
+  sample_rate: F,
+  /// Base delay in seconds
+  delay: F,
+  /// Sweep of the delay. Values from 0.0 to 1.0
+  depth: F,
+  /// Values from -0.95 to 0.95. Negative values emphasise the odd harmonics
+  feedback: F,
+  /// The dry/wet proportion. Values from 0.0 (dry) to 1.0 (wet)
+  mix: F,
+  left: Channel<F>,
+  right: Channel<F>,
+}
+
+impl<F: Float> Flanger<F> {
+  pub fn new(sample_rate: F) -> Self {
+    let max_delay_samples = (F::val(MAX_DELAY + MAX_DEPTH) * sample_rate)
+      .ceil()
+      .to_usize()
+      .unwrap_or(1);
+
+    let mut flanger = Flanger {
+      sample_rate,
+      delay: F::val(0.001),
+      depth: F::val(0.5),
+      feedback: F::val(0.5),
+      mix: F::val(0.5),
+      left: Channel::new(sample_rate, max_delay_samples),
+      right: Channel::new(sample_rate, max_delay_samples),
+    };
+    flanger.set_rate(F::val(0.2));
+    flanger
+  }
+
+  pub fn set_waveform(&mut self, waveform: OscWaveform<F>) {
+    self.left.lfo.set_waveform(waveform.clone());
+    self.right.lfo.set_waveform(waveform);
+  }
+
+  /// Rate of the LFOs in Hz
+  pub fn set_rate(&mut self, rate: F) {
+    self.left.lfo.set_rate(rate);
+    self.right.lfo.set_rate(rate);
+  }
+
+  pub fn set_depth(&mut self, depth: F) {
+    self.depth = depth.max(F::zero()).min(F::one());
+  }
+
+  /// Base delay in seconds
+  pub fn set_delay(&mut self, seconds: F) {
+    self.delay = seconds.max(F::zero()).min(F::val(MAX_DELAY));
+  }
+
+  pub fn set_feedback(&mut self, feedback: F) {
+    let max_feedback = F::val(MAX_FEEDBACK);
+    self.feedback = feedback.max(max_feedback.neg()).min(max_feedback);
+  }
+
+  /// Phase offset between the left and right LFOs. Values from 0.0 to 1.0 (half a cycle)
+  pub fn set_spread(&mut self, spread: F) {
+    let spread = spread.max(F::zero()).min(F::one());
+    self.right.lfo.set_phase(spread * F::val(0.5));
+  }
+
+  pub fn set_mix(&mut self, mix: F) {
+    self.mix = mix.max(F::zero()).min(F::one());
+  }
+
+  pub fn reset(&mut self) {
+    self.left.reset();
+    self.right.reset();
+  }
+
+  pub fn process(&mut self, left: F, right: F) -> (F, F) {
+    let base = self.delay * self.sample_rate;
+    let depth = self.depth * F::val(MAX_DEPTH) * self.sample_rate;
+
+    let wet_left = self.left.process(left, base, depth, self.feedback);
+    let wet_right = self.right.process(right, base, depth, self.feedback);
+
+    let dry = F::one() - self.mix;
+    (
+      left * dry + wet_left * self.mix,
+      right * dry + wet_right * self.mix,
+    )
+  }
+}
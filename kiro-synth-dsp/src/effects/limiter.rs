@@ -0,0 +1,215 @@
+use crate::float::Float;
+use crate::funcs::decibels::Decibels;
+
+/// Stereo-linked look-ahead peak limiter.
+///
+/// The signal is delayed by the look-ahead time while the gain needed to keep every sample
+/// under the ceiling is held at its minimum for the same time, and then smoothed with a moving
+/// average of the same length. This way the gain has reached its target when the peak comes out,
+/// so the output never goes over the ceiling.
+///
+/// The memory for the buffers is allocated on construction.
+#[derive(Debug, Clone)]
+pub struct Limiter<F: Float> {
+  sample_rate: F,
+  /// Maximum output amplitude
+  ceiling: F,
+  release_coeff: F,
+  envelope: F,
+  head: usize,
+  delay_left: Vec<F>,
+  delay_right: Vec<F>,
+  gains: MovingMin<F>,
+  envelopes: Vec<F>,
+  envelopes_sum: F,
+}
+
+impl<F: Float> Limiter<F> {
+  pub fn new(sample_rate: F, lookahead_seconds: F) -> Self {
+    let lookahead = (lookahead_seconds * sample_rate)
+      .round()
+      .to_usize()
+      .unwrap_or(1)
+      .max(1)
+      + 1;
+
+    let mut limiter = Limiter {
+      sample_rate,
+      ceiling: F::one(),
+      release_coeff: F::zero(),
+      envelope: F::one(),
+      head: 0,
+      delay_left: vec![F::zero(); lookahead],
+      delay_right: vec![F::zero(); lookahead],
+      gains: MovingMin::new(lookahead),
+      envelopes: vec![F::one(); lookahead],
+      envelopes_sum: F::val(lookahead),
+    };
+    limiter.set_release(F::val(0.05));
+    limiter
+  }
+
+  /// The latency introduced in samples
+  pub fn latency(&self) -> usize {
+    self.delay_left.len() - 1
+  }
+
+  /// Ceiling in dB
+  pub fn set_ceiling(&mut self, ceiling: F) {
+    self.ceiling = Decibels::new(ceiling.min(F::zero())).to_amplitude();
+  }
+
+  /// Release time in seconds
+  pub fn set_release(&mut self, release: F) {
+    let samples = release * self.sample_rate;
+    self.release_coeff = if samples > F::zero() {
+      (F::one().neg() / samples).exp()
+    } else {
+      F::zero()
+    };
+  }
+
+  /// The current gain reduction in dB (zero or negative)
+  pub fn get_gain_reduction(&self) -> F {
+    Decibels::from_amplitude(self.envelope).value()
+  }
+
+  pub fn reset(&mut self) {
+    self.envelope = F::one();
+    self.head = 0;
+    for index in 0..self.envelopes.len() {
+      self.delay_left[index] = F::zero();
+      self.delay_right[index] = F::zero();
+      self.envelopes[index] = F::one();
+    }
+    self.envelopes_sum = F::val(self.envelopes.len());
+    self.gains.reset();
+  }
+
+  pub fn process(&mut self, left: F, right: F) -> (F, F) {
+    let peak = left.abs().max(right.abs());
+    let gain = if peak > self.ceiling {
+      self.ceiling / peak
+    } else {
+      F::one()
+    };
+
+    let head = self.head;
+    let length = self.envelopes.len();
+    self.delay_left[head] = left;
+    self.delay_right[head] = right;
+    self.head = (head + 1) % length;
+
+    // the oldest sample is the only one covered by all the windows being averaged
+    let delayed_left = self.delay_left[self.head];
+    let delayed_right = self.delay_right[self.head];
+
+    // minimum gain needed along the look-ahead window
+    let held = self.gains.push(gain);
+
+    self.envelope = if held < self.envelope {
+      held
+    } else {
+      held + (self.envelope - held) * self.release_coeff
+    };
+
+    self.envelopes_sum = self.envelopes_sum - self.envelopes[head] + self.envelope;
+    self.envelopes[head] = self.envelope;
+    if self.head == 0 {
+      // avoid accumulating rounding errors in the running sum
+      self.envelopes_sum = self
+        .envelopes
+        .iter()
+        .fold(F::zero(), |sum, envelope| sum + *envelope);
+    }
+
+    let smoothed = self.envelopes_sum / F::val(length);
+
+    (delayed_left * smoothed, delayed_right * smoothed)
+  }
+}
+
+/// Minimum of the last values pushed, using a monotonic queue of the candidates to be the minimum.
+#[derive(Debug, Clone)]
+struct MovingMin<F: Float> {
+  /// Ring buffer with the position and the value of the candidates, in increasing order of value
+  candidates: Vec<(usize, F)>,
+  front: usize,
+  len: usize,
+  position: usize,
+}
+
+impl<F: Float> MovingMin<F> {
+  fn new(length: usize) -> Self {
+    MovingMin {
+      candidates: vec![(0, F::one()); length],
+      front: 0,
+      len: 0,
+      position: 0,
+    }
+  }
+
+  fn reset(&mut self) {
+    self.front = 0;
+    self.len = 0;
+    self.position = 0;
+  }
+
+  /// Adds a value and returns the minimum of the window, which includes it
+  fn push(&mut self, value: F) -> F {
+    let capacity = self.candidates.len();
+    let position = self.position;
+    self.position = position.wrapping_add(1);
+
+    // the oldest candidate leaves the window to make room for the new value
+    if self.len > 0 && position.wrapping_sub(self.candidates[self.front].0) >= capacity {
+      self.front = (self.front + 1) % capacity;
+      self.len -= 1;
+    }
+
+    // the candidates not lower than the new value will never be the minimum again
+    while self.len > 0 && self.candidates[(self.front + self.len - 1) % capacity].1 >= value {
+      self.len -= 1;
+    }
+    self.candidates[(self.front + self.len) % capacity] = (position, value);
+    self.len += 1;
+
+    self.candidates[self.front].1
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn limiter_keeps_the_ceiling() {
+    let mut limiter = Limiter::<f64>::new(44100.0, 0.005);
+    limiter.set_ceiling(-6.0);
+    let ceiling = Decibels::new(-6.0).to_amplitude();
+
+    for index in 0..44100 {
+      let input = (index as f64 * 0.05).sin() * if index % 3000 < 100 { 4.0 } else { 0.25 };
+      let (left, right) = limiter.process(input, -input);
+      assert!(left.abs() <= ceiling + 1e-9);
+      assert!(right.abs() <= ceiling + 1e-9);
+    }
+  }
+
+  #[test]
+  fn moving_min_of_the_window() {
+    let length = 5;
+    let mut moving_min = MovingMin::<f64>::new(length);
+    let values: Vec<f64> = (0..200)
+      .map(|index| ((index * 7919) % 113) as f64 / 113.0)
+      .collect();
+
+    for (index, value) in values.iter().enumerate() {
+      let start = (index + 1).saturating_sub(length);
+      let expected = values[start..=index]
+        .iter()
+        .fold(1.0f64, |min, value| min.min(*value));
+      assert_eq!(moving_min.push(*value).to_bits(), expected.to_bits());
+    }
+  }
+}
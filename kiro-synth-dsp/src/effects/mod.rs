@@ -1 +1,10 @@
+pub mod chorus;
+pub mod compressor;
 pub mod delay;
+pub mod equalizer;
+pub mod flanger;
+pub mod limiter;
+pub mod phaser;
+pub mod reverb;
+pub mod stereo_delay;
+pub mod waveshaper;
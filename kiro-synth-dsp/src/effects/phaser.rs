@@ -0,0 +1,195 @@
+use crate::float::Float;
+use crate::funcs::signal_polarity::bipolar_to_unipolar;
+use crate::oscillators::lfo::Lfo;
+use crate::oscillators::osc_waveform::OscWaveform;
+
+pub const MAX_STAGES: usize = 12;
+
+/// Limit for the feedback to keep it stable
+const MAX_FEEDBACK: f64 = 0.95;
+
+/// First order all-pass filter
+#[derive(Debug, Clone, Copy, Default)]
+struct AllpassStage<F: Float> {
+  x1: F,
+  y1: F,
+}
+
+impl<F: Float> AllpassStage<F> {
+  fn reset(&mut self) {
+    self.x1 = F::zero();
+    self.y1 = F::zero();
+  }
+
+  fn process(&mut self, input: F, coeff: F) -> F {
+    let output = coeff * input + self.x1 - coeff * self.y1;
+    self.x1 = input;
+    self.y1 = output;
+    output
+  }
+}
+
+#[derive(Debug)]
+struct Channel<F: Float> {
+  lfo: Lfo<F>,
+  stages: [AllpassStage<F>; MAX_STAGES],
+  last_output: F,
+}
+
+impl<F: Float> Channel<F> {
+  fn new(sample_rate: F) -> Self {
+    Channel {
+      lfo: Lfo::new(sample_rate, 0),
+      stages: [AllpassStage::default(); MAX_STAGES],
+      last_output: F::zero(),
+    }
+  }
+
+  fn reset(&mut self) {
+    self.lfo.reset();
+    self.stages.iter_mut().for_each(AllpassStage::reset);
+    self.last_output = F::zero();
+  }
+
+  fn process(&mut self, input: F, num_stages: usize, coeff: F, feedback: F) -> F {
+    let mut output = input + self.last_output * feedback;
+    for stage in self.stages.iter_mut().take(num_stages) {
+      output = stage.process(output, coeff);
+    }
+    self.last_output = output;
+    output
+  }
+}
+
+/// Stereo phaser made of a chain of first order all-pass stages per channel,
+/// with their break frequency swept exponentially by an LFO.
+/// Mixing the output with the dry signal creates a notch for every two stages.
+#[derive(Debug)]
+pub struct Phaser<F: Float> {
+  sample_rate: F,
+  num_stages: usize,
+  /// Lowest frequency of the sweep in Hz
+  min_frequency: F,
+  /// Highest frequency of the sweep in Hz
+  max_frequency: F,
+  /// Proportion of the sweep range used. Values from 0.0 to 1.0
+  depth: F,
+  /// Values from -0.95 to 0.95
+  feedback: F,
+  /// The dry/wet proportion. Values from 0.0 (dry) to 1.0 (wet)
+  mix: F,
+  left: Channel<F>,
+  right: Channel<F>,
+}
+
+impl<F: Float> Phaser<F> {
+  pub fn new(sample_rate: F) -> Self {
+    let mut phaser = Phaser {
+      sample_rate,
+      num_stages: 6,
+      min_frequency: F::val(200.0),
+      max_frequency: F::val(4000.0),
+      depth: F::one(),
+      feedback: F::zero(),
+      mix: F::val(0.5),
+      left: Channel::new(sample_rate),
+      right: Channel::new(sample_rate),
+    };
+    phaser.set_rate(F::val(0.5));
+    phaser
+  }
+
+  pub fn set_waveform(&mut self, waveform: OscWaveform<F>) {
+    self.left.lfo.set_waveform(waveform.clone());
+    self.right.lfo.set_waveform(waveform);
+  }
+
+  /// Rate of the LFOs in Hz
+  pub fn set_rate(&mut self, rate: F) {
+    self.left.lfo.set_rate(rate);
+    self.right.lfo.set_rate(rate);
+  }
+
+  pub fn set_depth(&mut self, depth: F) {
+    self.depth = depth.max(F::zero()).min(F::one());
+  }
+
+  /// Number of all-pass stages between 1 and `MAX_STAGES`
+  pub fn set_stages(&mut self, stages: usize) {
+    self.num_stages = stages.max(1).min(MAX_STAGES);
+  }
+
+  /// Range of the sweep in Hz
+  pub fn set_frequency_range(&mut self, min_frequency: F, max_frequency: F) {
+    let nyquist = self.sample_rate * F::val(0.49);
+    self.min_frequency = min_frequency.max(F::one()).min(nyquist);
+    self.max_frequency = max_frequency.max(self.min_frequency).min(nyquist);
+  }
+
+  pub fn set_feedback(&mut self, feedback: F) {
+    let max_feedback = F::val(MAX_FEEDBACK);
+    self.feedback = feedback.max(max_feedback.neg()).min(max_feedback);
+  }
+
+  /// Phase offset between the left and right LFOs. Values from 0.0 to 1.0 (half a cycle)
+  pub fn set_spread(&mut self, spread: F) {
+    let spread = spread.max(F::zero()).min(F::one());
+    self.right.lfo.set_phase(spread * F::val(0.5));
+  }
+
+  pub fn set_mix(&mut self, mix: F) {
+    self.mix = mix.max(F::zero()).min(F::one());
+  }
+
+  pub fn reset(&mut self) {
+    self.left.reset();
+    self.right.reset();
+  }
+
+  pub fn process(&mut self, left: F, right: F) -> (F, F) {
+    let modulation_left = self.left.lfo.generate();
+    let modulation_right = self.right.lfo.generate();
+    let coeff_left = self.coeff(modulation_left);
+    let coeff_right = self.coeff(modulation_right);
+
+    let wet_left = self
+      .left
+      .process(left, self.num_stages, coeff_left, self.feedback);
+    let wet_right = self
+      .right
+      .process(right, self.num_stages, coeff_right, self.feedback);
+
+    let dry = F::one() - self.mix;
+    (
+      left * dry + wet_left * self.mix,
+      right * dry + wet_right * self.mix,
+    )
+  }
+
+  /// All-pass coefficient for the break frequency given by the LFO
+  fn coeff(&self, modulation: F) -> F {
+    let ratio = self.max_frequency / self.min_frequency;
+    let position = bipolar_to_unipolar(modulation) * self.depth;
+    let frequency = self.min_frequency * ratio.powf(position);
+    let t = (F::PI * frequency / self.sample_rate).tan();
+    (t - F::one()) / (t + F::one())
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use assert_approx_eq::assert_approx_eq;
+
+  #[test]
+  fn allpass_stage_keeps_the_energy() {
+    let mut stage = AllpassStage::<f64>::default();
+    let coeff = -0.5;
+    let mut energy = stage.process(1.0, coeff).powi(2);
+    for _ in 0..1000 {
+      energy += stage.process(0.0, coeff).powi(2);
+    }
+
+    assert_approx_eq!(energy, 1.0);
+  }
+}
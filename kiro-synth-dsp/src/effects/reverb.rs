@@ -0,0 +1,306 @@
+use crate::effects::delay::InterpolatedDelayLine;
+use crate::float::Float;
+use crate::funcs::smoothing::Smoothing;
+
+/// The tunings of the original Freeverb, in samples at 44100 Hz
+const REFERENCE_SAMPLE_RATE: f64 = 44100.0;
+const COMB_TUNINGS: [f64; NUM_COMBS] = [
+  1116.0, 1188.0, 1277.0, 1356.0, 1422.0, 1491.0, 1557.0, 1617.0,
+];
+const ALLPASS_TUNINGS: [f64; NUM_ALLPASSES] = [556.0, 441.0, 341.0, 225.0];
+const STEREO_SPREAD: f64 = 23.0;
+
+const NUM_COMBS: usize = 8;
+const NUM_ALLPASSES: usize = 4;
+
+const INPUT_GAIN: f64 = 0.015;
+const OUTPUT_GAIN: f64 = 3.0;
+const ALLPASS_FEEDBACK: f64 = 0.5;
+
+/// The smallest size as a proportion of the longest comb lengths
+const MIN_SIZE: f64 = 0.1;
+
+/// Time constant in seconds used to smooth the changes in size
+const SIZE_SMOOTHING: f64 = 0.1;
+
+/// Feedback comb filter with a low-pass filter in the feedback path
+#[derive(Debug)]
+struct Comb<F: Float> {
+  delayline: InterpolatedDelayLine<F>,
+  length: F,
+  feedback: F,
+  filter_store: F,
+}
+
+impl<F: Float> Comb<F> {
+  fn new(length: F) -> Self {
+    Comb {
+      delayline: InterpolatedDelayLine::new(length.ceil().to_usize().unwrap_or(1)),
+      length,
+      feedback: F::zero(),
+      filter_store: F::zero(),
+    }
+  }
+
+  fn reset(&mut self) {
+    self.delayline.reset();
+    self.filter_store = F::zero();
+  }
+
+  /// Set the feedback so the comb decays 60 dB in `decay` seconds for the given size
+  fn set_decay(&mut self, sample_rate: F, size: F, decay: F) {
+    let samples = decay * sample_rate;
+    self.feedback = if samples > F::zero() {
+      let exponent = F::val(-3.0) * self.length * size / samples;
+      F::val(10.0).powf(exponent)
+    } else {
+      F::zero()
+    };
+  }
+
+  fn process(&mut self, input: F, size: F, damping: F) -> F {
+    let output = self.delayline.get(self.length * size);
+    self.filter_store = output * (F::one() - damping) + self.filter_store * damping;
+    self
+      .delayline
+      .update(input + self.filter_store * self.feedback);
+    output
+  }
+}
+
+/// Schroeder all-pass filter used to diffuse the output of the combs
+#[derive(Debug)]
+struct Allpass<F: Float> {
+  delayline: InterpolatedDelayLine<F>,
+  length: F,
+}
+
+impl<F: Float> Allpass<F> {
+  fn new(length: F) -> Self {
+    Allpass {
+      delayline: InterpolatedDelayLine::new(length.ceil().to_usize().unwrap_or(1)),
+      length,
+    }
+  }
+
+  fn reset(&mut self) {
+    self.delayline.reset();
+  }
+
+  fn process(&mut self, input: F) -> F {
+    let delayed = self.delayline.get(self.length);
+    self
+      .delayline
+      .update(input + delayed * F::val(ALLPASS_FEEDBACK));
+    delayed - input
+  }
+}
+
+#[derive(Debug)]
+struct Channel<F: Float> {
+  combs: [Comb<F>; NUM_COMBS],
+  allpasses: [Allpass<F>; NUM_ALLPASSES],
+}
+
+impl<F: Float> Channel<F> {
+  fn new(scale: F, spread: F) -> Self {
+    let comb = |index: usize| Comb::new(F::val(COMB_TUNINGS[index]) * scale + spread);
+    let allpass = |index: usize| Allpass::new(F::val(ALLPASS_TUNINGS[index]) * scale + spread);
+
+    Channel {
+      combs: [
+        comb(0),
+        comb(1),
+        comb(2),
+        comb(3),
+        comb(4),
+        comb(5),
+        comb(6),
+        comb(7),
+      ],
+      allpasses: [allpass(0), allpass(1), allpass(2), allpass(3)],
+    }
+  }
+
+  fn reset(&mut self) {
+    self.combs.iter_mut().for_each(Comb::reset);
+    self.allpasses.iter_mut().for_each(Allpass::reset);
+  }
+
+  fn set_decay(&mut self, sample_rate: F, size: F, decay: F) {
+    for comb in self.combs.iter_mut() {
+      comb.set_decay(sample_rate, size, decay);
+    }
+  }
+
+  fn process(&mut self, input: F, size: F, damping: F) -> F {
+    let mut output = F::zero();
+    for comb in self.combs.iter_mut() {
+      output = output + comb.process(input, size, damping);
+    }
+    for allpass in self.allpasses.iter_mut() {
+      output = allpass.process(output);
+    }
+    output
+  }
+}
+
+/// Stereo algorithmic reverb based on Freeverb, with parallel low-pass feedback combs
+/// followed by serial all-pass diffusers for each channel.
+///
+/// Unlike the original, the size scales the length of the combs, and the decay is given
+/// as the time in seconds for the tail to fall 60 dB, so both can be set independently.
+///
+/// The memory for the delay lines is allocated on construction.
+#[derive(Debug)]
+pub struct Reverb<F: Float> {
+  sample_rate: F,
+  /// The size of the room. Values from 0.0 to 1.0
+  size: Smoothing<F>,
+  /// The time in seconds for the tail to decay 60 dB
+  decay: F,
+  /// The damping of the high frequencies in the tail. Values from 0.0 to 1.0
+  damping: F,
+  /// The stereo width of the tail. Values from 0.0 (mono) to 1.0
+  width: F,
+  /// The dry/wet proportion. Values from 0.0 (dry) to 1.0 (wet)
+  mix: F,
+  predelay: InterpolatedDelayLine<F>,
+  predelay_samples: F,
+  left: Channel<F>,
+  right: Channel<F>,
+}
+
+impl<F: Float> Reverb<F> {
+  pub fn new(sample_rate: F, max_predelay_seconds: F) -> Self {
+    let scale = sample_rate / F::val(REFERENCE_SAMPLE_RATE);
+    let max_predelay_samples = (max_predelay_seconds * sample_rate)
+      .ceil()
+      .to_usize()
+      .unwrap_or(1);
+
+    let mut reverb = Reverb {
+      sample_rate,
+      size: Smoothing::new(sample_rate, F::val(SIZE_SMOOTHING), F::val(0.5)),
+      decay: F::val(2.0),
+      damping: F::val(0.5),
+      width: F::one(),
+      mix: F::zero(),
+      predelay: InterpolatedDelayLine::new(max_predelay_samples),
+      predelay_samples: F::one(),
+      left: Channel::new(scale, F::zero()),
+      right: Channel::new(scale, F::val(STEREO_SPREAD) * scale),
+    };
+    reverb.update_decay();
+    reverb
+  }
+
+  pub fn set_size(&mut self, size: F) {
+    let size = size.max(F::zero()).min(F::one());
+    let min_size = F::val(MIN_SIZE);
+    self
+      .size
+      .set_target(min_size + (F::one() - min_size) * size);
+    self.update_decay();
+  }
+
+  /// Time in seconds for the tail to decay 60 dB
+  pub fn set_decay(&mut self, decay: F) {
+    self.decay = decay.max(F::zero());
+    self.update_decay();
+  }
+
+  pub fn set_damping(&mut self, damping: F) {
+    self.damping = damping.max(F::zero()).min(F::one());
+  }
+
+  /// Time in seconds before the tail starts
+  pub fn set_predelay(&mut self, seconds: F) {
+    self.predelay_samples = seconds * self.sample_rate;
+  }
+
+  pub fn set_width(&mut self, width: F) {
+    self.width = width.max(F::zero()).min(F::one());
+  }
+
+  pub fn set_mix(&mut self, mix: F) {
+    self.mix = mix.max(F::zero()).min(F::one());
+  }
+
+  pub fn reset(&mut self) {
+    self.size.reset(self.size.get_target());
+    self.predelay.reset();
+    self.left.reset();
+    self.right.reset();
+  }
+
+  pub fn process(&mut self, left: F, right: F) -> (F, F) {
+    let size = self.size.next_value();
+
+    // a pre-delay of zero lets the input through
+    self.predelay.update((left + right) * F::val(INPUT_GAIN));
+    let input = self.predelay.get(self.predelay_samples);
+
+    let wet_left = self.left.process(input, size, self.damping);
+    let wet_right = self.right.process(input, size, self.damping);
+
+    let half = F::val(0.5);
+    let wet = self.mix * F::val(OUTPUT_GAIN);
+    let wet1 = wet * (self.width * half + half);
+    let wet2 = wet * ((F::one() - self.width) * half);
+    let dry = F::one() - self.mix;
+
+    (
+      left * dry + wet_left * wet1 + wet_right * wet2,
+      right * dry + wet_right * wet1 + wet_left * wet2,
+    )
+  }
+
+  fn update_decay(&mut self) {
+    let size = self.size.get_target();
+    self.left.set_decay(self.sample_rate, size, self.decay);
+    self.right.set_decay(self.sample_rate, size, self.decay);
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use assert_approx_eq::assert_approx_eq;
+
+  #[test]
+  fn reverb_dry() {
+    let mut reverb = Reverb::<f64>::new(44100.0, 0.1);
+    reverb.set_mix(0.0);
+    for _ in 0..1000 {
+      let (left, right) = reverb.process(0.5, -0.25);
+      assert_approx_eq!(left, 0.5);
+      assert_approx_eq!(right, -0.25);
+    }
+  }
+
+  #[test]
+  fn reverb_tail_decays() {
+    let sample_rate = 44100.0;
+    let mut reverb = Reverb::<f64>::new(sample_rate, 0.1);
+    reverb.set_mix(1.0);
+    reverb.set_decay(0.5);
+
+    let energy = |reverb: &mut Reverb<f64>, samples: usize| {
+      (0..samples)
+        .map(|_| {
+          let (left, right) = reverb.process(0.0, 0.0);
+          left * left + right * right
+        })
+        .sum::<f64>()
+    };
+
+    reverb.process(1.0, 1.0);
+    let early = energy(&mut reverb, 4410);
+    energy(&mut reverb, 22050);
+    let late = energy(&mut reverb, 4410);
+
+    assert!(early > 0.0);
+    assert!(late < early * 1e-3);
+  }
+}
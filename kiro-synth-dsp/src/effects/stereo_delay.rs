@@ -0,0 +1,286 @@
+use crate::effects::delay::InterpolatedDelayLine;
+use crate::filters::freq_control::FreqControl;
+use crate::filters::va_one_pole::{self, VAOnePoleFilter};
+use crate::float::Float;
+use crate::funcs::smoothing::Smoothing;
+
+/// Time constant in seconds used to smooth the changes in the delay time
+const TIME_SMOOTHING: f64 = 0.05;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+  /// Each channel has its own delay line, with optional cross-feedback between them
+  Stereo,
+  /// The input is summed to mono and the repetitions bounce between left and right
+  PingPong,
+}
+
+#[derive(Debug)]
+struct Channel<F: Float> {
+  delayline: InterpolatedDelayLine<F>,
+  time: Smoothing<F>,
+  lowpass: VAOnePoleFilter<F>,
+  highpass: VAOnePoleFilter<F>,
+}
+
+impl<F: Float> Channel<F> {
+  fn new(sample_rate: F, max_delay_samples: usize) -> Self {
+    let mut lowpass = VAOnePoleFilter::new(sample_rate, FreqControl::max_frequency());
+    lowpass.set_mode(va_one_pole::Mode::LowPass);
+    lowpass.set_frequency_modulation(F::zero());
+
+    let mut highpass = VAOnePoleFilter::new(sample_rate, FreqControl::min_frequency());
+    highpass.set_mode(va_one_pole::Mode::HighPass);
+    highpass.set_frequency_modulation(F::zero());
+
+    Channel {
+      delayline: InterpolatedDelayLine::new(max_delay_samples),
+      time: Smoothing::new(sample_rate, F::val(TIME_SMOOTHING), F::one()),
+      lowpass,
+      highpass,
+    }
+  }
+
+  fn reset(&mut self) {
+    self.delayline.reset();
+    self.time.reset(self.time.get_target());
+    self.lowpass.reset();
+    self.highpass.reset();
+  }
+
+  fn filter(&mut self, input: F) -> F {
+    self.highpass.process(self.lowpass.process(input))
+  }
+}
+
+/// Stereo delay with fractional delay times that can be modulated smoothly,
+/// ping-pong and cross-feedback, and a band-pass filter in the feedback path.
+///
+/// The memory for the delay lines is allocated on construction.
+#[derive(Debug)]
+pub struct StereoDelay<F: Float> {
+  sample_rate: F,
+  mode: Mode,
+  /// The amount of feedback into the delay lines. Values from 0.0 to 1.0
+  feedback: F,
+  /// How much of the feedback goes to the opposite channel. Values from 0.0 to 1.0
+  cross_feedback: F,
+  /// The dry/wet proportion. Values from 0.0 (dry) to 1.0 (wet)
+  mix: F,
+  left: Channel<F>,
+  right: Channel<F>,
+}
+
+impl<F: Float> StereoDelay<F> {
+  pub fn new(sample_rate: F, max_delay_seconds: F) -> Self {
+    let max_delay_samples = (max_delay_seconds * sample_rate)
+      .ceil()
+      .to_usize()
+      .unwrap_or(1);
+
+    StereoDelay {
+      sample_rate,
+      mode: Mode::Stereo,
+      feedback: F::zero(),
+      cross_feedback: F::zero(),
+      mix: F::zero(),
+      left: Channel::new(sample_rate, max_delay_samples),
+      right: Channel::new(sample_rate, max_delay_samples),
+    }
+  }
+
+  pub fn set_mode(&mut self, mode: Mode) {
+    self.mode = mode;
+  }
+
+  /// Delay time for the left channel in seconds
+  pub fn set_time_left(&mut self, seconds: F) {
+    self.left.time.set_target(seconds * self.sample_rate);
+  }
+
+  /// Delay time for the right channel in seconds
+  pub fn set_time_right(&mut self, seconds: F) {
+    self.right.time.set_target(seconds * self.sample_rate);
+  }
+
+  pub fn set_feedback(&mut self, feedback: F) {
+    self.feedback = feedback.max(F::zero()).min(F::one());
+  }
+
+  pub fn set_cross_feedback(&mut self, cross_feedback: F) {
+    self.cross_feedback = cross_feedback.max(F::zero()).min(F::one());
+  }
+
+  pub fn set_mix(&mut self, mix: F) {
+    self.mix = mix.max(F::zero()).min(F::one());
+  }
+
+  /// Cutoff frequency of the low-pass filter in the feedback path
+  pub fn set_lowpass_frequency(&mut self, freq: F) {
+    self.left.lowpass.set_frequency(freq);
+    self.right.lowpass.set_frequency(freq);
+  }
+
+  /// Cutoff frequency of the high-pass filter in the feedback path
+  pub fn set_highpass_frequency(&mut self, freq: F) {
+    self.left.highpass.set_frequency(freq);
+    self.right.highpass.set_frequency(freq);
+  }
+
+  pub fn reset(&mut self) {
+    self.left.reset();
+    self.right.reset();
+  }
+
+  pub fn process(&mut self, left: F, right: F) -> (F, F) {
+    let delayed_left = self.left.delayline.get(self.left.time.next_value());
+    let delayed_right = self.right.delayline.get(self.right.time.next_value());
+
+    let (input_left, input_right, cross) = match self.mode {
+      Mode::Stereo => (left, right, self.cross_feedback),
+      Mode::PingPong => ((left + right) * F::val(0.5), F::zero(), F::one()),
+    };
+
+    let direct = F::one() - cross;
+    let feedback_left = self
+      .left
+      .filter(delayed_left * direct + delayed_right * cross);
+    let feedback_right = self
+      .right
+      .filter(delayed_right * direct + delayed_left * cross);
+
+    self
+      .left
+      .delayline
+      .update(input_left + feedback_left * self.feedback);
+    self
+      .right
+      .delayline
+      .update(input_right + feedback_right * self.feedback);
+
+    let dry = F::one() - self.mix;
+    (
+      left * dry + delayed_left * self.mix,
+      right * dry + delayed_right * self.mix,
+    )
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use assert_approx_eq::assert_approx_eq;
+
+  const SAMPLE_RATE: f64 = 44100.0;
+
+  /// The delay time of the tests is 441 samples
+  const TIME: f64 = 0.01;
+
+  fn stereo_delay(mode: Mode, feedback: f64, cross_feedback: f64) -> StereoDelay<f64> {
+    let mut delay = StereoDelay::new(SAMPLE_RATE, 0.1);
+    delay.set_mode(mode);
+    delay.set_time_left(TIME);
+    delay.set_time_right(TIME);
+    delay.set_feedback(feedback);
+    delay.set_cross_feedback(cross_feedback);
+    delay.set_mix(1.0);
+    delay.reset();
+    delay
+  }
+
+  /// The output for an impulse in the left channel, and optionally in the right one
+  fn impulse_response(delay: &mut StereoDelay<f64>, right: f64) -> Vec<(f64, f64)> {
+    let mut output = vec![delay.process(1.0, right)];
+    output.extend((1..2000).map(|_| delay.process(0.0, 0.0)));
+    output
+  }
+
+  /// The sum of the absolute values of every channel around the given repetition
+  fn echo(output: &[(f64, f64)], repetition: usize) -> (f64, f64) {
+    let start = repetition * 441 - 10;
+    output[start..start + 400]
+      .iter()
+      .fold((0.0, 0.0), |(left, right), (l, r)| {
+        (left + l.abs(), right + r.abs())
+      })
+  }
+
+  #[test]
+  fn first_repetition_is_not_filtered() {
+    let mut delay = stereo_delay(Mode::Stereo, 0.5, 0.0);
+    delay.set_lowpass_frequency(100.0);
+    let output = impulse_response(&mut delay, -0.5);
+
+    assert_approx_eq!(output[441].0, 1.0);
+    assert_approx_eq!(output[441].1, -0.5);
+    for (left, right) in output[..441].iter() {
+      assert_approx_eq!(*left, 0.0);
+      assert_approx_eq!(*right, 0.0);
+    }
+  }
+
+  #[test]
+  fn ping_pong_alternates_the_channels() {
+    let mut delay = stereo_delay(Mode::PingPong, 0.8, 0.0);
+    let output = impulse_response(&mut delay, 1.0);
+
+    // the input is summed to mono into the left channel
+    assert_approx_eq!(output[441].0, 1.0);
+    assert_approx_eq!(output[441].1, 0.0);
+
+    // the tails of the filtered repetitions can still be heard in the other channel
+    for repetition in 1..4 {
+      let (left, right) = echo(&output, repetition);
+      if repetition % 2 == 1 {
+        assert!(left > 0.1 && right < left * 0.05);
+      } else {
+        assert!(right > 0.1 && left < right * 0.05);
+      }
+    }
+  }
+
+  #[test]
+  fn cross_feedback_sends_the_repetitions_to_the_other_channel() {
+    let mut delay = stereo_delay(Mode::Stereo, 0.8, 0.0);
+    let output = impulse_response(&mut delay, 0.0);
+    for repetition in 1..4 {
+      let (left, right) = echo(&output, repetition);
+      assert!(left > 0.1 && right < 1e-9);
+    }
+
+    let mut delay = stereo_delay(Mode::Stereo, 0.8, 1.0);
+    let output = impulse_response(&mut delay, 0.0);
+    let (left, right) = echo(&output, 2);
+    assert!(right > 0.1 && left < 1e-9);
+
+    let mut delay = stereo_delay(Mode::Stereo, 0.8, 0.5);
+    let output = impulse_response(&mut delay, 0.0);
+    let (left, right) = echo(&output, 2);
+    assert_approx_eq!(left, right);
+  }
+
+  #[test]
+  fn feedback_filter() {
+    let mut delay = stereo_delay(Mode::Stereo, 0.8, 0.0);
+    let unfiltered = impulse_response(&mut delay, 0.0);
+    let unfiltered_peak = unfiltered[882].0.abs();
+
+    // the low-pass smooths the repetitions
+    let mut delay = stereo_delay(Mode::Stereo, 0.8, 0.0);
+    delay.set_lowpass_frequency(500.0);
+    let output = impulse_response(&mut delay, 0.0);
+    assert!(output[882].0.abs() < unfiltered_peak * 0.5);
+
+    // the high-pass removes the low frequencies
+    let mut delay = stereo_delay(Mode::Stereo, 0.8, 0.0);
+    delay.set_highpass_frequency(5000.0);
+    let output = impulse_response(&mut delay, 0.0);
+    let dc = output[872..1272]
+      .iter()
+      .fold(0.0, |sum, (left, _)| sum + left);
+    let unfiltered_dc = unfiltered[872..1272]
+      .iter()
+      .fold(0.0, |sum, (left, _)| sum + left);
+    assert!(dc.abs() < unfiltered_dc.abs() * 0.5);
+  }
+}
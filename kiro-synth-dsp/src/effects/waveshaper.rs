@@ -0,0 +1,274 @@
+use crate::filters::biquad::{Biquad, Mode};
+use crate::float::Float;
+
+/// Maximum gain applied to the input by the clipping and folding curves
+const MAX_DRIVE_GAIN: f64 = 32.0;
+
+/// Maximum number of samples held by the sample-rate reduction
+const MAX_RATE_REDUCTION: f64 = 64.0;
+
+/// Cutoff of the anti-aliasing filters relative to the Nyquist frequency of the original rate
+const OVERSAMPLING_CUTOFF: f64 = 0.9;
+
+/// The Q of the two sections of a 4th order Butterworth low-pass
+const BUTTERWORTH_Q: [f64; 2] = [0.541_196_1, 1.306_563];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Curve {
+  /// Smooth saturation using tanh
+  SoftClip,
+  /// Clips the signal to [-1.0, 1.0]
+  HardClip,
+  /// Folds the signal back when it goes beyond [-1.0, 1.0]
+  Foldback,
+  /// Reduces the number of bits used to represent the signal
+  BitCrush,
+  /// Holds the samples to reduce the sample rate
+  RateReduction,
+}
+
+impl Curve {
+  const CURVES: [Curve; 5] = [
+    Curve::SoftClip,
+    Curve::HardClip,
+    Curve::Foldback,
+    Curve::BitCrush,
+    Curve::RateReduction,
+  ];
+
+  pub fn count() -> usize {
+    Self::CURVES.len()
+  }
+
+  pub fn from<F: Float>(value: F) -> Option<Self> {
+    value
+      .to_usize()
+      .and_then(|index| Self::CURVES.get(index).copied())
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Oversampling {
+  None,
+  X2,
+  X4,
+}
+
+impl Oversampling {
+  const FACTORS: [Oversampling; 3] = [Oversampling::None, Oversampling::X2, Oversampling::X4];
+
+  pub fn count() -> usize {
+    Self::FACTORS.len()
+  }
+
+  pub fn from<F: Float>(value: F) -> Option<Self> {
+    value
+      .to_usize()
+      .and_then(|index| Self::FACTORS.get(index).copied())
+  }
+
+  pub fn factor(self) -> usize {
+    match self {
+      Oversampling::None => 1,
+      Oversampling::X2 => 2,
+      Oversampling::X4 => 4,
+    }
+  }
+}
+
+/// 4th order Butterworth low-pass made of two biquads
+#[derive(Debug, Clone)]
+struct AntiAliasing<F: Float> {
+  sections: [Biquad<F>; 2],
+}
+
+impl<F: Float> AntiAliasing<F> {
+  fn new(factor: usize) -> Self {
+    // the frequencies are relative to the oversampled rate
+    let sample_rate = F::one();
+    let cutoff = F::val(0.5 * OVERSAMPLING_CUTOFF) / F::val(factor);
+    AntiAliasing {
+      sections: [
+        Biquad::new(sample_rate, Mode::LowPass, cutoff, F::val(BUTTERWORTH_Q[0])),
+        Biquad::new(sample_rate, Mode::LowPass, cutoff, F::val(BUTTERWORTH_Q[1])),
+      ],
+    }
+  }
+
+  fn reset(&mut self) {
+    self.sections.iter_mut().for_each(Biquad::reset);
+  }
+
+  fn process(&mut self, input: F) -> F {
+    let output = self.sections[0].process(input);
+    self.sections[1].process(output)
+  }
+}
+
+/// Waveshaper with selectable curves and optional oversampling to reduce the aliasing.
+///
+/// The drive goes from 0.0 to 1.0 and its meaning depends on the curve:
+/// - clipping and folding: the input gain, from 0 dB to about 30 dB
+/// - bit crush: the number of bits, from 16 down to 1
+/// - rate reduction: the number of samples held, from 1 to 64
+#[derive(Debug, Clone)]
+pub struct Waveshaper<F: Float> {
+  curve: Curve,
+  drive: F,
+  /// The dry/wet proportion. Values from 0.0 (dry) to 1.0 (wet)
+  mix: F,
+  oversampling: Oversampling,
+
+  gain: F,
+  levels: F,
+  hold_samples: F,
+  hold_count: F,
+  hold_value: F,
+
+  upsampling_filter: AntiAliasing<F>,
+  downsampling_filter: AntiAliasing<F>,
+}
+
+impl<F: Float> Default for Waveshaper<F> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<F: Float> Waveshaper<F> {
+  pub fn new() -> Self {
+    let mut waveshaper = Waveshaper {
+      curve: Curve::SoftClip,
+      drive: F::zero(),
+      mix: F::one(),
+      oversampling: Oversampling::None,
+
+      gain: F::one(),
+      levels: F::one(),
+      hold_samples: F::one(),
+      hold_count: F::zero(),
+      hold_value: F::zero(),
+
+      upsampling_filter: AntiAliasing::new(1),
+      downsampling_filter: AntiAliasing::new(1),
+    };
+    waveshaper.update_drive();
+    waveshaper
+  }
+
+  pub fn set_curve(&mut self, curve: Curve) {
+    self.curve = curve;
+  }
+
+  pub fn set_drive(&mut self, drive: F) {
+    self.drive = drive.max(F::zero()).min(F::one());
+    self.update_drive();
+  }
+
+  pub fn set_mix(&mut self, mix: F) {
+    self.mix = mix.max(F::zero()).min(F::one());
+  }
+
+  pub fn set_oversampling(&mut self, oversampling: Oversampling) {
+    if self.oversampling != oversampling {
+      self.oversampling = oversampling;
+      self.upsampling_filter = AntiAliasing::new(oversampling.factor());
+      self.downsampling_filter = AntiAliasing::new(oversampling.factor());
+      self.update_drive();
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.upsampling_filter.reset();
+    self.downsampling_filter.reset();
+    self.hold_count = F::zero();
+    self.hold_value = F::zero();
+  }
+
+  pub fn process(&mut self, input: F) -> F {
+    let output = match self.oversampling {
+      Oversampling::None => self.shape(input),
+      _ => self.process_oversampled(input),
+    };
+
+    input * (F::one() - self.mix) + output * self.mix
+  }
+
+  /// Zero-stuffing upsampling, low-pass, shaping, low-pass and decimation
+  fn process_oversampled(&mut self, input: F) -> F {
+    let factor = self.oversampling.factor();
+    let mut output = F::zero();
+    for index in 0..factor {
+      let sample = if index == 0 {
+        input * F::val(factor)
+      } else {
+        F::zero()
+      };
+      let upsampled = self.upsampling_filter.process(sample);
+      let shaped = self.shape(upsampled);
+      output = self.downsampling_filter.process(shaped);
+    }
+    output
+  }
+
+  fn shape(&mut self, input: F) -> F {
+    match self.curve {
+      Curve::SoftClip => (input * self.gain).tanh(),
+      Curve::HardClip => (input * self.gain).max(F::one().neg()).min(F::one()),
+      Curve::Foldback => Self::fold(input * self.gain),
+      Curve::BitCrush => (input * self.levels).round() / self.levels,
+      Curve::RateReduction => {
+        if self.hold_count <= F::zero() {
+          self.hold_value = input;
+          self.hold_count = self.hold_count + self.hold_samples;
+        }
+        self.hold_count = self.hold_count - F::one();
+        self.hold_value
+      }
+    }
+  }
+
+  /// Triangle folding that maps any value into [-1.0, 1.0]
+  fn fold(input: F) -> F {
+    let position = (input + F::one()) * F::val(0.25);
+    let fraction = position - position.floor();
+    F::one() - F::val(4.0) * (fraction - F::val(0.5)).abs()
+  }
+
+  fn update_drive(&mut self) {
+    self.gain = F::val(MAX_DRIVE_GAIN).powf(self.drive);
+    let bits = F::val(16.0) - F::val(15.0) * self.drive;
+    self.levels = F::val(2.0).powf(bits - F::one());
+    let hold = F::one() + F::val(MAX_RATE_REDUCTION - 1.0) * self.drive;
+    self.hold_samples = hold * F::val(self.oversampling.factor());
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use assert_approx_eq::assert_approx_eq;
+
+  #[test]
+  fn waveshaper_fold() {
+    assert_approx_eq!(Waveshaper::<f64>::fold(0.0), 0.0);
+    assert_approx_eq!(Waveshaper::<f64>::fold(0.5), 0.5);
+    assert_approx_eq!(Waveshaper::<f64>::fold(1.0), 1.0);
+    assert_approx_eq!(Waveshaper::<f64>::fold(1.5), 0.5);
+    assert_approx_eq!(Waveshaper::<f64>::fold(3.0), -1.0);
+    assert_approx_eq!(Waveshaper::<f64>::fold(-1.25), -0.75);
+  }
+
+  #[test]
+  fn waveshaper_oversampling_keeps_the_level() {
+    let mut waveshaper = Waveshaper::<f64>::new();
+    waveshaper.set_curve(Curve::HardClip);
+    waveshaper.set_oversampling(Oversampling::X4);
+
+    let mut output = 0.0;
+    for _ in 0..1000 {
+      output = waveshaper.process(0.5);
+    }
+    assert_approx_eq!(output, 0.5, 1e-3);
+  }
+}
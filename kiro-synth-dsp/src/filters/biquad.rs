@@ -0,0 +1,277 @@
+use crate::float::Float;
+use crate::funcs::decibels::Decibels;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+  LowPass,
+  HighPass,
+  BandPass,
+  Notch,
+  Peaking,
+  LowShelf,
+  HighShelf,
+}
+
+impl Mode {
+  const MODES: [Mode; 7] = [
+    Mode::LowPass,
+    Mode::HighPass,
+    Mode::BandPass,
+    Mode::Notch,
+    Mode::Peaking,
+    Mode::LowShelf,
+    Mode::HighShelf,
+  ];
+
+  pub fn count() -> usize {
+    Self::MODES.len()
+  }
+
+  pub fn from<F: Float>(value: F) -> Option<Self> {
+    value
+      .to_usize()
+      .and_then(|index| Self::MODES.get(index).copied())
+  }
+}
+
+/// Second order filter with the coefficients from the RBJ Audio EQ Cookbook.
+///
+/// The gain is only used by the peaking and shelving modes.
+/// The Q sets the bandwidth for the band modes and the slope for the shelving ones.
+#[derive(Debug, Clone)]
+pub struct Biquad<F: Float> {
+  sample_rate: F,
+  mode: Mode,
+  /// Cutoff or center frequency in Hz
+  frequency: F,
+  q: F,
+  /// Gain in dB
+  gain: F,
+  b0: F,
+  b1: F,
+  b2: F,
+  a1: F,
+  a2: F,
+  z1: F,
+  z2: F,
+}
+
+impl<F: Float> Biquad<F> {
+  pub fn new(sample_rate: F, mode: Mode, frequency: F, q: F) -> Self {
+    let mut biquad = Biquad {
+      sample_rate,
+      mode,
+      frequency,
+      q,
+      gain: F::zero(),
+      b0: F::one(),
+      b1: F::zero(),
+      b2: F::zero(),
+      a1: F::zero(),
+      a2: F::zero(),
+      z1: F::zero(),
+      z2: F::zero(),
+    };
+    biquad.set_frequency(frequency);
+    biquad.set_q(q);
+    biquad
+  }
+
+  pub fn set_mode(&mut self, mode: Mode) {
+    self.mode = mode;
+    self.update();
+  }
+
+  pub fn set_frequency(&mut self, frequency: F) {
+    let nyquist = self.sample_rate * F::val(0.49);
+    self.frequency = frequency.max(F::val(1e-6) * self.sample_rate).min(nyquist);
+    self.update();
+  }
+
+  pub fn set_q(&mut self, q: F) {
+    self.q = q.max(F::val(0.01));
+    self.update();
+  }
+
+  /// Gain in dB
+  pub fn set_gain(&mut self, gain: F) {
+    self.gain = gain;
+    self.update();
+  }
+
+  pub fn reset(&mut self) {
+    self.z1 = F::zero();
+    self.z2 = F::zero();
+  }
+
+  pub fn process(&mut self, input: F) -> F {
+    let output = self.b0 * input + self.z1;
+    self.z1 = self.b1 * input - self.a1 * output + self.z2;
+    self.z2 = self.b2 * input - self.a2 * output;
+    output
+  }
+
+  /// The magnitude of the frequency response at the given frequency in Hz
+  pub fn magnitude(&self, frequency: F) -> F {
+    let w = F::val(2.0) * F::PI * frequency / self.sample_rate;
+    let (cos1, sin1) = (w.cos(), w.sin());
+    let (cos2, sin2) = ((w + w).cos(), (w + w).sin());
+    let num_re = self.b0 + self.b1 * cos1 + self.b2 * cos2;
+    let num_im = self.b1 * sin1 + self.b2 * sin2;
+    let den_re = F::one() + self.a1 * cos1 + self.a2 * cos2;
+    let den_im = self.a1 * sin1 + self.a2 * sin2;
+    ((num_re * num_re + num_im * num_im) / (den_re * den_re + den_im * den_im)).sqrt()
+  }
+
+  fn update(&mut self) {
+    let one = F::one();
+    let two = F::val(2.0);
+    let w0 = two * F::PI * self.frequency / self.sample_rate;
+    let cos_w0 = w0.cos();
+    let alpha = w0.sin() / (two * self.q);
+    let a = Decibels::new(self.gain / two).to_amplitude();
+
+    let (b0, b1, b2, a0, a1, a2) = match self.mode {
+      Mode::LowPass => {
+        let b1 = one - cos_w0;
+        (
+          b1 / two,
+          b1,
+          b1 / two,
+          one + alpha,
+          -two * cos_w0,
+          one - alpha,
+        )
+      }
+      Mode::HighPass => {
+        let b1 = -(one + cos_w0);
+        (
+          -b1 / two,
+          b1,
+          -b1 / two,
+          one + alpha,
+          -two * cos_w0,
+          one - alpha,
+        )
+      }
+      Mode::BandPass => (
+        alpha,
+        F::zero(),
+        -alpha,
+        one + alpha,
+        -two * cos_w0,
+        one - alpha,
+      ),
+      Mode::Notch => (
+        one,
+        -two * cos_w0,
+        one,
+        one + alpha,
+        -two * cos_w0,
+        one - alpha,
+      ),
+      Mode::Peaking => (
+        one + alpha * a,
+        -two * cos_w0,
+        one - alpha * a,
+        one + alpha / a,
+        -two * cos_w0,
+        one - alpha / a,
+      ),
+      Mode::LowShelf => {
+        let beta = two * a.sqrt() * alpha;
+        let (plus, minus) = (a + one, a - one);
+        (
+          a * (plus - minus * cos_w0 + beta),
+          two * a * (minus - plus * cos_w0),
+          a * (plus - minus * cos_w0 - beta),
+          plus + minus * cos_w0 + beta,
+          -two * (minus + plus * cos_w0),
+          plus + minus * cos_w0 - beta,
+        )
+      }
+      Mode::HighShelf => {
+        let beta = two * a.sqrt() * alpha;
+        let (plus, minus) = (a + one, a - one);
+        (
+          a * (plus + minus * cos_w0 + beta),
+          -two * a * (minus + plus * cos_w0),
+          a * (plus + minus * cos_w0 - beta),
+          plus - minus * cos_w0 + beta,
+          two * (minus - plus * cos_w0),
+          plus - minus * cos_w0 - beta,
+        )
+      }
+    };
+
+    self.b0 = b0 / a0;
+    self.b1 = b1 / a0;
+    self.b2 = b2 / a0;
+    self.a1 = a1 / a0;
+    self.a2 = a2 / a0;
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use assert_approx_eq::assert_approx_eq;
+
+  const SAMPLE_RATE: f64 = 44100.0;
+
+  fn gain_db(biquad: &Biquad<f64>, frequency: f64) -> f64 {
+    Decibels::from_amplitude(biquad.magnitude(frequency)).value()
+  }
+
+  #[test]
+  fn biquad_pass_filters() {
+    let lowpass = Biquad::new(SAMPLE_RATE, Mode::LowPass, 1000.0, 0.5f64.sqrt());
+    assert_approx_eq!(gain_db(&lowpass, 0.0), 0.0);
+    assert_approx_eq!(gain_db(&lowpass, 1000.0), -3.0103, 1e-3);
+
+    let highpass = Biquad::new(SAMPLE_RATE, Mode::HighPass, 1000.0, 0.5f64.sqrt());
+    assert_approx_eq!(gain_db(&highpass, SAMPLE_RATE / 2.0), 0.0);
+    assert_approx_eq!(gain_db(&highpass, 1000.0), -3.0103, 1e-3);
+
+    let bandpass = Biquad::new(SAMPLE_RATE, Mode::BandPass, 1000.0, 2.0);
+    assert_approx_eq!(gain_db(&bandpass, 1000.0), 0.0);
+
+    let notch = Biquad::new(SAMPLE_RATE, Mode::Notch, 1000.0, 2.0);
+    assert_approx_eq!(notch.magnitude(1000.0), 0.0);
+  }
+
+  #[test]
+  fn biquad_peaking_and_shelving_gains() {
+    let mut peaking = Biquad::new(SAMPLE_RATE, Mode::Peaking, 1000.0, 1.0);
+    peaking.set_gain(6.0);
+    assert_approx_eq!(gain_db(&peaking, 1000.0), 6.0);
+    assert_approx_eq!(gain_db(&peaking, 0.0), 0.0);
+
+    let mut low_shelf = Biquad::new(SAMPLE_RATE, Mode::LowShelf, 200.0, 0.5f64.sqrt());
+    low_shelf.set_gain(-12.0);
+    assert_approx_eq!(gain_db(&low_shelf, 0.0), -12.0);
+    assert_approx_eq!(gain_db(&low_shelf, 200.0), -6.0);
+    assert_approx_eq!(gain_db(&low_shelf, SAMPLE_RATE / 2.0), 0.0);
+
+    let mut high_shelf = Biquad::new(SAMPLE_RATE, Mode::HighShelf, 5000.0, 0.5f64.sqrt());
+    high_shelf.set_gain(9.0);
+    assert_approx_eq!(gain_db(&high_shelf, 0.0), 0.0);
+    assert_approx_eq!(gain_db(&high_shelf, SAMPLE_RATE / 2.0), 9.0);
+  }
+
+  #[test]
+  fn biquad_process_matches_the_magnitude() {
+    let mut peaking = Biquad::new(SAMPLE_RATE, Mode::Peaking, 1000.0, 1.0);
+    peaking.set_gain(-6.0);
+
+    let omega = 2.0 * std::f64::consts::PI * 1000.0 / SAMPLE_RATE;
+    let mut peak = 0.0f64;
+    for index in 0..44100 {
+      let output = peaking.process((index as f64 * omega).sin());
+      if index > 22050 {
+        peak = peak.max(output.abs());
+      }
+    }
+    assert_approx_eq!(peak, peaking.magnitude(1000.0), 1e-3);
+  }
+}
@@ -1,3 +1,4 @@
+pub mod biquad;
 pub mod freq_control;
 pub mod oberheim_sem;
 pub mod q_control;
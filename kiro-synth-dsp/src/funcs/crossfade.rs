@@ -0,0 +1,32 @@
+use crate::float::Float;
+
+/// Equal-power gains for the two signals of a crossfade.
+/// The position goes from 0.0 (only the first signal) to 1.0 (only the second one),
+/// and it is clamped to that range.
+pub fn equal_power_gains<F: Float>(position: F) -> (F, F) {
+  let angle = position.max(F::zero()).min(F::one()) * F::PI * F::val(0.5);
+  (angle.cos(), angle.sin())
+}
+
+pub fn equal_power_crossfade<F: Float>(first: F, second: F, position: F) -> F {
+  let (first_gain, second_gain) = equal_power_gains(position);
+  first * first_gain + second * second_gain
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use assert_approx_eq::assert_approx_eq;
+
+  #[test]
+  fn equal_power_gains_keep_the_power() {
+    for index in 0..=10 {
+      let (first, second) = equal_power_gains(index as f64 / 10.0);
+      assert_approx_eq!(first * first + second * second, 1.0);
+    }
+
+    assert_approx_eq!(equal_power_crossfade(0.5f64, -0.5, 0.0), 0.5);
+    assert_approx_eq!(equal_power_crossfade(0.5f64, -0.5, 1.0), -0.5);
+    assert_approx_eq!(equal_power_crossfade(0.5f64, -0.5, 2.0), -0.5);
+  }
+}
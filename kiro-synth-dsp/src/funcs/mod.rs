@@ -1,5 +1,8 @@
 pub mod concave_transforms;
+pub mod crossfade;
 pub mod decibels;
 pub mod interpolation;
 pub mod parabolic_sine;
+pub mod random;
 pub mod signal_polarity;
+pub mod smoothing;
@@ -0,0 +1,44 @@
+use crate::float::Float;
+
+/// The seed used when none is given, the state of the generator can not be zero
+const DEFAULT_SEED: u32 = 0x9e37_79b9;
+
+/// Xorshift pseudo-random generator.
+/// It is not suitable for anything else than audio and modulation signals.
+#[derive(Debug, Clone)]
+pub struct Random {
+  state: u32,
+}
+
+impl Default for Random {
+  fn default() -> Self {
+    Random::new(DEFAULT_SEED)
+  }
+}
+
+impl Random {
+  pub fn new(seed: u32) -> Self {
+    Random {
+      state: if seed == 0 { DEFAULT_SEED } else { seed },
+    }
+  }
+
+  pub fn next_u32(&mut self) -> u32 {
+    let mut x = self.state;
+    x ^= x << 13;
+    x ^= x >> 17;
+    x ^= x << 5;
+    self.state = x;
+    x
+  }
+
+  /// value between [0.0, 1.0]
+  pub fn next_unipolar<F: Float>(&mut self) -> F {
+    F::val(self.next_u32()) / F::val(u32::MAX)
+  }
+
+  /// value between [-1.0, 1.0]
+  pub fn next_bipolar<F: Float>(&mut self) -> F {
+    F::val(2.0) * self.next_unipolar::<F>() - F::one()
+  }
+}
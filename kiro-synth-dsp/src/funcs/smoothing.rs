@@ -0,0 +1,52 @@
+use crate::float::Float;
+
+/// One-pole smoothing for parameter changes to avoid zipper noise.
+#[derive(Debug, Clone)]
+pub struct Smoothing<F: Float> {
+  value: F,
+  target: F,
+  coeff: F,
+}
+
+impl<F: Float> Smoothing<F> {
+  /// `time` is the time constant in seconds (time to reach 63% of the target)
+  pub fn new(sample_rate: F, time: F, value: F) -> Self {
+    Smoothing {
+      value,
+      target: value,
+      coeff: Self::coeff(sample_rate, time),
+    }
+  }
+
+  pub fn set_time(&mut self, sample_rate: F, time: F) {
+    self.coeff = Self::coeff(sample_rate, time);
+  }
+
+  pub fn set_target(&mut self, target: F) {
+    self.target = target;
+  }
+
+  pub fn get_target(&self) -> F {
+    self.target
+  }
+
+  /// Jump to a value without smoothing
+  pub fn reset(&mut self, value: F) {
+    self.value = value;
+    self.target = value;
+  }
+
+  pub fn next_value(&mut self) -> F {
+    self.value = self.target + (self.value - self.target) * self.coeff;
+    self.value
+  }
+
+  fn coeff(sample_rate: F, time: F) -> F {
+    let samples = time * sample_rate;
+    if samples > F::zero() {
+      (F::one().neg() / samples).exp()
+    } else {
+      F::zero()
+    }
+  }
+}
@@ -8,5 +8,7 @@ pub mod filters;
 pub mod float;
 pub mod funcs;
 pub mod meters;
+pub mod mixer;
 pub mod oscillators;
+pub mod utilities;
 pub mod waveforms;
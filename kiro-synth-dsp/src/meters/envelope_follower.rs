@@ -0,0 +1,152 @@
+use generic_array::typenum::consts;
+
+use crate::float::Float;
+use crate::meters::rms_online::RmsOnline;
+
+/// Number of samples averaged by the RMS detector
+type RmsWindow = consts::U64;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Detector {
+  /// The absolute value of every sample
+  Peak,
+  /// The root mean square over a short window
+  Rms,
+}
+
+impl Detector {
+  const DETECTORS: [Detector; 2] = [Detector::Peak, Detector::Rms];
+
+  pub fn count() -> usize {
+    Self::DETECTORS.len()
+  }
+
+  pub fn from<F: Float>(value: F) -> Option<Self> {
+    value
+      .to_usize()
+      .and_then(|index| Self::DETECTORS.get(index).copied())
+  }
+}
+
+/// Converts an audio signal into a control signal following its amplitude.
+///
+/// The level from the detector is smoothed with different attack and release times,
+/// so the output rises while the level goes up and falls while it goes down.
+#[derive(Debug, Clone)]
+pub struct EnvelopeFollower<F: Float> {
+  sample_rate: F,
+  detector: Detector,
+  attack_coeff: F,
+  release_coeff: F,
+  rms: RmsOnline<F, RmsWindow>,
+  envelope: F,
+}
+
+impl<F: Float> EnvelopeFollower<F> {
+  pub fn new(sample_rate: F) -> Self {
+    let mut follower = EnvelopeFollower {
+      sample_rate,
+      detector: Detector::Peak,
+      attack_coeff: F::zero(),
+      release_coeff: F::zero(),
+      rms: RmsOnline::default(),
+      envelope: F::zero(),
+    };
+    follower.set_attack(F::val(0.01));
+    follower.set_release(F::val(0.1));
+    follower
+  }
+
+  pub fn set_detector(&mut self, detector: Detector) {
+    self.detector = detector;
+  }
+
+  /// Attack time in seconds
+  pub fn set_attack(&mut self, attack: F) {
+    self.attack_coeff = Self::coeff(self.sample_rate, attack);
+  }
+
+  /// Release time in seconds
+  pub fn set_release(&mut self, release: F) {
+    self.release_coeff = Self::coeff(self.sample_rate, release);
+  }
+
+  pub fn get(&self) -> F {
+    self.envelope
+  }
+
+  pub fn reset(&mut self) {
+    self.rms.reset();
+    self.envelope = F::zero();
+  }
+
+  pub fn process(&mut self, input: F) -> F {
+    let level = match self.detector {
+      Detector::Peak => input.abs(),
+      Detector::Rms => {
+        self.rms.process(input);
+        self.rms.get()
+      }
+    };
+
+    let coeff = if level > self.envelope {
+      self.attack_coeff
+    } else {
+      self.release_coeff
+    };
+    self.envelope = level + (self.envelope - level) * coeff;
+    self.envelope
+  }
+
+  fn coeff(sample_rate: F, time: F) -> F {
+    let samples = time * sample_rate;
+    if samples > F::zero() {
+      (F::one().neg() / samples).exp()
+    } else {
+      F::zero()
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use assert_approx_eq::assert_approx_eq;
+
+  const SAMPLE_RATE: f64 = 44100.0;
+
+  fn follow(follower: &mut EnvelopeFollower<f64>, amplitude: f64, samples: usize) -> f64 {
+    let omega = 2.0 * std::f64::consts::PI * 1000.0 / SAMPLE_RATE;
+    (0..samples).fold(0.0, |_, index| {
+      follower.process(amplitude * (index as f64 * omega).sin())
+    })
+  }
+
+  #[test]
+  fn envelope_follower_detectors() {
+    let mut follower = EnvelopeFollower::<f64>::new(SAMPLE_RATE);
+    follower.set_detector(Detector::Rms);
+    follower.set_attack(0.0);
+    follower.set_release(0.0);
+    assert_approx_eq!(follow(&mut follower, 0.8, 4410), 0.8 / 2.0f64.sqrt(), 0.02);
+
+    follower.set_detector(Detector::Peak);
+    follower.set_release(0.05);
+    assert_approx_eq!(follow(&mut follower, 0.8, 4410), 0.8, 0.02);
+  }
+
+  #[test]
+  fn envelope_follower_attack_and_release() {
+    let mut follower = EnvelopeFollower::<f64>::new(SAMPLE_RATE);
+    follower.set_detector(Detector::Rms);
+    follower.set_attack(0.01);
+    follower.set_release(0.1);
+
+    let attack = follow(&mut follower, 1.0, 441);
+    let sustain = follow(&mut follower, 1.0, 4410);
+    assert!(attack > 0.5 * sustain && attack < sustain);
+
+    let release = follow(&mut follower, 0.0, 4410);
+    assert!(release > 0.3 * sustain && release < 0.4 * sustain);
+  }
+}
@@ -1,3 +1,4 @@
+pub mod envelope_follower;
 pub mod level_meter;
 pub mod rms_online;
 
@@ -0,0 +1,155 @@
+use crate::float::Float;
+
+/// A mono input of a mixer with its level, pan and mute, producing a stereo pair.
+///
+/// The pan uses a balance law, so a centered channel keeps its level in both sides,
+/// and panning it only attenuates the opposite side.
+///
+/// The send splits the channel after its level: that amount goes to a mono send output,
+/// and only the rest goes to the stereo pair, so nothing is heard twice.
+#[derive(Debug, Clone)]
+pub struct MixerChannel<F: Float> {
+  level: F,
+  pan: F,
+  send: F,
+  mute: bool,
+  gain_left: F,
+  gain_right: F,
+  gain_send: F,
+}
+
+impl<F: Float> Default for MixerChannel<F> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<F: Float> MixerChannel<F> {
+  pub fn new() -> Self {
+    MixerChannel {
+      level: F::one(),
+      pan: F::zero(),
+      send: F::zero(),
+      mute: false,
+      gain_left: F::one(),
+      gain_right: F::one(),
+      gain_send: F::zero(),
+    }
+  }
+
+  /// value expected to be between 0.0 and 1.0
+  pub fn set_level(&mut self, level: F) {
+    self.level = level.max(F::zero());
+    self.update_gains();
+  }
+
+  /// value expected to be between -1.0 and 1.0
+  pub fn set_pan(&mut self, pan: F) {
+    self.pan = pan.max(F::one().neg()).min(F::one());
+    self.update_gains();
+  }
+
+  /// value expected to be between 0.0 and 1.0
+  pub fn set_send(&mut self, send: F) {
+    self.send = send.max(F::zero()).min(F::one());
+    self.update_gains();
+  }
+
+  pub fn set_mute(&mut self, mute: bool) {
+    self.mute = mute;
+    self.update_gains();
+  }
+
+  pub fn process(&self, input: F) -> (F, F) {
+    (input * self.gain_left, input * self.gain_right)
+  }
+
+  pub fn process_send(&self, input: F) -> F {
+    input * self.gain_send
+  }
+
+  fn update_gains(&mut self) {
+    if self.mute {
+      self.gain_left = F::zero();
+      self.gain_right = F::zero();
+      self.gain_send = F::zero();
+    } else {
+      let direct = self.level * (F::one() - self.send);
+      self.gain_left = direct * (F::one() - self.pan).min(F::one());
+      self.gain_right = direct * (F::one() + self.pan).min(F::one());
+      self.gain_send = self.level * self.send;
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use assert_approx_eq::assert_approx_eq;
+
+  fn assert_gains(channel: &MixerChannel<f64>, left: f64, right: f64) {
+    let (output_left, output_right) = channel.process(0.5);
+    assert_approx_eq!(output_left, 0.5 * left);
+    assert_approx_eq!(output_right, 0.5 * right);
+  }
+
+  #[test]
+  fn mixer_channel_level() {
+    let mut channel = MixerChannel::<f64>::new();
+    assert_gains(&channel, 1.0, 1.0);
+
+    channel.set_level(0.25);
+    assert_gains(&channel, 0.25, 0.25);
+
+    channel.set_level(-1.0);
+    assert_gains(&channel, 0.0, 0.0);
+  }
+
+  #[test]
+  fn mixer_channel_pan() {
+    let mut channel = MixerChannel::<f64>::new();
+    channel.set_level(0.5);
+
+    channel.set_pan(-1.0);
+    assert_gains(&channel, 0.5, 0.0);
+
+    channel.set_pan(0.5);
+    assert_gains(&channel, 0.25, 0.5);
+
+    channel.set_pan(2.0);
+    assert_gains(&channel, 0.0, 0.5);
+  }
+
+  #[test]
+  fn mixer_channel_send() {
+    let mut channel = MixerChannel::<f64>::new();
+    assert_approx_eq!(channel.process_send(0.5), 0.0);
+
+    channel.set_level(0.5);
+    channel.set_pan(-1.0);
+    channel.set_send(0.25);
+    assert_gains(&channel, 0.375, 0.0);
+    assert_approx_eq!(channel.process_send(0.5), 0.5 * 0.125);
+
+    channel.set_send(2.0);
+    assert_gains(&channel, 0.0, 0.0);
+    assert_approx_eq!(channel.process_send(0.5), 0.5 * 0.5);
+  }
+
+  #[test]
+  fn mixer_channel_mute() {
+    let mut channel = MixerChannel::<f64>::new();
+    channel.set_pan(-0.5);
+    channel.set_send(0.5);
+    channel.set_mute(true);
+    assert_gains(&channel, 0.0, 0.0);
+    assert_approx_eq!(channel.process_send(0.5), 0.0);
+
+    // the level and pan changes while muted are kept
+    channel.set_level(0.5);
+    assert_gains(&channel, 0.0, 0.0);
+    channel.set_send(0.0);
+    channel.set_mute(false);
+    assert_gains(&channel, 0.5, 0.25);
+  }
+}
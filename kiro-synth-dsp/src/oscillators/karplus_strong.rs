@@ -0,0 +1,169 @@
+use crate::effects::delay::InterpolatedDelayLine;
+use crate::float::Float;
+use crate::funcs::random::Random;
+
+/// Lowest frequency that can be played
+pub const MIN_FREQUENCY: f64 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Excitation {
+  /// A burst of white noise as long as one period when plucked
+  Noise,
+  /// The input signal is fed continuously into the string
+  Input,
+}
+
+impl Excitation {
+  const EXCITATIONS: [Excitation; 2] = [Excitation::Noise, Excitation::Input];
+
+  pub fn count() -> usize {
+    Self::EXCITATIONS.len()
+  }
+
+  pub fn from<F: Float>(value: F) -> Option<Self> {
+    value
+      .to_usize()
+      .and_then(|index| Self::EXCITATIONS.get(index).copied())
+  }
+}
+
+/// Karplus-Strong plucked string.
+///
+/// The excitation goes into a delay line as long as the period of the note,
+/// and its output is fed back through a low-pass filter (the damping) and a gain (the decay).
+/// The delay is fractional and compensates the delay of the filter, so the string stays in tune.
+///
+/// The memory for the delay line is allocated on construction.
+#[derive(Debug, Clone)]
+pub struct KarplusStrong<F: Float> {
+  sample_rate: F,
+  excitation: Excitation,
+  frequency: F,
+  /// Values from 0.0 (bright) to 1.0 (dull)
+  damping: F,
+  /// Time in seconds for the string to decay by 60 dB
+  decay: F,
+
+  delay_samples: F,
+  feedback: F,
+  burst_samples: usize,
+  last_output: F,
+  delayline: InterpolatedDelayLine<F>,
+  random: Random,
+}
+
+impl<F: Float> KarplusStrong<F> {
+  /// The seed is used for the noise burst, so every string can sound different
+  pub fn new(sample_rate: F, seed: u32) -> Self {
+    let max_delay = (sample_rate / F::val(MIN_FREQUENCY))
+      .ceil()
+      .to_usize()
+      .unwrap_or(1)
+      + 1;
+
+    let mut karplus_strong = KarplusStrong {
+      sample_rate,
+      excitation: Excitation::Noise,
+      frequency: F::val(440.0),
+      damping: F::val(0.5),
+      decay: F::val(2.0),
+      delay_samples: F::one(),
+      feedback: F::zero(),
+      burst_samples: 0,
+      last_output: F::zero(),
+      delayline: InterpolatedDelayLine::new(max_delay),
+      random: Random::new(seed),
+    };
+    karplus_strong.update();
+    karplus_strong
+  }
+
+  pub fn set_excitation(&mut self, excitation: Excitation) {
+    self.excitation = excitation;
+  }
+
+  /// Frequency of the note in Hz
+  pub fn set_frequency(&mut self, frequency: F) {
+    let nyquist = self.sample_rate * F::val(0.5);
+    self.frequency = frequency.max(F::val(MIN_FREQUENCY)).min(nyquist);
+    self.update();
+  }
+
+  pub fn set_damping(&mut self, damping: F) {
+    self.damping = damping.max(F::zero()).min(F::one());
+    self.update();
+  }
+
+  /// Decay time in seconds
+  pub fn set_decay(&mut self, decay: F) {
+    self.decay = decay.max(F::val(0.001));
+    self.update();
+  }
+
+  /// Starts the noise burst when the excitation is `Excitation::Noise`
+  pub fn pluck(&mut self) {
+    if self.excitation == Excitation::Noise {
+      self.burst_samples = self.delay_samples.round().to_usize().unwrap_or(1);
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.delayline.reset();
+    self.burst_samples = 0;
+    self.last_output = F::zero();
+  }
+
+  pub fn process(&mut self, input: F) -> F {
+    let excitation = match self.excitation {
+      Excitation::Input => input,
+      Excitation::Noise if self.burst_samples > 0 => {
+        self.burst_samples -= 1;
+        self.random.next_bipolar()
+      }
+      Excitation::Noise => F::zero(),
+    };
+
+    let delayed = self.delayline.get(self.delay_samples);
+    let half_damping = self.damping * F::val(0.5);
+    let filtered = delayed * (F::one() - half_damping) + self.last_output * half_damping;
+    self.last_output = delayed;
+
+    let output = excitation + filtered * self.feedback;
+    self.delayline.update(output);
+    output
+  }
+
+  fn update(&mut self) {
+    let period = self.sample_rate / self.frequency;
+    // the two points average of the damping filter delays the loop by half the damping
+    let max_delay = F::val(self.delayline.max_delay());
+    self.delay_samples = (period - self.damping * F::val(0.5))
+      .max(F::one())
+      .min(max_delay);
+    // gain per period to decay 60 dB along the decay time
+    self.feedback = F::val(10.0).powf(F::val(-3.0) / (self.decay * self.frequency));
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn karplus_strong_decays() {
+    let mut string = KarplusStrong::<f64>::new(44100.0, 1);
+    string.set_frequency(220.0);
+    string.set_decay(0.5);
+    string.pluck();
+
+    let peak = |string: &mut KarplusStrong<f64>, samples: usize| {
+      (0..samples).fold(0.0f64, |peak, _| peak.max(string.process(0.0).abs()))
+    };
+
+    let start = peak(&mut string, 4410);
+    assert!(start > 0.1);
+    peak(&mut string, 22050 - 4410);
+    let end = peak(&mut string, 4410);
+    assert!(end < start * 0.01);
+  }
+}
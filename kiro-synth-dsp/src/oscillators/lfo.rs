@@ -4,53 +4,97 @@ use crate::oscillators::osc_waveform::OscWaveform;
 
 // TODO add another waveform for quadrature phase output
 
-// TODO Mode: free-running, synchronized, one-shot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+  /// The LFO keeps running, triggers don't change its phase
+  FreeRunning,
+  /// The LFO restarts from the start phase on every trigger
+  Retrigger,
+  /// The LFO restarts from the start phase on every trigger and stops after one cycle (like an envelope)
+  OneShot,
+}
 
 #[derive(Debug, Clone)]
 pub struct Lfo<F: Float> {
   waveform: OscWaveform<F>,
+  mode: Mode,
   rate: F,
   phase: F,
   depth: F,
+  delay: F,
+  fade_in: F,
+  seed: u32,
 
   modulo: F,
   phase_inc: F,
   phase_inc_invalidated: bool,
+  sample_rate: F,
   inv_sample_rate: F,
+
+  cycle_position: F,
+  finished: bool,
+  last_signal: F,
+
+  delay_remaining: usize,
+  fade_gain: F,
+  fade_inc: F,
 }
 
 impl<F: Float> Lfo<F> {
-  pub fn new(sample_rate: F) -> Self {
+  /// The seed is used by the random waveforms, so every LFO can play a different sequence
+  pub fn new(sample_rate: F, seed: u32) -> Self {
     let waveform = OscWaveform::default();
     let modulo = waveform.initial_modulo();
     Lfo {
       waveform,
+      mode: Mode::FreeRunning,
       rate: F::one(),
       phase: F::zero(),
       depth: F::one(),
+      delay: F::zero(),
+      fade_in: F::zero(),
+      seed,
 
       modulo,
       phase_inc: F::zero(),
       phase_inc_invalidated: true,
+      sample_rate,
       inv_sample_rate: sample_rate.recip(),
+
+      cycle_position: F::zero(),
+      finished: false,
+      last_signal: F::zero(),
+
+      delay_remaining: 0,
+      fade_gain: F::one(),
+      fade_inc: F::one(),
     }
   }
 
   /// Set the waveform
   pub fn set_waveform(&mut self, waveform: OscWaveform<F>) {
     self.waveform = waveform;
+    self.waveform.set_seed(self.seed);
+    self.waveform.reset();
     self.reset_modulo();
     // FIXME figure out how to avoid clips after changing the waveform and the module
   }
 
+  /// Set the mode
+  pub fn set_mode(&mut self, mode: Mode) {
+    self.mode = mode;
+    self.finished = false;
+  }
+
   /// Set the rate
   pub fn set_rate(&mut self, rate: F) {
     self.rate = rate;
     self.phase_inc_invalidated = true;
   }
 
-  /// Set the phase
+  /// Set the start phase between [0.0, 1.0). It takes effect immediately.
   pub fn set_phase(&mut self, phase: F) {
+    self.modulo = clamp_modulo(self.modulo + phase - self.phase);
     self.phase = phase;
   }
 
@@ -59,29 +103,259 @@ impl<F: Float> Lfo<F> {
     self.depth = depth;
   }
 
+  /// Set the time in seconds from the trigger until the fade-in starts
+  pub fn set_delay(&mut self, delay: F) {
+    self.delay = delay.max(F::zero());
+  }
+
+  /// Set the time in seconds that takes the depth to go from zero to its full value after the delay
+  pub fn set_fade_in(&mut self, fade_in: F) {
+    self.fade_in = fade_in.max(F::zero());
+    self.fade_inc = Self::fade_inc(self.fade_in, self.sample_rate);
+  }
+
   /// Set the sample rate
   pub fn set_sample_rate(&mut self, sample_rate: F) {
+    self.sample_rate = sample_rate;
     self.inv_sample_rate = sample_rate.recip();
+    self.fade_inc = Self::fade_inc(self.fade_in, sample_rate);
     self.phase_inc_invalidated = true;
   }
 
+  /// Set the position within the cycle between [0.0, 1.0), not including the start phase.
+  /// Useful to keep the LFO locked to an external clock.
+  pub fn set_position(&mut self, position: F) {
+    let modulo = clamp_modulo(self.waveform.initial_modulo() + self.phase);
+    self.modulo = clamp_modulo(modulo + position - position.floor());
+  }
+
   /// Reset the LFO
   pub fn reset(&mut self) {
     self.reset_modulo();
+    self.cycle_position = F::zero();
+    self.finished = false;
+    self.delay_remaining = 0;
+    self.fade_gain = F::one();
+  }
+
+  /// Notify the start of a note. Depending on the mode it will restart the phase,
+  /// and it will always restart the delay and fade-in.
+  pub fn trigger(&mut self) {
+    match self.mode {
+      Mode::FreeRunning => {}
+      Mode::Retrigger | Mode::OneShot => {
+        self.reset_modulo();
+        self.cycle_position = F::zero();
+        self.finished = false;
+      }
+    }
+
+    self.delay_remaining = (self.delay * self.sample_rate)
+      .round()
+      .to_usize()
+      .unwrap_or(0);
+    self.fade_gain = if self.delay_remaining > 0 || self.fade_in > F::zero() {
+      F::zero()
+    } else {
+      F::one()
+    };
   }
 
   /// Generate the next value
   pub fn generate(&mut self) -> F {
     if self.phase_inc_invalidated {
+      self.phase_inc_invalidated = false;
       self.phase_inc = self.rate * self.inv_sample_rate;
     }
 
-    let signal = self.waveform.generate(self.modulo, self.phase_inc);
-    self.modulo = clamp_modulo(self.modulo + self.phase_inc);
-    signal * self.depth
+    let signal = if self.finished {
+      self.last_signal
+    } else {
+      let signal = self.waveform.generate(self.modulo, self.phase_inc);
+      self.modulo = clamp_modulo(self.modulo + self.phase_inc);
+      if let Mode::OneShot = self.mode {
+        self.cycle_position = self.cycle_position + self.phase_inc;
+        self.finished = self.cycle_position >= F::one();
+      }
+      self.last_signal = signal;
+      signal
+    };
+
+    signal * self.depth * self.next_fade_gain()
+  }
+
+  fn next_fade_gain(&mut self) -> F {
+    if self.delay_remaining > 0 {
+      self.delay_remaining -= 1;
+    } else if self.fade_gain < F::one() {
+      self.fade_gain = (self.fade_gain + self.fade_inc).min(F::one());
+    }
+    self.fade_gain
   }
 
   fn reset_modulo(&mut self) {
     self.modulo = clamp_modulo(self.waveform.initial_modulo() + self.phase);
   }
+
+  fn fade_inc(fade_in: F, sample_rate: F) -> F {
+    if fade_in > F::zero() {
+      (fade_in * sample_rate).recip()
+    } else {
+      F::one()
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::waveforms::sample_and_hold::SampleAndHold;
+  use crate::waveforms::smooth_random::SmoothRandom;
+  use assert_approx_eq::assert_approx_eq;
+
+  const SAMPLE_RATE: f64 = 100.0;
+
+  /// An LFO doing a cycle every second, so a cycle takes 100 samples
+  fn lfo(mode: Mode) -> Lfo<f64> {
+    let mut lfo = Lfo::new(SAMPLE_RATE, 1);
+    lfo.set_mode(mode);
+    lfo.set_rate(1.0);
+    lfo
+  }
+
+  fn generate(lfo: &mut Lfo<f64>, samples: usize) -> Vec<f64> {
+    (0..samples).map(|_| lfo.generate()).collect()
+  }
+
+  #[test]
+  fn free_running_ignores_the_triggers() {
+    let mut lfo = lfo(Mode::FreeRunning);
+    generate(&mut lfo, 30);
+    let mut expected = lfo.clone();
+
+    lfo.trigger();
+    assert_eq!(generate(&mut lfo, 100), generate(&mut expected, 100));
+  }
+
+  #[test]
+  fn retrigger_restarts_the_phase() {
+    let mut lfo = lfo(Mode::Retrigger);
+    let first_cycle = generate(&mut lfo, 100);
+    generate(&mut lfo, 30);
+
+    lfo.trigger();
+    assert_eq!(generate(&mut lfo, 100), first_cycle);
+  }
+
+  #[test]
+  fn one_shot_stops_after_one_cycle() {
+    let mut lfo = lfo(Mode::OneShot);
+    let first_cycle = generate(&mut lfo, 100);
+    let last = *first_cycle.last().unwrap();
+    for value in generate(&mut lfo, 50) {
+      assert_approx_eq!(value, last);
+    }
+
+    lfo.trigger();
+    assert_eq!(generate(&mut lfo, 100), first_cycle);
+  }
+
+  #[test]
+  fn delay_and_fade_in_after_the_trigger() {
+    let mut expected = lfo(Mode::Retrigger);
+    expected.trigger();
+    let expected = generate(&mut expected, 100);
+
+    let mut lfo = lfo(Mode::Retrigger);
+    lfo.set_delay(0.1);
+    lfo.set_fade_in(0.1);
+    lfo.trigger();
+    let values = generate(&mut lfo, 100);
+
+    // silent for 10 samples, then the depth grows during 10 samples
+    for value in values[..10].iter() {
+      assert_approx_eq!(*value, 0.0);
+    }
+    for index in 10..19 {
+      let gain = (index - 9) as f64 / 10.0;
+      assert_approx_eq!(values[index], expected[index] * gain);
+    }
+    for index in 19..100 {
+      assert_approx_eq!(values[index], expected[index]);
+    }
+
+    // the delay restarts on every trigger, even for the free running mode
+    let mut lfo = lfo.clone();
+    lfo.set_mode(Mode::FreeRunning);
+    lfo.trigger();
+    assert_approx_eq!(lfo.generate(), 0.0);
+  }
+
+  #[test]
+  fn set_phase_takes_effect_immediately() {
+    let mut expected = lfo(Mode::Retrigger);
+    let cycle = generate(&mut expected, 100);
+
+    let mut lfo = lfo(Mode::Retrigger);
+    lfo.set_phase(0.25);
+    assert_approx_eq!(lfo.generate(), cycle[25]);
+
+    // a running LFO jumps to the new phase
+    generate(&mut lfo, 9);
+    lfo.set_phase(0.5);
+    assert_approx_eq!(lfo.generate(), cycle[60]);
+
+    // and the triggers restart from it
+    lfo.trigger();
+    assert_approx_eq!(lfo.generate(), cycle[50]);
+  }
+
+  #[test]
+  fn sample_and_hold_waveform() {
+    let mut lfo = lfo(Mode::FreeRunning);
+    lfo.set_rate(12.5);
+    lfo.set_waveform(OscWaveform::SampleAndHold(SampleAndHold::new()));
+    let values = generate(&mut lfo, 96);
+
+    // a new value is held during every cycle of 8 samples
+    for cycle in values.chunks(8) {
+      assert!(cycle[0] >= -1.0 && cycle[0] <= 1.0);
+      for value in cycle.iter() {
+        assert_approx_eq!(*value, cycle[0]);
+      }
+    }
+    let changes = values
+      .chunks(8)
+      .zip(values.chunks(8).skip(1))
+      .filter(|(current, next)| (current[0] - next[0]).abs() > 1e-6)
+      .count();
+    assert!(changes > 0);
+
+    // the LFOs with the same seed play the same sequence, and a different one otherwise
+    let mut same_seed = Lfo::new(SAMPLE_RATE, 1);
+    same_seed.set_rate(12.5);
+    same_seed.set_waveform(OscWaveform::SampleAndHold(SampleAndHold::new()));
+    assert_eq!(generate(&mut same_seed, 96), values);
+
+    let mut other_seed = Lfo::new(SAMPLE_RATE, 2);
+    other_seed.set_rate(12.5);
+    other_seed.set_waveform(OscWaveform::SampleAndHold(SampleAndHold::new()));
+    assert_ne!(generate(&mut other_seed, 96), values);
+  }
+
+  #[test]
+  fn smooth_random_waveform() {
+    let mut lfo = lfo(Mode::FreeRunning);
+    lfo.set_rate(10.0);
+    lfo.set_waveform(OscWaveform::SmoothRandom(SmoothRandom::new()));
+    let values = generate(&mut lfo, 1000);
+
+    // the smoothstep changes at most 1.5 times the difference between the random values per cycle
+    let max_change = 1.5 * 2.0 * 10.0 / SAMPLE_RATE;
+    for pair in values.windows(2) {
+      assert!(pair[0] >= -1.0 && pair[0] <= 1.0);
+      assert!((pair[1] - pair[0]).abs() <= max_change);
+    }
+    assert!(values.iter().any(|value| (value - values[0]).abs() > 0.1));
+  }
 }
@@ -1,5 +1,6 @@
 use crate::float::Float;
 
+pub mod karplus_strong;
 pub mod lfo;
 pub mod osc_freq_linear_mod;
 pub mod osc_pitch_shift;
@@ -4,7 +4,7 @@ use crate::float::Float;
 /// - A number of octaves
 /// - A number of semitones
 /// - A number of cents of semitone
-/// - The pitch bend
+/// - The pitch bend, scaled by the bend up or down range
 /// - The frequency modulation
 ///
 #[derive(Debug)]
@@ -15,8 +15,12 @@ pub struct OscPitchShift<F: Float> {
   semitones_shift: F,
   /// shift in semitones for the cents
   cents_shift: F,
-  /// pitch bend
+  /// pitch bend from -1.0 to 1.0
   pitch_bend: F,
+  /// range in semitones for a positive pitch bend
+  pitch_bend_up: F,
+  /// range in semitones for a negative pitch bend
+  pitch_bend_down: F,
   /// frequency modulation (exponential)
   modulation: F,
 }
@@ -31,6 +35,8 @@ where
       semitones_shift: F::zero(),
       cents_shift: F::zero(),
       pitch_bend: F::zero(),
+      pitch_bend_up: F::one(),
+      pitch_bend_down: F::one(),
       modulation: F::zero(),
     }
   }
@@ -60,6 +66,16 @@ where
     self.pitch_bend = pitch_bend;
   }
 
+  /// Set the range in semitones for a positive pitch bend
+  pub fn set_pitch_bend_up(&mut self, semitones: F) {
+    self.pitch_bend_up = semitones;
+  }
+
+  /// Set the range in semitones for a negative pitch bend
+  pub fn set_pitch_bend_down(&mut self, semitones: F) {
+    self.pitch_bend_down = semitones;
+  }
+
   /// Set the frequency modulation
   pub fn set_modulation(&mut self, modulation: F) {
     self.modulation = modulation;
@@ -67,10 +83,16 @@ where
 
   /// The multiplier for the configured pitch shift
   pub fn multiplier(&self) -> F {
+    let pitch_bend_range = if self.pitch_bend > F::zero() {
+      self.pitch_bend_up
+    } else {
+      self.pitch_bend_down
+    };
+
     let total_semitones_shift = self.octaves_shift
       + self.semitones_shift
       + self.cents_shift
-      + self.pitch_bend
+      + self.pitch_bend * pitch_bend_range
       + self.modulation;
 
     F::from(2.0)
@@ -78,3 +100,46 @@ where
       .powf(total_semitones_shift / F::val(12.0))
   }
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use assert_approx_eq::assert_approx_eq;
+
+  #[test]
+  fn pitch_bend_default_range_is_one_semitone() {
+    let mut shift = OscPitchShift::<f64>::default();
+    shift.set_pitch_bend(1.0);
+    assert_approx_eq!(shift.multiplier(), 2.0f64.powf(1.0 / 12.0));
+    shift.set_pitch_bend(-1.0);
+    assert_approx_eq!(shift.multiplier(), 2.0f64.powf(-1.0 / 12.0));
+  }
+
+  #[test]
+  fn pitch_bend_up_and_down_ranges() {
+    let mut shift = OscPitchShift::<f64>::default();
+    shift.set_pitch_bend_up(12.0);
+    shift.set_pitch_bend_down(24.0);
+
+    shift.set_pitch_bend(1.0);
+    assert_approx_eq!(shift.multiplier(), 2.0);
+    shift.set_pitch_bend(0.5);
+    assert_approx_eq!(shift.multiplier(), 2.0f64.powf(0.5));
+    shift.set_pitch_bend(-1.0);
+    assert_approx_eq!(shift.multiplier(), 0.25);
+    shift.set_pitch_bend(-0.5);
+    assert_approx_eq!(shift.multiplier(), 0.5);
+    shift.set_pitch_bend(0.0);
+    assert_approx_eq!(shift.multiplier(), 1.0);
+  }
+
+  #[test]
+  fn pitch_bend_adds_to_the_other_shifts() {
+    let mut shift = OscPitchShift::<f64>::default();
+    shift.set_octaves(1.0);
+    shift.set_semitones(-12.0);
+    shift.set_pitch_bend_down(7.0);
+    shift.set_pitch_bend(-1.0);
+    assert_approx_eq!(shift.multiplier(), 2.0f64.powf(-7.0 / 12.0));
+  }
+}
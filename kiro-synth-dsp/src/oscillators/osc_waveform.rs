@@ -1,7 +1,9 @@
 use crate::float::Float;
+use crate::waveforms::sample_and_hold::SampleAndHold;
 use crate::waveforms::saw_blep::SawBlep;
 use crate::waveforms::saw_trivial::SawTrivial;
 use crate::waveforms::sine_parabolic::SineParabolic;
+use crate::waveforms::smooth_random::SmoothRandom;
 use crate::waveforms::triangle_dpw2x::TriangleDpw2x;
 use crate::waveforms::triangle_trivial::TriangleTrivial;
 use crate::waveforms::Waveform;
@@ -13,6 +15,8 @@ pub enum OscWaveform<F: Float> {
   SawBlep(SawBlep<F>),
   TriangleTrivial(TriangleTrivial),
   TriangleDpw2x(TriangleDpw2x<F>),
+  SampleAndHold(SampleAndHold<F>),
+  SmoothRandom(SmoothRandom<F>),
 }
 
 impl<F: Float> Default for OscWaveform<F> {
@@ -29,6 +33,29 @@ impl<F: Float> OscWaveform<F> {
       OscWaveform::SawBlep(wf) => wf.initial_modulo(),
       OscWaveform::TriangleTrivial(wf) => wf.initial_modulo(),
       OscWaveform::TriangleDpw2x(wf) => wf.initial_modulo(),
+      OscWaveform::SampleAndHold(wf) => wf.initial_modulo(),
+      OscWaveform::SmoothRandom(wf) => wf.initial_modulo(),
+    }
+  }
+
+  pub fn reset(&mut self) {
+    match self {
+      OscWaveform::SineParabolic(wf) => Waveform::<F>::reset(wf),
+      OscWaveform::SawTrivial(wf) => Waveform::<F>::reset(wf),
+      OscWaveform::SawBlep(wf) => wf.reset(),
+      OscWaveform::TriangleTrivial(wf) => Waveform::<F>::reset(wf),
+      OscWaveform::TriangleDpw2x(wf) => wf.reset(),
+      OscWaveform::SampleAndHold(wf) => wf.reset(),
+      OscWaveform::SmoothRandom(wf) => wf.reset(),
+    }
+  }
+
+  /// Set the seed of the random waveforms, taken on the next reset
+  pub fn set_seed(&mut self, seed: u32) {
+    match self {
+      OscWaveform::SampleAndHold(wf) => wf.set_seed(seed),
+      OscWaveform::SmoothRandom(wf) => wf.set_seed(seed),
+      _ => {}
     }
   }
 
@@ -39,6 +66,8 @@ impl<F: Float> OscWaveform<F> {
       OscWaveform::SawBlep(wf) => wf.generate(modulo, phase_inc),
       OscWaveform::TriangleTrivial(wf) => wf.generate(modulo, phase_inc),
       OscWaveform::TriangleDpw2x(wf) => wf.generate(modulo, phase_inc),
+      OscWaveform::SampleAndHold(wf) => wf.generate(modulo, phase_inc),
+      OscWaveform::SmoothRandom(wf) => wf.generate(modulo, phase_inc),
     }
   }
 }
@@ -75,6 +75,18 @@ impl<F: Float> PitchedOscillator<F> {
     self.phase_inc_invalidated = true;
   }
 
+  /// Set the range in semitones for a positive pitch bend
+  pub fn set_pitch_bend_up(&mut self, semitones: F) {
+    self.pitch_shift.set_pitch_bend_up(semitones);
+    self.phase_inc_invalidated = true;
+  }
+
+  /// Set the range in semitones for a negative pitch bend
+  pub fn set_pitch_bend_down(&mut self, semitones: F) {
+    self.pitch_shift.set_pitch_bend_down(semitones);
+    self.phase_inc_invalidated = true;
+  }
+
   /// Set the frequency modulation
   pub fn set_frequency_modulation(&mut self, freq_mod: F) {
     self.pitch_shift.set_modulation(freq_mod);
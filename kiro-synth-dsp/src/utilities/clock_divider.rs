@@ -0,0 +1,72 @@
+use crate::float::Float;
+use crate::utilities::edge_detector::EdgeDetector;
+
+/// Lets through one of every N pulses of a clock or trigger signal.
+/// The output is 1.0 while a pulse that passes is high, and 0.0 otherwise.
+#[derive(Debug, Clone)]
+pub struct ClockDivider<F: Float> {
+  clock: EdgeDetector<F>,
+  division: usize,
+  count: usize,
+  open: bool,
+}
+
+impl<F: Float> Default for ClockDivider<F> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<F: Float> ClockDivider<F> {
+  pub fn new() -> Self {
+    ClockDivider {
+      clock: EdgeDetector::new(),
+      division: 1,
+      count: 0,
+      open: false,
+    }
+  }
+
+  /// Number of input pulses for every output pulse, from 1
+  pub fn set_division(&mut self, division: usize) {
+    self.division = division.max(1);
+    self.count %= self.division;
+  }
+
+  pub fn reset(&mut self) {
+    self.clock.reset();
+    self.count = 0;
+    self.open = false;
+  }
+
+  pub fn process(&mut self, input: F) -> F {
+    if self.clock.process(input) {
+      self.open = self.count == 0;
+      self.count = (self.count + 1) % self.division;
+    }
+
+    if self.open && input > F::zero() {
+      F::one()
+    } else {
+      F::zero()
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use assert_approx_eq::assert_approx_eq;
+
+  #[test]
+  fn clock_divider_by_three() {
+    let mut divider = ClockDivider::<f64>::new();
+    divider.set_division(3);
+
+    let clock = [1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0];
+    let expected = [1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0];
+    for (input, output) in clock.iter().zip(expected.iter()) {
+      assert_approx_eq!(divider.process(*input), *output);
+    }
+  }
+}
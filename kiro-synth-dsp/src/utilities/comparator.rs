@@ -0,0 +1,64 @@
+use crate::float::Float;
+
+/// Outputs 1.0 when the input is above the threshold and 0.0 otherwise.
+/// The hysteresis avoids chattering when a noisy input stays close to the threshold:
+/// the output goes high above `threshold + hysteresis` and low below `threshold - hysteresis`.
+#[derive(Debug, Clone)]
+pub struct Comparator<F: Float> {
+  hysteresis: F,
+  high: bool,
+}
+
+impl<F: Float> Default for Comparator<F> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<F: Float> Comparator<F> {
+  pub fn new() -> Self {
+    Comparator {
+      hysteresis: F::zero(),
+      high: false,
+    }
+  }
+
+  pub fn set_hysteresis(&mut self, hysteresis: F) {
+    self.hysteresis = hysteresis.abs();
+  }
+
+  pub fn reset(&mut self) {
+    self.high = false;
+  }
+
+  pub fn process(&mut self, input: F, threshold: F) -> F {
+    if self.high {
+      self.high = input >= threshold - self.hysteresis;
+    } else {
+      self.high = input > threshold + self.hysteresis;
+    }
+
+    if self.high {
+      F::one()
+    } else {
+      F::zero()
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use assert_approx_eq::assert_approx_eq;
+
+  #[test]
+  fn comparator_hysteresis() {
+    let mut comparator = Comparator::<f64>::new();
+    comparator.set_hysteresis(0.1);
+
+    assert_approx_eq!(comparator.process(0.05, 0.0), 0.0);
+    assert_approx_eq!(comparator.process(0.15, 0.0), 1.0);
+    assert_approx_eq!(comparator.process(-0.05, 0.0), 1.0);
+    assert_approx_eq!(comparator.process(-0.15, 0.0), 0.0);
+  }
+}
@@ -0,0 +1,25 @@
+use crate::float::Float;
+
+/// Detects when a signal goes from zero or below to above zero,
+/// so it works both with gates (0.0 to 1.0) and with bipolar signals.
+#[derive(Debug, Clone, Default)]
+pub struct EdgeDetector<F: Float> {
+  last: F,
+}
+
+impl<F: Float> EdgeDetector<F> {
+  pub fn new() -> Self {
+    EdgeDetector { last: F::zero() }
+  }
+
+  pub fn reset(&mut self) {
+    self.last = F::zero();
+  }
+
+  /// Returns true on the rising edges
+  pub fn process(&mut self, input: F) -> bool {
+    let rising = self.last <= F::zero() && input > F::zero();
+    self.last = input;
+    rising
+  }
+}
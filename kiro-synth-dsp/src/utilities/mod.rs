@@ -0,0 +1,5 @@
+pub mod clock_divider;
+pub mod comparator;
+pub mod edge_detector;
+pub mod sample_hold;
+pub mod slew_limiter;
@@ -0,0 +1,46 @@
+use crate::float::Float;
+use crate::utilities::edge_detector::EdgeDetector;
+
+/// Samples the input on the rising edges of the trigger, and holds it until the next one
+#[derive(Debug, Clone, Default)]
+pub struct SampleHold<F: Float> {
+  trigger: EdgeDetector<F>,
+  value: F,
+}
+
+impl<F: Float> SampleHold<F> {
+  pub fn new() -> Self {
+    SampleHold {
+      trigger: EdgeDetector::new(),
+      value: F::zero(),
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.trigger.reset();
+    self.value = F::zero();
+  }
+
+  pub fn process(&mut self, input: F, trigger: F) -> F {
+    if self.trigger.process(trigger) {
+      self.value = input;
+    }
+    self.value
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use assert_approx_eq::assert_approx_eq;
+
+  #[test]
+  fn sample_hold_on_rising_edges() {
+    let mut sample_hold = SampleHold::<f64>::new();
+    assert_approx_eq!(sample_hold.process(0.3, 0.0), 0.0);
+    assert_approx_eq!(sample_hold.process(0.4, 1.0), 0.4);
+    assert_approx_eq!(sample_hold.process(0.5, 1.0), 0.4);
+    assert_approx_eq!(sample_hold.process(0.6, -1.0), 0.4);
+    assert_approx_eq!(sample_hold.process(0.7, 0.5), 0.7);
+  }
+}
@@ -0,0 +1,78 @@
+use crate::float::Float;
+
+/// Limits how fast a signal can change, with different times for rising and falling.
+/// The times are the seconds needed to change by 1.0.
+#[derive(Debug, Clone)]
+pub struct SlewLimiter<F: Float> {
+  sample_rate: F,
+  rise_step: F,
+  fall_step: F,
+  value: F,
+}
+
+impl<F: Float> SlewLimiter<F> {
+  pub fn new(sample_rate: F) -> Self {
+    SlewLimiter {
+      sample_rate,
+      rise_step: F::infinity(),
+      fall_step: F::infinity(),
+      value: F::zero(),
+    }
+  }
+
+  /// Rise time in seconds
+  pub fn set_rise(&mut self, time: F) {
+    self.rise_step = Self::step(self.sample_rate, time);
+  }
+
+  /// Fall time in seconds
+  pub fn set_fall(&mut self, time: F) {
+    self.fall_step = Self::step(self.sample_rate, time);
+  }
+
+  pub fn reset(&mut self, value: F) {
+    self.value = value;
+  }
+
+  pub fn process(&mut self, input: F) -> F {
+    let delta = input - self.value;
+    self.value = if delta > F::zero() {
+      self.value + delta.min(self.rise_step)
+    } else {
+      self.value + delta.max(self.fall_step.neg())
+    };
+    self.value
+  }
+
+  fn step(sample_rate: F, time: F) -> F {
+    let samples = time * sample_rate;
+    if samples > F::zero() {
+      samples.recip()
+    } else {
+      F::infinity()
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use assert_approx_eq::assert_approx_eq;
+
+  #[test]
+  fn slew_limiter_rise_and_fall() {
+    let mut slew = SlewLimiter::<f64>::new(100.0);
+    slew.set_rise(0.1);
+    slew.set_fall(0.0);
+
+    for _ in 0..5 {
+      slew.process(1.0);
+    }
+    assert_approx_eq!(slew.process(1.0), 0.6);
+    for _ in 0..10 {
+      slew.process(1.0);
+    }
+    assert_approx_eq!(slew.process(1.0), 1.0);
+    assert_approx_eq!(slew.process(-1.0), -1.0);
+  }
+}
@@ -0,0 +1,37 @@
+use crate::float::Float;
+use crate::funcs::random::Random;
+use crate::waveforms::Waveform;
+
+/// Holds a new random value at the beginning of every cycle
+#[derive(Debug, Clone, Default)]
+pub struct SampleAndHold<F: Float> {
+  seed: u32,
+  random: Random,
+  value: F,
+}
+
+impl<F: Float> SampleAndHold<F> {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Set the seed for the random sequence, which restarts from it on every reset
+  pub fn set_seed(&mut self, seed: u32) {
+    self.seed = seed;
+  }
+}
+
+impl<F: Float> Waveform<F> for SampleAndHold<F> {
+  fn reset(&mut self) {
+    self.random = Random::new(self.seed);
+    self.value = self.random.next_bipolar();
+  }
+
+  fn generate(&mut self, modulo: F, phase_inc: F) -> F {
+    // the modulo has just wrapped around
+    if modulo < phase_inc {
+      self.value = self.random.next_bipolar();
+    }
+    self.value
+  }
+}
@@ -0,0 +1,43 @@
+use crate::float::Float;
+use crate::funcs::random::Random;
+use crate::waveforms::Waveform;
+
+/// Moves smoothly towards a new random value on every cycle
+#[derive(Debug, Clone, Default)]
+pub struct SmoothRandom<F: Float> {
+  seed: u32,
+  random: Random,
+  from: F,
+  to: F,
+}
+
+impl<F: Float> SmoothRandom<F> {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Set the seed for the random sequence, which restarts from it on every reset
+  pub fn set_seed(&mut self, seed: u32) {
+    self.seed = seed;
+  }
+}
+
+impl<F: Float> Waveform<F> for SmoothRandom<F> {
+  fn reset(&mut self) {
+    self.random = Random::new(self.seed);
+    self.from = self.random.next_bipolar();
+    self.to = self.random.next_bipolar();
+  }
+
+  fn generate(&mut self, modulo: F, phase_inc: F) -> F {
+    // the modulo has just wrapped around
+    if modulo < phase_inc {
+      self.from = self.to;
+      self.to = self.random.next_bipolar();
+    }
+
+    // smoothstep between the two random values
+    let x = modulo * modulo * (F::val(3.0) - F::val(2.0) * modulo);
+    self.from + (self.to - self.from) * x
+  }
+}
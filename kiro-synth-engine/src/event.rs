@@ -1,16 +1,82 @@
 use crate::float::Float;
 use crate::program::{ParamRef, SourceRef};
 
+/// Expressions of a single voice, as the MPE controllers send them in the channel of every note,
+/// or as they are sent for a specific key
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Expression {
+  /// From -1.0 to 1.0, scaled by the voice pitch bend range
+  PitchBend,
+  /// From 0.0 to 1.0
+  Pressure,
+  /// From 0.0 to 1.0 (usually from the CC74)
+  Timbre,
+}
+
+pub const NUM_EXPRESSIONS: usize = 3;
+
+impl Expression {
+  pub const EXPRESSIONS: [Expression; NUM_EXPRESSIONS] = [
+    Expression::PitchBend,
+    Expression::Pressure,
+    Expression::Timbre,
+  ];
+
+  /// The value of the expression until the channel sends one
+  pub fn default_value<F: Float>(self) -> F {
+    match self {
+      Expression::PitchBend | Expression::Pressure => F::zero(),
+      Expression::Timbre => F::val(0.5),
+    }
+  }
+}
+
+/// The pedals that change how the voices are released and played
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pedal {
+  /// Keeps playing the released notes until the pedal is released (CC64)
+  Sustain,
+  /// Keeps playing the notes that were held when the pedal was pressed (CC66)
+  Sostenuto,
+  /// Plays the new notes softer (CC67)
+  Soft,
+}
+
 #[derive(Debug, Clone)]
 pub enum Message<F: Float> {
   NoteOn {
+    channel: u8,
     key: u8,
     velocity: F,
   },
   NoteOff {
+    channel: u8,
     key: u8,
     velocity: F,
   },
+  /// Changes an expression of the voices playing in a channel,
+  /// and of the notes that will play in that channel
+  ChannelExpression {
+    channel: u8,
+    expression: Expression,
+    value: F,
+  },
+  /// Changes an expression of the voices playing a key in a channel,
+  /// such as the polyphonic key pressure
+  KeyExpression {
+    channel: u8,
+    key: u8,
+    expression: Expression,
+    value: F,
+  },
+  Pedal {
+    pedal: Pedal,
+    pressed: bool,
+  },
+  /// Range in semitones of the `Expression::PitchBend`
+  VoicePitchBendRange {
+    semitones: F,
+  },
   ParamValue {
     param_ref: ParamRef,
     value: F,
@@ -28,6 +94,12 @@ pub enum Message<F: Float> {
     source_ref: SourceRef,
     param_ref: ParamRef,
   },
+  Tempo {
+    bpm: F,
+  },
+  SongPosition {
+    beats: F,
+  },
 }
 
 #[derive(Debug, Clone)]
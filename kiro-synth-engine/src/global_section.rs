@@ -0,0 +1,325 @@
+use core::ops::DerefMut;
+use heapless::Vec;
+
+use kiro_synth_dsp::funcs::random::Random;
+
+use crate::float::Float;
+use crate::globals::SynthGlobals;
+use crate::processor::Processor;
+use crate::program::{
+  Block, MaxEffectBlocks, MaxEffectParams, MaxGlobalBlocks, MaxSignals, Program, SignalRef,
+};
+use crate::signal::{Signal, SignalBus};
+use crate::tuning::KeyFrequencies;
+
+/// Processes the global blocks of the program once per sample,
+/// so all the voices share the same state for them (ex. global LFOs).
+/// It also processes the effects chain for the mix of all the voices.
+pub struct GlobalSection<F: Float> {
+  signals: Vec<Signal<F>, MaxSignals>,
+  processors: Vec<Processor<F>, MaxGlobalBlocks>,
+  effect_param_processors: Vec<Processor<F>, MaxEffectParams>,
+  effect_processors: Vec<Processor<F>, MaxEffectBlocks>,
+}
+
+impl<F: Float> GlobalSection<F> {
+  /// The seeds for the blocks that generate random values are taken from `seeds`
+  pub(crate) fn new(sample_rate: F, program: &Program<F>, seeds: &mut Random) -> Self {
+    let mut signals: Vec<Signal<F>, MaxSignals> = Vec::new();
+    for _ in 0..program.get_signals_count() {
+      signals.push(Signal::default()).unwrap();
+    }
+
+    for block in program.get_blocks().iter() {
+      if let Block::Const { value, signal } = block {
+        signals[signal.0].set(*value)
+      }
+    }
+
+    let mut processors: Vec<Processor<F>, MaxGlobalBlocks> = Vec::new();
+    for block in program.get_global_blocks().iter() {
+      if let Block::Const { value, signal } = block {
+        signals[signal.0].set(*value)
+      } else {
+        let processor = Processor::new(sample_rate, block, seeds.next_u32());
+        processors.push(processor).unwrap();
+      }
+    }
+
+    let mut effect_param_processors: Vec<Processor<F>, MaxEffectParams> = Vec::new();
+    for param in program.get_effect_params().iter() {
+      let block = Block::Param(param.clone());
+      effect_param_processors
+        .push(Processor::new(sample_rate, &block, seeds.next_u32()))
+        .unwrap();
+    }
+
+    let mut effect_processors: Vec<Processor<F>, MaxEffectBlocks> = Vec::new();
+    for block in program.get_effect_blocks().iter() {
+      effect_processors
+        .push(Processor::new(sample_rate, block, seeds.next_u32()))
+        .unwrap();
+    }
+
+    GlobalSection {
+      signals,
+      processors,
+      effect_param_processors,
+      effect_processors,
+    }
+  }
+
+  pub fn get_signals(&self) -> &[Signal<F>] {
+    self.signals.as_ref()
+  }
+
+  pub(crate) fn get_signal(&self, signal_ref: SignalRef) -> F {
+    self.signals[signal_ref.0].get()
+  }
+
+  /// The global section sees the last note played
+  pub(crate) fn note_on(
+    &mut self,
+    program: &Program<F>,
+    tuning: &KeyFrequencies,
+    key: u8,
+    velocity: F,
+  ) {
+    let voice = program.voice();
+    self.signals[voice.key.0].set(F::val(key));
+    self.signals[voice.velocity.0].set(velocity);
+    if let Some(frequency) = tuning.frequency(key) {
+      self.signals[voice.note_pitch.0].set(F::val(frequency));
+    }
+    self.signals[voice.gate.0].set(F::one());
+    self.signals[voice.trigger.0].set(F::one());
+  }
+
+  pub(crate) fn retune(&mut self, program: &Program<F>, tuning: &KeyFrequencies) {
+    let voice = program.voice();
+    if let Some(frequency) = self.signals[voice.key.0]
+      .get()
+      .to_u8()
+      .and_then(|key| tuning.frequency(key))
+    {
+      self.signals[voice.note_pitch.0].set(F::val(frequency));
+    }
+  }
+
+  /// Called when there are no more notes held
+  pub(crate) fn note_off(&mut self, program: &Program<F>) {
+    self.signals[program.voice().gate.0].set(F::zero());
+  }
+
+  /// Process the global blocks before the voices
+  pub(crate) fn process(&mut self, program: &mut Program<F>, synth_globals: &SynthGlobals<F>) {
+    let mut signals = SignalBus::new(self.signals.deref_mut());
+
+    for processor in self.processors.iter_mut() {
+      processor.process(&mut signals, program, synth_globals)
+    }
+  }
+
+  /// Process the effects chain for the mix of all the voices, and return its output
+  pub(crate) fn process_effects(
+    &mut self,
+    program: &mut Program<F>,
+    synth_globals: &SynthGlobals<F>,
+    left: F,
+    right: F,
+  ) -> (F, F) {
+    let mut signals = SignalBus::new(self.signals.deref_mut());
+
+    let effects = program.effects().clone();
+    signals[effects.input_left].set(left);
+    signals[effects.input_right].set(right);
+
+    for processor in self.effect_param_processors.iter_mut() {
+      processor.process(&mut signals, program, synth_globals)
+    }
+
+    let (mut left, mut right) = (left, right);
+    for (index, processor) in self.effect_processors.iter_mut().enumerate() {
+      let slot = program.get_effect_slots()[index];
+      signals[slot.input_left].set(left);
+      signals[slot.input_right].set(right);
+
+      // a bypassed effect starts again from silence when it is enabled
+      signals[slot.enabled].if_updated(|enabled| {
+        if enabled <= F::val(0.5) {
+          processor.reset()
+        }
+      });
+
+      if signals[slot.enabled].get() > F::val(0.5) {
+        processor.process(&mut signals, program, synth_globals);
+        left = signals[slot.output_left].get();
+        right = signals[slot.output_right].get();
+      } else {
+        signals[slot.output_left].set(left);
+        signals[slot.output_right].set(right);
+      }
+    }
+
+    signals[effects.output_left].set(left);
+    signals[effects.output_right].set(right);
+    let output = (left, right);
+
+    signals.update();
+
+    // The trigger does an spike of 1 sample
+    let voice = program.voice();
+    if signals[voice.trigger].get() > F::zero() {
+      signals[voice.trigger].set(F::zero())
+    }
+
+    output
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::program::{ParamBlock, ParamValues, ProgramBuilder};
+  use crate::voice::Voice;
+  use assert_approx_eq::assert_approx_eq;
+
+  const SAMPLE_RATE: f64 = 48000.0;
+
+  struct TestProgram {
+    program: Program<'static, f64>,
+    global_param: ParamBlock,
+    global_output: SignalRef,
+    effect_param: ParamBlock,
+    effect_enabled: ParamBlock,
+  }
+
+  fn values(initial_value: f64) -> ParamValues<f64> {
+    ParamValues {
+      initial_value,
+      origin: 0.0,
+      min: 0.0,
+      max: 1.0,
+      resolution: 0.01,
+    }
+  }
+
+  /// A global param doubled by a global block, and two effects chained:
+  /// one that scales the left channel by a param, and one that inverts the right channel
+  fn test_program() -> TestProgram {
+    let mut builder = ProgramBuilder::new();
+
+    let global_param = builder.param("global", values(0.25));
+    builder.global_block(Block::Param(global_param.clone()));
+    let mut double = builder.expr(|expr| expr.mul_signal_value(global_param.out_signal_ref, 2.0));
+    double.output = builder.global_signal();
+    let global_output = double.output;
+    builder.global_block(Block::Expr(double));
+
+    let effect_param = builder.param("level", values(0.5));
+    builder.effect_param(effect_param.clone());
+    let effect_enabled = builder.param("enabled", values(1.0));
+    builder.effect_param(effect_enabled.clone());
+
+    let level = effect_param.out_signal_ref;
+    let scale_output = builder.signal();
+    let mut scale_slot =
+      builder.effect_slot(effect_enabled.out_signal_ref, scale_output, scale_output);
+    scale_slot.output_right = scale_slot.input_right;
+    let mut scale = builder.expr(|expr| expr.mul_signals(scale_slot.input_left, level));
+    scale.output = scale_output;
+    builder.effect(scale_slot, Block::Expr(scale));
+
+    let always = builder.const_one();
+    let invert_output = builder.signal();
+    let mut invert_slot = builder.effect_slot(always, invert_output, invert_output);
+    invert_slot.output_left = invert_slot.input_left;
+    let mut invert = builder.expr(|expr| expr.mul_signal_value(invert_slot.input_right, -1.0));
+    invert.output = invert_output;
+    builder.effect(invert_slot, Block::Expr(invert));
+
+    TestProgram {
+      program: builder.build(),
+      global_param,
+      global_output,
+      effect_param,
+      effect_enabled,
+    }
+  }
+
+  #[test]
+  fn global_signals_are_visible_from_voices() {
+    let TestProgram {
+      mut program,
+      global_param,
+      global_output,
+      effect_param,
+      ..
+    } = test_program();
+    let globals = SynthGlobals::new();
+    let mut seeds = Random::default();
+    let mut global_section = GlobalSection::new(SAMPLE_RATE, &program, &mut seeds);
+    let mut voice = Voice::new(SAMPLE_RATE, &program, &mut seeds);
+
+    global_section.process(&mut program, &globals);
+    global_section.process_effects(&mut program, &globals, 0.0, 0.0);
+    voice.process(&mut program, &globals, &global_section);
+
+    let signals = voice.get_signals();
+    assert_approx_eq!(signals[global_param.out_signal_ref.0].get(), 0.25);
+    assert_approx_eq!(signals[global_output.0].get(), 0.5);
+
+    // the effect params stay in the global section
+    assert!(!program
+      .get_global_signals()
+      .contains(&effect_param.out_signal_ref));
+    assert_approx_eq!(global_section.get_signal(effect_param.out_signal_ref), 0.5);
+    assert_approx_eq!(signals[effect_param.out_signal_ref.0].get(), 0.0);
+  }
+
+  #[test]
+  fn effects_process_the_mix_of_the_voices() {
+    let TestProgram {
+      mut program,
+      effect_param,
+      ..
+    } = test_program();
+    let globals = SynthGlobals::new();
+    let mut global_section = GlobalSection::new(SAMPLE_RATE, &program, &mut Random::default());
+
+    let (left, right) = global_section.process_effects(&mut program, &globals, 0.8, -0.4);
+    assert_approx_eq!(left, 0.4);
+    assert_approx_eq!(right, 0.4);
+
+    if let Some((_, param)) = program.get_param_mut(effect_param.reference) {
+      param.value.set(0.25);
+    }
+    let (left, _) = global_section.process_effects(&mut program, &globals, 0.8, -0.4);
+    assert_approx_eq!(left, 0.2);
+  }
+
+  #[test]
+  fn disabled_effects_are_bypassed() {
+    let TestProgram {
+      mut program,
+      effect_enabled,
+      ..
+    } = test_program();
+    let globals = SynthGlobals::new();
+    let mut global_section = GlobalSection::new(SAMPLE_RATE, &program, &mut Random::default());
+
+    if let Some((_, param)) = program.get_param_mut(effect_enabled.reference) {
+      param.value.set(0.0);
+    }
+    let (left, right) = global_section.process_effects(&mut program, &globals, 0.8, -0.4);
+    assert_approx_eq!(left, 0.8);
+    assert_approx_eq!(right, 0.4);
+
+    if let Some((_, param)) = program.get_param_mut(effect_enabled.reference) {
+      param.value.set(1.0);
+    }
+    let (left, right) = global_section.process_effects(&mut program, &globals, 0.8, -0.4);
+    assert_approx_eq!(left, 0.4);
+    assert_approx_eq!(right, 0.4);
+  }
+}
@@ -1,11 +1,15 @@
 use kiro_synth_dsp::float::Float;
 
+use crate::transport::Transport;
+use crate::tuning::KeyFrequencies;
 use crate::waveforms::{LfoWaveforms, OscWaveforms};
 
 #[derive(Debug, Clone, Default)]
 pub struct SynthGlobals<F: Float> {
   pub osc_waveforms: OscWaveforms<F>,
   pub lfo_waveforms: LfoWaveforms<F>,
+  pub transport: Transport,
+  pub tuning: KeyFrequencies,
 }
 
 impl<F: Float> SynthGlobals<F> {
@@ -13,6 +17,8 @@ impl<F: Float> SynthGlobals<F> {
     SynthGlobals {
       osc_waveforms: OscWaveforms::new(),
       lfo_waveforms: LfoWaveforms::new(),
+      transport: Transport::new(),
+      tuning: KeyFrequencies::default(),
     }
   }
 }
@@ -5,7 +5,7 @@ extern crate hash32_derive;
 
 #[macro_use]
 mod signal;
-mod key_freqs;
+mod global_section;
 mod processor;
 mod voice;
 
@@ -13,6 +13,8 @@ pub mod event;
 pub mod globals;
 pub mod program;
 pub mod synth;
+pub mod transport;
+pub mod tuning;
 pub mod waveforms;
 
 pub use kiro_synth_dsp::float;
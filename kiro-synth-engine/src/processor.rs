@@ -8,17 +8,34 @@ use crate::signal::SignalBus;
 pub(crate) enum Processor<F: Float> {
   Const(F, SignalRef),
   Param(ParamRef),
+  ClockDivider(clock_divider::Processor<F>),
+  Comparator(comparator::Processor<F>),
+  Compressor(Box<compressor::Processor<F>>),
+  Crossfade(crossfade::Processor<F>),
   DCA(dca::Processor<F>),
+  Delay(Box<delay::Processor<F>>),
   EG(envgen::Processor<F>),
+  EnvelopeFollower(Box<envelope_follower::Processor<F>>),
+  Equalizer(Box<equalizer::Processor<F>>),
   Expr(expr::Processor<F>),
   Filter(filter::Processor<F>),
+  KarplusStrong(Box<karplus_strong::Processor<F>>),
   Lfo(lfo::Processor<F>),
+  Limiter(Box<limiter::Processor<F>>),
+  Mixer(Box<mixer::Processor<F>>),
+  ModFx(Box<modfx::Processor<F>>),
   Osc(osc::Processor<F>),
+  Reverb(Box<reverb::Processor<F>>),
+  RingMod(ringmod::Processor<F>),
+  SampleHold(sample_hold::Processor<F>),
+  SlewLimiter(slew_limiter::Processor<F>),
+  Waveshaper(waveshaper::Processor<F>),
   Out(SignalRef, SignalRef),
 }
 
 impl<F: Float> Processor<F> {
-  pub fn new(sample_rate: F, block: &Block<F>) -> Self {
+  /// The seed is taken by the blocks that generate random values
+  pub fn new(sample_rate: F, block: &Block<F>, seed: u32) -> Self {
     match block.clone() {
       Block::Const { value, signal } => Processor::Const(value, signal),
       Block::Param(ParamBlock {
@@ -26,14 +43,63 @@ impl<F: Float> Processor<F> {
         out_signal_ref: _,
         mod_signal_ref: _,
       }) => Processor::Param(reference),
+      Block::ClockDivider(clock_divider_block) => Processor::ClockDivider(
+        clock_divider::Processor::new(sample_rate, clock_divider_block),
+      ),
+      Block::Comparator(comparator_block) => {
+        Processor::Comparator(comparator::Processor::new(sample_rate, comparator_block))
+      }
+      Block::Compressor(compressor_block) => Processor::Compressor(Box::new(
+        compressor::Processor::new(sample_rate, compressor_block),
+      )),
+      Block::Crossfade(crossfade_block) => {
+        Processor::Crossfade(crossfade::Processor::new(sample_rate, crossfade_block))
+      }
       Block::DCA(dca_block) => Processor::DCA(dca::Processor::new(sample_rate, dca_block)),
+      Block::Delay(delay_block) => {
+        Processor::Delay(Box::new(delay::Processor::new(sample_rate, delay_block)))
+      }
       Block::EG(eg_block) => Processor::EG(envgen::Processor::new(sample_rate, eg_block)),
-      Block::Lfo(lfo_block) => Processor::Lfo(lfo::Processor::new(sample_rate, lfo_block)),
+      Block::EnvelopeFollower(envelope_follower_block) => Processor::EnvelopeFollower(Box::new(
+        envelope_follower::Processor::new(sample_rate, envelope_follower_block),
+      )),
+      Block::Equalizer(equalizer_block) => Processor::Equalizer(Box::new(
+        equalizer::Processor::new(sample_rate, equalizer_block),
+      )),
+      Block::KarplusStrong(karplus_strong_block) => Processor::KarplusStrong(Box::new(
+        karplus_strong::Processor::new(sample_rate, karplus_strong_block, seed),
+      )),
+      Block::Lfo(lfo_block) => Processor::Lfo(lfo::Processor::new(sample_rate, lfo_block, seed)),
+      Block::Limiter(limiter_block) => Processor::Limiter(Box::new(limiter::Processor::new(
+        sample_rate,
+        limiter_block,
+      ))),
+      Block::Mixer(mixer_block) => {
+        Processor::Mixer(Box::new(mixer::Processor::new(sample_rate, mixer_block)))
+      }
+      Block::ModFx(modfx_block) => {
+        Processor::ModFx(Box::new(modfx::Processor::new(sample_rate, modfx_block)))
+      }
       Block::Osc(osc_block) => Processor::Osc(osc::Processor::new(sample_rate, osc_block)),
       Block::Expr(expr_block) => Processor::Expr(expr::Processor::new(expr_block)),
       Block::Filter(filt_block) => {
         Processor::Filter(filter::Processor::new(sample_rate, filt_block))
       }
+      Block::Reverb(reverb_block) => {
+        Processor::Reverb(Box::new(reverb::Processor::new(sample_rate, reverb_block)))
+      }
+      Block::RingMod(ringmod_block) => {
+        Processor::RingMod(ringmod::Processor::new(sample_rate, ringmod_block))
+      }
+      Block::SampleHold(sample_hold_block) => {
+        Processor::SampleHold(sample_hold::Processor::new(sample_rate, sample_hold_block))
+      }
+      Block::SlewLimiter(slew_limiter_block) => Processor::SlewLimiter(
+        slew_limiter::Processor::new(sample_rate, slew_limiter_block),
+      ),
+      Block::Waveshaper(waveshaper_block) => {
+        Processor::Waveshaper(waveshaper::Processor::new(sample_rate, waveshaper_block))
+      }
       Block::Out { left, right } => Processor::Out(left, right),
     }
   }
@@ -42,12 +108,28 @@ impl<F: Float> Processor<F> {
     match self {
       Processor::Const(_, _) => {}
       Processor::Param(_) => {}
+      Processor::ClockDivider(ref mut proc) => proc.reset(),
+      Processor::Comparator(ref mut proc) => proc.reset(),
+      Processor::Compressor(ref mut proc) => proc.reset(),
+      Processor::Crossfade(ref mut proc) => proc.reset(),
       Processor::DCA(ref mut proc) => proc.reset(),
+      Processor::Delay(ref mut proc) => proc.reset(),
       Processor::EG(ref mut proc) => proc.reset(),
+      Processor::EnvelopeFollower(ref mut proc) => proc.reset(),
+      Processor::Equalizer(ref mut proc) => proc.reset(),
       Processor::Expr(ref mut proc) => proc.reset(),
       Processor::Filter(ref mut proc) => proc.reset(),
+      Processor::KarplusStrong(ref mut proc) => proc.reset(),
       Processor::Lfo(ref mut proc) => proc.reset(),
+      Processor::Limiter(ref mut proc) => proc.reset(),
+      Processor::Mixer(ref mut proc) => proc.reset(),
+      Processor::ModFx(ref mut proc) => proc.reset(),
       Processor::Osc(ref mut proc) => proc.reset(),
+      Processor::Reverb(ref mut proc) => proc.reset(),
+      Processor::RingMod(ref mut proc) => proc.reset(),
+      Processor::SampleHold(ref mut proc) => proc.reset(),
+      Processor::SlewLimiter(ref mut proc) => proc.reset(),
+      Processor::Waveshaper(ref mut proc) => proc.reset(),
       Processor::Out(ref _left, ref _right) => {}
     }
   }
@@ -75,12 +157,28 @@ impl<F: Float> Processor<F> {
           signals[param.out_signal_ref].set(value);
         }
       }
+      Processor::ClockDivider(ref mut proc) => proc.process(signals, program),
+      Processor::Comparator(ref mut proc) => proc.process(signals, program),
+      Processor::Compressor(ref mut proc) => proc.process(signals, program),
+      Processor::Crossfade(ref mut proc) => proc.process(signals, program),
       Processor::DCA(ref mut proc) => proc.process(signals, program),
+      Processor::Delay(ref mut proc) => proc.process(signals, program, synth_globals),
       Processor::EG(ref mut proc) => proc.process(signals, program),
+      Processor::EnvelopeFollower(ref mut proc) => proc.process(signals, program),
+      Processor::Equalizer(ref mut proc) => proc.process(signals, program),
       Processor::Expr(ref mut proc) => proc.process(signals, program),
       Processor::Filter(ref mut proc) => proc.process(signals, program),
+      Processor::KarplusStrong(ref mut proc) => proc.process(signals, program),
       Processor::Lfo(ref mut proc) => proc.process(signals, program, synth_globals),
+      Processor::Limiter(ref mut proc) => proc.process(signals, program),
+      Processor::Mixer(ref mut proc) => proc.process(signals, program),
+      Processor::ModFx(ref mut proc) => proc.process(signals, program, synth_globals),
       Processor::Osc(ref mut proc) => proc.process(signals, program, synth_globals),
+      Processor::Reverb(ref mut proc) => proc.process(signals, program),
+      Processor::RingMod(ref mut proc) => proc.process(signals, program),
+      Processor::SampleHold(ref mut proc) => proc.process(signals, program),
+      Processor::SlewLimiter(ref mut proc) => proc.process(signals, program),
+      Processor::Waveshaper(ref mut proc) => proc.process(signals, program),
       Processor::Out(ref left, ref right) => {
         let voice = program.voice();
         let left_value = signals[*left].consume();
@@ -0,0 +1,51 @@
+use kiro_synth_dsp::float::Float;
+use kiro_synth_dsp::utilities::clock_divider::ClockDivider;
+
+use crate::program::{Program, SignalRef};
+use crate::signal::SignalBus;
+
+#[derive(Debug, Clone)]
+pub struct Inputs {
+  /// Clock or trigger pulses, high when above zero
+  pub input: SignalRef,
+  /// Number of input pulses for every output pulse
+  pub division: SignalRef,
+}
+
+#[derive(Debug, Clone)]
+pub struct Block {
+  pub inputs: Inputs,
+  pub output: SignalRef,
+}
+
+#[derive(Debug)]
+pub(crate) struct Processor<F: Float> {
+  divider: ClockDivider<F>,
+  block: Block,
+}
+
+impl<F: Float> Processor<F> {
+  pub fn new(_sample_rate: F, block: Block) -> Self {
+    Processor {
+      divider: ClockDivider::new(),
+      block,
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.divider.reset()
+  }
+
+  pub fn process<'a>(&mut self, signals: &mut SignalBus<'a, F>, _program: &Program<F>) {
+    let Block { inputs, output } = self.block.clone();
+    let Inputs { input, division } = inputs;
+
+    signals[division].if_updated(|value| {
+      let division = value.round().to_usize().unwrap_or(1);
+      self.divider.set_division(division)
+    });
+
+    let value = self.divider.process(signals[input].get());
+    signals[output].set(value);
+  }
+}
@@ -0,0 +1,54 @@
+use kiro_synth_dsp::float::Float;
+use kiro_synth_dsp::utilities::comparator::Comparator;
+
+use crate::program::{Program, SignalRef};
+use crate::signal::SignalBus;
+
+#[derive(Debug, Clone)]
+pub struct Inputs {
+  pub input: SignalRef,
+  pub threshold: SignalRef,
+  pub hysteresis: SignalRef,
+}
+
+#[derive(Debug, Clone)]
+pub struct Block {
+  pub inputs: Inputs,
+  /// 1.0 when the input is above the threshold, 0.0 otherwise
+  pub output: SignalRef,
+}
+
+#[derive(Debug)]
+pub(crate) struct Processor<F: Float> {
+  comparator: Comparator<F>,
+  block: Block,
+}
+
+impl<F: Float> Processor<F> {
+  pub fn new(_sample_rate: F, block: Block) -> Self {
+    Processor {
+      comparator: Comparator::new(),
+      block,
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.comparator.reset()
+  }
+
+  pub fn process<'a>(&mut self, signals: &mut SignalBus<'a, F>, _program: &Program<F>) {
+    let Block { inputs, output } = self.block.clone();
+    let Inputs {
+      input,
+      threshold,
+      hysteresis,
+    } = inputs;
+
+    signals[hysteresis].if_updated(|value| self.comparator.set_hysteresis(value));
+
+    let value = self
+      .comparator
+      .process(signals[input].get(), signals[threshold].get());
+    signals[output].set(value);
+  }
+}
@@ -0,0 +1,75 @@
+use kiro_synth_dsp::effects::compressor::Compressor;
+use kiro_synth_dsp::float::Float;
+
+use crate::program::{Program, SignalRef};
+use crate::signal::SignalBus;
+
+#[derive(Debug, Clone)]
+pub struct Inputs {
+  pub left: SignalRef,
+  pub right: SignalRef,
+  pub threshold: SignalRef,
+  pub ratio: SignalRef,
+  pub attack: SignalRef,
+  pub release: SignalRef,
+  pub knee: SignalRef,
+  pub makeup: SignalRef,
+}
+
+#[derive(Debug, Clone)]
+pub struct Outputs {
+  pub left: SignalRef,
+  pub right: SignalRef,
+}
+
+#[derive(Debug, Clone)]
+pub struct Block {
+  pub inputs: Inputs,
+  pub outputs: Outputs,
+}
+
+#[derive(Debug)]
+pub(crate) struct Processor<F: Float> {
+  compressor: Compressor<F>,
+  block: Block,
+}
+
+impl<F: Float> Processor<F> {
+  pub fn new(sample_rate: F, block: Block) -> Self {
+    Processor {
+      compressor: Compressor::new(sample_rate),
+      block,
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.compressor.reset()
+  }
+
+  pub fn process<'a>(&mut self, signals: &mut SignalBus<'a, F>, _program: &Program<F>) {
+    let Block { inputs, outputs } = self.block.clone();
+    let Inputs {
+      left,
+      right,
+      threshold,
+      ratio,
+      attack,
+      release,
+      knee,
+      makeup,
+    } = inputs;
+
+    signals[threshold].if_updated(|value| self.compressor.set_threshold(value));
+    signals[ratio].if_updated(|value| self.compressor.set_ratio(value));
+    signals[attack].if_updated(|value| self.compressor.set_attack(value));
+    signals[release].if_updated(|value| self.compressor.set_release(value));
+    signals[knee].if_updated(|value| self.compressor.set_knee(value));
+    signals[makeup].if_updated(|value| self.compressor.set_makeup(value));
+
+    let left_in = signals[left].get();
+    let right_in = signals[right].get();
+    let (left_out, right_out) = self.compressor.process(left_in, right_in);
+    signals[outputs.left].set(left_out);
+    signals[outputs.right].set(right_out);
+  }
+}
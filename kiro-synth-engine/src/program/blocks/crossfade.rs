@@ -0,0 +1,74 @@
+use kiro_synth_dsp::float::Float;
+use kiro_synth_dsp::funcs::crossfade::equal_power_gains;
+
+use crate::program::{Program, SignalRef};
+use crate::signal::SignalBus;
+
+#[derive(Debug, Clone)]
+pub struct Inputs {
+  pub first: SignalRef,
+  pub second: SignalRef,
+  /// From 0.0 (only the first input) to 1.0 (only the second one)
+  pub position: SignalRef,
+  pub position_mod: SignalRef,
+}
+
+#[derive(Debug, Clone)]
+pub struct Block {
+  pub inputs: Inputs,
+  pub output: SignalRef,
+}
+
+/// Equal-power crossfade between two signals
+#[derive(Debug)]
+pub(crate) struct Processor<F: Float> {
+  position: F,
+  position_mod: F,
+  first_gain: F,
+  second_gain: F,
+  block: Block,
+}
+
+impl<F: Float> Processor<F> {
+  pub fn new(_sample_rate: F, block: Block) -> Self {
+    let (first_gain, second_gain) = equal_power_gains(F::zero());
+    Processor {
+      position: F::zero(),
+      position_mod: F::zero(),
+      first_gain,
+      second_gain,
+      block,
+    }
+  }
+
+  pub fn reset(&mut self) {}
+
+  pub fn process<'a>(&mut self, signals: &mut SignalBus<'a, F>, _program: &Program<F>) {
+    let Block { inputs, output } = self.block.clone();
+    let Inputs {
+      first,
+      second,
+      position,
+      position_mod,
+    } = inputs;
+
+    let mut invalidated = false;
+    signals[position].if_updated(|value| {
+      self.position = value;
+      invalidated = true;
+    });
+    signals[position_mod].if_updated(|value| {
+      self.position_mod = value;
+      invalidated = true;
+    });
+
+    if invalidated {
+      let (first_gain, second_gain) = equal_power_gains(self.position + self.position_mod);
+      self.first_gain = first_gain;
+      self.second_gain = second_gain;
+    }
+
+    let value = signals[first].get() * self.first_gain + signals[second].get() * self.second_gain;
+    signals[output].set(value);
+  }
+}
@@ -0,0 +1,160 @@
+use kiro_synth_dsp::effects::stereo_delay::{Mode, StereoDelay};
+use kiro_synth_dsp::float::Float;
+
+use crate::globals::SynthGlobals;
+use crate::program::{Program, SignalRef};
+use crate::signal::SignalBus;
+use crate::transport::Division;
+
+/// Maximum delay time in seconds
+pub const MAX_DELAY: f64 = 4.0;
+
+#[derive(Debug, Clone)]
+pub struct Inputs {
+  pub left: SignalRef,
+  pub right: SignalRef,
+  pub mode: SignalRef,
+  pub time_left: SignalRef,
+  pub time_right: SignalRef,
+  pub sync: SignalRef,
+  pub division_left: SignalRef,
+  pub division_right: SignalRef,
+  pub feedback: SignalRef,
+  pub cross_feedback: SignalRef,
+  pub lowpass: SignalRef,
+  pub highpass: SignalRef,
+  pub mix: SignalRef,
+}
+
+#[derive(Debug, Clone)]
+pub struct Outputs {
+  pub left: SignalRef,
+  pub right: SignalRef,
+}
+
+#[derive(Debug, Clone)]
+pub struct Block {
+  pub inputs: Inputs,
+  pub outputs: Outputs,
+}
+
+/// The delay time in seconds for a division at a given tempo, limited to the maximum delay
+fn division_time<F: Float>(division: &Division, bpm: f64) -> F {
+  F::val((division.beats * 60.0 / bpm).min(MAX_DELAY))
+}
+
+#[derive(Debug)]
+pub(crate) struct Processor<F: Float> {
+  delay: StereoDelay<F>,
+  time_left: F,
+  time_right: F,
+  division_left: Option<Division>,
+  division_right: Option<Division>,
+  block: Block,
+}
+
+impl<F: Float> Processor<F> {
+  pub fn new(sample_rate: F, block: Block) -> Self {
+    Processor {
+      delay: StereoDelay::new(sample_rate, F::val(MAX_DELAY)),
+      time_left: F::zero(),
+      time_right: F::zero(),
+      division_left: None,
+      division_right: None,
+      block,
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.delay.reset()
+  }
+
+  pub fn process<'a>(
+    &mut self,
+    signals: &mut SignalBus<'a, F>,
+    _program: &Program<F>,
+    synth_globals: &SynthGlobals<F>,
+  ) {
+    let Block { inputs, outputs } = self.block.clone();
+    let Inputs {
+      left,
+      right,
+      mode,
+      time_left,
+      time_right,
+      sync,
+      division_left,
+      division_right,
+      feedback,
+      cross_feedback,
+      lowpass,
+      highpass,
+      mix,
+    } = inputs;
+    let Outputs {
+      left: left_output,
+      right: right_output,
+    } = outputs;
+
+    signals[mode].if_updated(|value| match value.round().to_usize() {
+      Some(0) => self.delay.set_mode(Mode::Stereo),
+      Some(1) => self.delay.set_mode(Mode::PingPong),
+      _ => {}
+    });
+    signals[time_left].if_updated(|value| self.time_left = value);
+    signals[time_right].if_updated(|value| self.time_right = value);
+    signals[division_left].if_updated(|value| self.division_left = Division::from(value.round()));
+    signals[division_right].if_updated(|value| self.division_right = Division::from(value.round()));
+    signals[feedback].if_updated(|value| self.delay.set_feedback(value));
+    signals[cross_feedback].if_updated(|value| self.delay.set_cross_feedback(value));
+    signals[lowpass].if_updated(|value| self.delay.set_lowpass_frequency(value));
+    signals[highpass].if_updated(|value| self.delay.set_highpass_frequency(value));
+    signals[mix].if_updated(|value| self.delay.set_mix(value));
+
+    // When synced the times follow the tempo
+    let synced = signals[sync].get() > F::zero();
+    let bpm = synth_globals.transport.bpm();
+    let sync_time = |division: Option<Division>, time: F| match division.filter(|_| synced) {
+      Some(division) => division_time(&division, bpm),
+      None => time,
+    };
+    let time_left = sync_time(self.division_left, self.time_left);
+    let time_right = sync_time(self.division_right, self.time_right);
+    self.delay.set_time_left(time_left);
+    self.delay.set_time_right(time_right);
+
+    let left_in = signals[left].get();
+    let right_in = signals[right].get();
+    let (left_out, right_out) = self.delay.process(left_in, right_in);
+    signals[left_output].set(left_out);
+    signals[right_output].set(right_out);
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use assert_approx_eq::assert_approx_eq;
+
+  fn division(name: &str) -> Division {
+    (0..Division::count())
+      .filter_map(|index| Division::from(index as f64))
+      .find(|division| division.name == name)
+      .unwrap()
+  }
+
+  #[test]
+  fn division_time_follows_the_tempo() {
+    assert_approx_eq!(division_time::<f64>(&division("1/4"), 120.0), 0.5);
+    assert_approx_eq!(division_time::<f64>(&division("1/8D"), 100.0), 0.45);
+    assert_approx_eq!(division_time::<f64>(&division("1/16T"), 60.0), 1.0 / 6.0);
+    assert_approx_eq!(division_time::<f64>(&division("1/1"), 60.0), 4.0);
+  }
+
+  #[test]
+  fn division_time_is_limited_to_the_max_delay() {
+    assert_approx_eq!(division_time::<f64>(&division("2/1"), 60.0), MAX_DELAY);
+    assert_approx_eq!(division_time::<f64>(&division("4/1"), 120.0), MAX_DELAY);
+    assert_approx_eq!(division_time::<f64>(&division("1/2"), 20.0), MAX_DELAY);
+  }
+}
@@ -0,0 +1,62 @@
+use kiro_synth_dsp::float::Float;
+use kiro_synth_dsp::meters::envelope_follower::{Detector, EnvelopeFollower};
+
+use crate::program::{Program, SignalRef};
+use crate::signal::SignalBus;
+
+#[derive(Debug, Clone)]
+pub struct Inputs {
+  pub input: SignalRef,
+  pub detector: SignalRef,
+  /// Attack time in seconds
+  pub attack: SignalRef,
+  /// Release time in seconds
+  pub release: SignalRef,
+}
+
+#[derive(Debug, Clone)]
+pub struct Block {
+  pub inputs: Inputs,
+  pub output: SignalRef,
+}
+
+/// Follows the amplitude of the input, so any audio signal can be used as a modulation source
+#[derive(Debug)]
+pub(crate) struct Processor<F: Float> {
+  follower: EnvelopeFollower<F>,
+  block: Block,
+}
+
+impl<F: Float> Processor<F> {
+  pub fn new(sample_rate: F, block: Block) -> Self {
+    Processor {
+      follower: EnvelopeFollower::new(sample_rate),
+      block,
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.follower.reset()
+  }
+
+  pub fn process<'a>(&mut self, signals: &mut SignalBus<'a, F>, _program: &Program<F>) {
+    let Block { inputs, output } = self.block.clone();
+    let Inputs {
+      input,
+      detector,
+      attack,
+      release,
+    } = inputs;
+
+    signals[detector].if_updated(|value| {
+      if let Some(detector) = Detector::from(value.round()) {
+        self.follower.set_detector(detector);
+      }
+    });
+    signals[attack].if_updated(|value| self.follower.set_attack(value));
+    signals[release].if_updated(|value| self.follower.set_release(value));
+
+    let value = self.follower.process(signals[input].get());
+    signals[output].set(value);
+  }
+}
@@ -0,0 +1,68 @@
+use kiro_synth_dsp::effects::equalizer::{Equalizer, NUM_BANDS};
+use kiro_synth_dsp::float::Float;
+
+use crate::program::{Program, SignalRef};
+use crate::signal::SignalBus;
+
+#[derive(Debug, Clone)]
+pub struct BandInputs {
+  pub frequency: SignalRef,
+  pub gain: SignalRef,
+  pub q: SignalRef,
+}
+
+#[derive(Debug, Clone)]
+pub struct Inputs {
+  pub left: SignalRef,
+  pub right: SignalRef,
+  /// From low to high: low shelf, two peaking bands and high shelf
+  pub bands: [BandInputs; NUM_BANDS],
+}
+
+#[derive(Debug, Clone)]
+pub struct Outputs {
+  pub left: SignalRef,
+  pub right: SignalRef,
+}
+
+#[derive(Debug, Clone)]
+pub struct Block {
+  pub inputs: Inputs,
+  pub outputs: Outputs,
+}
+
+#[derive(Debug)]
+pub(crate) struct Processor<F: Float> {
+  equalizer: Equalizer<F>,
+  block: Block,
+}
+
+impl<F: Float> Processor<F> {
+  pub fn new(sample_rate: F, block: Block) -> Self {
+    Processor {
+      equalizer: Equalizer::new(sample_rate),
+      block,
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.equalizer.reset()
+  }
+
+  pub fn process<'a>(&mut self, signals: &mut SignalBus<'a, F>, _program: &Program<F>) {
+    let Block { inputs, outputs } = self.block.clone();
+    let Inputs { left, right, bands } = inputs;
+
+    for (index, band) in bands.iter().enumerate() {
+      signals[band.frequency].if_updated(|value| self.equalizer.set_frequency(index, value));
+      signals[band.gain].if_updated(|value| self.equalizer.set_gain(index, value));
+      signals[band.q].if_updated(|value| self.equalizer.set_q(index, value));
+    }
+
+    let left_in = signals[left].get();
+    let right_in = signals[right].get();
+    let (left_out, right_out) = self.equalizer.process(left_in, right_in);
+    signals[outputs.left].set(left_out);
+    signals[outputs.right].set(right_out);
+  }
+}
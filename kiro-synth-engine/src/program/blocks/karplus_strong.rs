@@ -0,0 +1,71 @@
+use kiro_synth_dsp::float::Float;
+use kiro_synth_dsp::oscillators::karplus_strong::{Excitation, KarplusStrong};
+
+use crate::program::{Program, SignalRef};
+use crate::signal::SignalBus;
+
+#[derive(Debug, Clone)]
+pub struct Inputs {
+  pub excitation: SignalRef,
+  /// Signal fed into the string when the excitation is `Excitation::Input`
+  pub input: SignalRef,
+  pub note_pitch: SignalRef,
+  pub damping: SignalRef,
+  pub decay: SignalRef,
+}
+
+#[derive(Debug, Clone)]
+pub struct Block {
+  pub inputs: Inputs,
+  pub output: SignalRef,
+}
+
+/// Plucked string that is plucked every time the voice is triggered
+#[derive(Debug)]
+pub(crate) struct Processor<F: Float> {
+  string: KarplusStrong<F>,
+  block: Block,
+}
+
+impl<F: Float> Processor<F> {
+  pub fn new(sample_rate: F, block: Block, seed: u32) -> Self {
+    Processor {
+      string: KarplusStrong::new(sample_rate, seed),
+      block,
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.string.reset()
+  }
+
+  pub fn process<'a>(&mut self, signals: &mut SignalBus<'a, F>, program: &Program<F>) {
+    let Block { inputs, output } = self.block.clone();
+    let Inputs {
+      excitation,
+      input,
+      note_pitch,
+      damping,
+      decay,
+    } = inputs;
+
+    signals[excitation].if_updated(|value| {
+      if let Some(excitation) = Excitation::from(value.round()) {
+        self.string.set_excitation(excitation);
+      }
+    });
+    signals[note_pitch].if_updated(|value| self.string.set_frequency(value));
+    signals[damping].if_updated(|value| self.string.set_damping(value));
+    signals[decay].if_updated(|value| self.string.set_decay(value));
+
+    let voice = program.voice();
+    signals[voice.trigger].if_updated(|value| {
+      if value > F::zero() {
+        self.string.pluck();
+      }
+    });
+
+    let value = self.string.process(signals[input].get());
+    signals[output].set(value);
+  }
+}
@@ -1,16 +1,22 @@
-use kiro_synth_dsp::oscillators::lfo::Lfo;
+use kiro_synth_dsp::oscillators::lfo::{Lfo, Mode};
 
 use crate::float::Float;
 use crate::globals::SynthGlobals;
 use crate::program::{Program, SignalRef};
 use crate::signal::SignalBus;
+use crate::transport::Division;
 
 #[derive(Debug, Clone)]
 pub struct Inputs {
   pub shape: SignalRef,
+  pub mode: SignalRef,
   pub rate: SignalRef,
   pub phase: SignalRef,
   pub depth: SignalRef,
+  pub delay: SignalRef,
+  pub fade_in: SignalRef,
+  pub sync: SignalRef,
+  pub division: SignalRef,
 }
 
 #[derive(Debug, Clone)]
@@ -22,45 +28,89 @@ pub struct Block {
 #[derive(Debug)]
 pub(crate) struct Processor<F: Float> {
   lfo: Lfo<F>,
+  shape: Option<usize>,
+  rate: F,
+  division: Option<Division>,
   block: Block,
 }
 
 impl<F: Float> Processor<F> {
-  pub fn new(sample_rate: F, block: Block) -> Self {
-    let lfo = Lfo::new(sample_rate);
+  pub fn new(sample_rate: F, block: Block, seed: u32) -> Self {
+    let lfo = Lfo::new(sample_rate, seed);
 
-    Processor { lfo, block }
+    Processor {
+      lfo,
+      shape: None,
+      rate: F::one(),
+      division: None,
+      block,
+    }
   }
 
-  pub fn reset(&mut self) {
-    self.lfo.reset()
-  }
+  /// The phase is not reset here but when the voice is triggered, so the free-running mode can work
+  pub fn reset(&mut self) {}
 
   pub fn process<'a>(
     &mut self,
     signals: &mut SignalBus<'a, F>,
-    _program: &Program<F>,
+    program: &Program<F>,
     synth_globals: &SynthGlobals<F>,
   ) {
     let Block { inputs, output } = self.block.clone();
     let Inputs {
       shape,
+      mode,
       rate,
       phase,
       depth,
+      delay,
+      fade_in,
+      sync,
+      division,
     } = inputs;
 
     signals[shape].if_updated(|value| {
-      self.lfo.set_waveform(
-        synth_globals
-          .lfo_waveforms
-          .waveform(value.to_usize().unwrap())
-          .clone(),
-      )
+      let index = value.to_usize().unwrap();
+      if self.shape != Some(index) {
+        self.shape = Some(index);
+        self
+          .lfo
+          .set_waveform(synth_globals.lfo_waveforms.waveform(index).clone())
+      }
+    });
+    signals[mode].if_updated(|value| match value.round().to_usize() {
+      Some(0) => self.lfo.set_mode(Mode::FreeRunning),
+      Some(1) => self.lfo.set_mode(Mode::Retrigger),
+      Some(2) => self.lfo.set_mode(Mode::OneShot),
+      _ => {}
     });
-    signals[rate].if_updated(|value| self.lfo.set_rate(value));
+    signals[rate].if_updated(|value| self.rate = value);
     signals[phase].if_updated(|value| self.lfo.set_phase(value));
     signals[depth].if_updated(|value| self.lfo.set_depth(value));
+    signals[delay].if_updated(|value| self.lfo.set_delay(value));
+    signals[fade_in].if_updated(|value| self.lfo.set_fade_in(value));
+
+    let voice = program.voice();
+    signals[voice.trigger].if_updated(|value| {
+      if value > F::zero() {
+        self.lfo.trigger();
+      }
+    });
+
+    signals[division].if_updated(|value| self.division = Division::from(value.round()));
+    let synced = signals[sync].get() > F::zero();
+
+    // When synced the rate follows the tempo, and the phase is locked to the beat
+    match self.division.filter(|_| synced) {
+      Some(division) => {
+        let transport = &synth_globals.transport;
+        self.lfo.set_rate(F::val(division.rate(transport.bpm())));
+        self
+          .lfo
+          .set_position(F::val(transport.cycle_position(&division)));
+      }
+      None => self.lfo.set_rate(self.rate),
+    }
 
     signals[output].set(self.lfo.generate());
   }
@@ -0,0 +1,66 @@
+use kiro_synth_dsp::effects::limiter::Limiter;
+use kiro_synth_dsp::float::Float;
+
+use crate::program::{Program, SignalRef};
+use crate::signal::SignalBus;
+
+/// Look-ahead time in seconds
+pub const LOOKAHEAD: f64 = 0.005;
+
+#[derive(Debug, Clone)]
+pub struct Inputs {
+  pub left: SignalRef,
+  pub right: SignalRef,
+  pub ceiling: SignalRef,
+  pub release: SignalRef,
+}
+
+#[derive(Debug, Clone)]
+pub struct Outputs {
+  pub left: SignalRef,
+  pub right: SignalRef,
+}
+
+#[derive(Debug, Clone)]
+pub struct Block {
+  pub inputs: Inputs,
+  pub outputs: Outputs,
+}
+
+#[derive(Debug)]
+pub(crate) struct Processor<F: Float> {
+  limiter: Limiter<F>,
+  block: Block,
+}
+
+impl<F: Float> Processor<F> {
+  pub fn new(sample_rate: F, block: Block) -> Self {
+    Processor {
+      limiter: Limiter::new(sample_rate, F::val(LOOKAHEAD)),
+      block,
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.limiter.reset()
+  }
+
+  pub fn process<'a>(&mut self, signals: &mut SignalBus<'a, F>, _program: &Program<F>) {
+    let Block { inputs, outputs } = self.block.clone();
+    let Inputs {
+      left,
+      right,
+      ceiling,
+      release,
+    } = inputs;
+
+    signals[ceiling].if_updated(|value| self.limiter.set_ceiling(value));
+    signals[release].if_updated(|value| self.limiter.set_release(value));
+
+    let left_in = signals[left].get();
+    let right_in = signals[right].get();
+    let (left_out, right_out) = self.limiter.process(left_in, right_in);
+    signals[outputs.left].set(left_out);
+    signals[outputs.right].set(right_out);
+  }
+}
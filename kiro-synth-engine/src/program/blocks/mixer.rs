@@ -0,0 +1,183 @@
+use heapless::consts;
+use heapless::Vec;
+
+use kiro_synth_dsp::float::Float;
+use kiro_synth_dsp::mixer::MixerChannel;
+
+use crate::program::{Program, SignalRef};
+use crate::signal::SignalBus;
+
+pub type MaxChannels = consts::U8;
+
+#[derive(Debug, Clone)]
+pub struct ChannelInputs {
+  pub input: SignalRef,
+  pub level: SignalRef,
+  pub pan: SignalRef,
+  pub send: SignalRef,
+  pub mute: SignalRef,
+}
+
+#[derive(Debug, Clone)]
+pub struct Outputs {
+  pub left: SignalRef,
+  pub right: SignalRef,
+  pub send: SignalRef,
+}
+
+#[derive(Debug, Clone)]
+pub struct Block {
+  pub channels: Vec<ChannelInputs, MaxChannels>,
+  pub outputs: Outputs,
+}
+
+impl Block {
+  /// Panics if there are more channels than `MaxChannels`
+  pub fn new(channels: &[ChannelInputs], outputs: Outputs) -> Self {
+    let mut block = Block {
+      channels: Vec::new(),
+      outputs,
+    };
+    block
+      .channels
+      .extend_from_slice(channels)
+      .expect("Too many mixer channels");
+    block
+  }
+}
+
+/// Sums mono inputs into a stereo pair, and the part of them that is sent into a mono output
+#[derive(Debug)]
+pub(crate) struct Processor<F: Float> {
+  channels: Vec<MixerChannel<F>, MaxChannels>,
+  block: Block,
+}
+
+impl<F: Float> Processor<F> {
+  pub fn new(_sample_rate: F, block: Block) -> Self {
+    let mut channels = Vec::new();
+    // the block can not have more channels than the capacity
+    channels.resize_default(block.channels.len()).unwrap();
+
+    Processor { channels, block }
+  }
+
+  pub fn reset(&mut self) {}
+
+  pub fn process<'a>(&mut self, signals: &mut SignalBus<'a, F>, _program: &Program<F>) {
+    let mut left = F::zero();
+    let mut right = F::zero();
+    let mut send = F::zero();
+
+    for (channel, inputs) in self.channels.iter_mut().zip(self.block.channels.iter()) {
+      signals[inputs.level].if_updated(|value| channel.set_level(value));
+      signals[inputs.pan].if_updated(|value| channel.set_pan(value));
+      signals[inputs.send].if_updated(|value| channel.set_send(value));
+      signals[inputs.mute].if_updated(|value| channel.set_mute(value > F::val(0.5)));
+
+      let input = signals[inputs.input].get();
+      let (channel_left, channel_right) = channel.process(input);
+      left = left + channel_left;
+      right = right + channel_right;
+      send = send + channel.process_send(input);
+    }
+
+    signals[self.block.outputs.left].set(left);
+    signals[self.block.outputs.right].set(right);
+    signals[self.block.outputs.send].set(send);
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::program::ProgramBuilder;
+  use crate::signal::Signal;
+  use assert_approx_eq::assert_approx_eq;
+
+  fn channel_inputs(builder: &mut ProgramBuilder<f64>) -> ChannelInputs {
+    ChannelInputs {
+      input: builder.signal(),
+      level: builder.signal(),
+      pan: builder.signal(),
+      send: builder.signal(),
+      mute: builder.signal(),
+    }
+  }
+
+  #[test]
+  fn mixer_sums_the_channels() {
+    let mut builder = ProgramBuilder::new();
+    let channel1 = channel_inputs(&mut builder);
+    let channel2 = channel_inputs(&mut builder);
+    let outputs = Outputs {
+      left: builder.signal(),
+      right: builder.signal(),
+      send: builder.signal(),
+    };
+    let block = Block::new(&[channel1.clone(), channel2.clone()], outputs.clone());
+    let program = builder.build();
+
+    let mut signals = vec![Signal::default(); program.get_signals_count()];
+    let mut signals = SignalBus::new(&mut signals);
+    let mut processor = Processor::new(44100.0, block);
+
+    signals[channel1.input].set(1.0);
+    signals[channel1.level].set(0.5);
+    signals[channel1.pan].set(-1.0);
+    signals[channel2.input].set(0.5);
+    signals[channel2.level].set(1.0);
+    signals[channel2.pan].set(0.5);
+    processor.process(&mut signals, &program);
+    assert_approx_eq!(signals[outputs.left].get(), 0.5 + 0.25);
+    assert_approx_eq!(signals[outputs.right].get(), 0.5);
+
+    signals.update();
+    signals[channel2.mute].set(1.0);
+    processor.process(&mut signals, &program);
+    assert_approx_eq!(signals[outputs.left].get(), 0.5);
+    assert_approx_eq!(signals[outputs.right].get(), 0.0);
+
+    signals.update();
+    signals[channel2.mute].set(0.0);
+    signals[channel1.pan].set(0.0);
+    processor.process(&mut signals, &program);
+    assert_approx_eq!(signals[outputs.left].get(), 0.5 + 0.25);
+    assert_approx_eq!(signals[outputs.right].get(), 0.5 + 0.5);
+  }
+
+  #[test]
+  fn mixer_sums_the_sends() {
+    let mut builder = ProgramBuilder::new();
+    let channel1 = channel_inputs(&mut builder);
+    let channel2 = channel_inputs(&mut builder);
+    let outputs = Outputs {
+      left: builder.signal(),
+      right: builder.signal(),
+      send: builder.signal(),
+    };
+    let block = Block::new(&[channel1.clone(), channel2.clone()], outputs.clone());
+    let program = builder.build();
+
+    let mut signals = vec![Signal::default(); program.get_signals_count()];
+    let mut signals = SignalBus::new(&mut signals);
+    let mut processor = Processor::new(44100.0, block);
+
+    signals[channel1.input].set(1.0);
+    signals[channel1.level].set(0.5);
+    signals[channel1.send].set(1.0);
+    signals[channel2.input].set(0.5);
+    signals[channel2.level].set(1.0);
+    signals[channel2.pan].set(1.0);
+    signals[channel2.send].set(0.5);
+    processor.process(&mut signals, &program);
+    assert_approx_eq!(signals[outputs.left].get(), 0.0);
+    assert_approx_eq!(signals[outputs.right].get(), 0.25);
+    assert_approx_eq!(signals[outputs.send].get(), 0.5 + 0.25);
+
+    signals.update();
+    signals[channel1.mute].set(1.0);
+    processor.process(&mut signals, &program);
+    assert_approx_eq!(signals[outputs.send].get(), 0.25);
+  }
+}
@@ -1,6 +1,22 @@
+pub mod clock_divider;
+pub mod comparator;
+pub mod compressor;
+pub mod crossfade;
 pub mod dca;
+pub mod delay;
+pub mod envelope_follower;
 pub mod envgen;
+pub mod equalizer;
 pub mod expr;
 pub mod filter;
+pub mod karplus_strong;
 pub mod lfo;
+pub mod limiter;
+pub mod mixer;
+pub mod modfx;
 pub mod osc;
+pub mod reverb;
+pub mod ringmod;
+pub mod sample_hold;
+pub mod slew_limiter;
+pub mod waveshaper;
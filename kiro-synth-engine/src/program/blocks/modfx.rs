@@ -0,0 +1,164 @@
+use kiro_synth_dsp::effects::chorus::Chorus;
+use kiro_synth_dsp::effects::flanger::Flanger;
+use kiro_synth_dsp::effects::phaser::Phaser;
+use kiro_synth_dsp::float::Float;
+
+use crate::globals::SynthGlobals;
+use crate::program::{Program, SignalRef};
+use crate::signal::SignalBus;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+  Chorus,
+  Flanger,
+  Phaser,
+}
+
+impl Mode {
+  const MODES: [Mode; 3] = [Mode::Chorus, Mode::Flanger, Mode::Phaser];
+
+  pub fn count() -> usize {
+    Self::MODES.len()
+  }
+
+  pub fn from<F: Float>(value: F) -> Option<Self> {
+    value
+      .to_usize()
+      .and_then(|index| Self::MODES.get(index).copied())
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct Inputs {
+  pub left: SignalRef,
+  pub right: SignalRef,
+  pub mode: SignalRef,
+  pub shape: SignalRef,
+  pub rate: SignalRef,
+  pub depth: SignalRef,
+  pub feedback: SignalRef,
+  pub spread: SignalRef,
+  pub mix: SignalRef,
+}
+
+#[derive(Debug, Clone)]
+pub struct Outputs {
+  pub left: SignalRef,
+  pub right: SignalRef,
+}
+
+#[derive(Debug, Clone)]
+pub struct Block {
+  pub inputs: Inputs,
+  pub outputs: Outputs,
+}
+
+/// Modulation effects sharing the same parameters, only the selected one is processed.
+/// The chorus ignores the feedback.
+#[derive(Debug)]
+pub(crate) struct Processor<F: Float> {
+  mode: Mode,
+  shape: Option<usize>,
+  chorus: Chorus<F>,
+  flanger: Flanger<F>,
+  phaser: Phaser<F>,
+  block: Block,
+}
+
+impl<F: Float> Processor<F> {
+  pub fn new(sample_rate: F, block: Block) -> Self {
+    Processor {
+      mode: Mode::Chorus,
+      shape: None,
+      chorus: Chorus::new(sample_rate),
+      flanger: Flanger::new(sample_rate),
+      phaser: Phaser::new(sample_rate),
+      block,
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.chorus.reset();
+    self.flanger.reset();
+    self.phaser.reset();
+  }
+
+  pub fn process<'a>(
+    &mut self,
+    signals: &mut SignalBus<'a, F>,
+    _program: &Program<F>,
+    synth_globals: &SynthGlobals<F>,
+  ) {
+    let Block { inputs, outputs } = self.block.clone();
+    let Inputs {
+      left,
+      right,
+      mode,
+      shape,
+      rate,
+      depth,
+      feedback,
+      spread,
+      mix,
+    } = inputs;
+
+    signals[mode].if_updated(|value| {
+      if let Some(mode) = Mode::from(value.round()) {
+        if self.mode != mode {
+          self.mode = mode;
+          self.reset();
+        }
+      }
+    });
+
+    signals[shape].if_updated(|value| {
+      let index = value.to_usize().unwrap_or(0);
+      if self.shape != Some(index) {
+        self.shape = Some(index);
+        let waveform = synth_globals.lfo_waveforms.waveform(index);
+        self.chorus.set_waveform(waveform.clone());
+        self.flanger.set_waveform(waveform.clone());
+        self.phaser.set_waveform(waveform.clone());
+      }
+    });
+
+    signals[rate].if_updated(|value| {
+      self.chorus.set_rate(value);
+      self.flanger.set_rate(value);
+      self.phaser.set_rate(value);
+    });
+
+    signals[depth].if_updated(|value| {
+      self.chorus.set_depth(value);
+      self.flanger.set_depth(value);
+      self.phaser.set_depth(value);
+    });
+
+    signals[feedback].if_updated(|value| {
+      self.flanger.set_feedback(value);
+      self.phaser.set_feedback(value);
+    });
+
+    signals[spread].if_updated(|value| {
+      self.chorus.set_spread(value);
+      self.flanger.set_spread(value);
+      self.phaser.set_spread(value);
+    });
+
+    signals[mix].if_updated(|value| {
+      self.chorus.set_mix(value);
+      self.flanger.set_mix(value);
+      self.phaser.set_mix(value);
+    });
+
+    let left_in = signals[left].get();
+    let right_in = signals[right].get();
+    let (left_out, right_out) = match self.mode {
+      Mode::Chorus => self.chorus.process(left_in, right_in),
+      Mode::Flanger => self.flanger.process(left_in, right_in),
+      Mode::Phaser => self.phaser.process(left_in, right_in),
+    };
+    signals[outputs.left].set(left_out);
+    signals[outputs.right].set(right_out);
+  }
+}
@@ -16,6 +16,8 @@ pub struct Inputs {
   pub cents: SignalRef,
   pub note_pitch: SignalRef,
   pub pitch_bend: SignalRef,
+  pub pitch_bend_up: SignalRef,
+  pub pitch_bend_down: SignalRef,
   pub freq_mod: SignalRef,
 }
 
@@ -59,6 +61,8 @@ impl<F: Float> Processor<F> {
       cents,
       note_pitch,
       pitch_bend,
+      pitch_bend_up,
+      pitch_bend_down,
       freq_mod,
     } = inputs;
 
@@ -77,6 +81,8 @@ impl<F: Float> Processor<F> {
     signals[cents].if_updated(|value| self.osc.set_cents(value));
     signals[note_pitch].if_updated(|value| self.osc.set_pitch_frequency(value));
     signals[pitch_bend].if_updated(|value| self.osc.set_pitch_bend(value));
+    signals[pitch_bend_up].if_updated(|value| self.osc.set_pitch_bend_up(value));
+    signals[pitch_bend_down].if_updated(|value| self.osc.set_pitch_bend_down(value));
     signals[freq_mod].if_updated(|value| self.osc.set_frequency_modulation(value));
 
     signals[output].set(self.osc.generate());
@@ -0,0 +1,78 @@
+use kiro_synth_dsp::effects::reverb::Reverb;
+use kiro_synth_dsp::float::Float;
+
+use crate::program::{Program, SignalRef};
+use crate::signal::SignalBus;
+
+/// Maximum pre-delay time in seconds
+pub const MAX_PREDELAY: f64 = 0.5;
+
+#[derive(Debug, Clone)]
+pub struct Inputs {
+  pub left: SignalRef,
+  pub right: SignalRef,
+  pub size: SignalRef,
+  pub decay: SignalRef,
+  pub damping: SignalRef,
+  pub predelay: SignalRef,
+  pub width: SignalRef,
+  pub mix: SignalRef,
+}
+
+#[derive(Debug, Clone)]
+pub struct Outputs {
+  pub left: SignalRef,
+  pub right: SignalRef,
+}
+
+#[derive(Debug, Clone)]
+pub struct Block {
+  pub inputs: Inputs,
+  pub outputs: Outputs,
+}
+
+#[derive(Debug)]
+pub(crate) struct Processor<F: Float> {
+  reverb: Reverb<F>,
+  block: Block,
+}
+
+impl<F: Float> Processor<F> {
+  pub fn new(sample_rate: F, block: Block) -> Self {
+    Processor {
+      reverb: Reverb::new(sample_rate, F::val(MAX_PREDELAY)),
+      block,
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.reverb.reset()
+  }
+
+  pub fn process<'a>(&mut self, signals: &mut SignalBus<'a, F>, _program: &Program<F>) {
+    let Block { inputs, outputs } = self.block.clone();
+    let Inputs {
+      left,
+      right,
+      size,
+      decay,
+      damping,
+      predelay,
+      width,
+      mix,
+    } = inputs;
+
+    signals[size].if_updated(|value| self.reverb.set_size(value));
+    signals[decay].if_updated(|value| self.reverb.set_decay(value));
+    signals[damping].if_updated(|value| self.reverb.set_damping(value));
+    signals[predelay].if_updated(|value| self.reverb.set_predelay(value));
+    signals[width].if_updated(|value| self.reverb.set_width(value));
+    signals[mix].if_updated(|value| self.reverb.set_mix(value));
+
+    let left_in = signals[left].get();
+    let right_in = signals[right].get();
+    let (left_out, right_out) = self.reverb.process(left_in, right_in);
+    signals[outputs.left].set(left_out);
+    signals[outputs.right].set(right_out);
+  }
+}
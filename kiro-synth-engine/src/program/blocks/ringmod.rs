@@ -0,0 +1,51 @@
+use kiro_synth_dsp::float::Float;
+
+use crate::program::{Program, SignalRef};
+use crate::signal::SignalBus;
+
+#[derive(Debug, Clone)]
+pub struct Inputs {
+  pub carrier: SignalRef,
+  pub modulator: SignalRef,
+  /// The dry/wet proportion. Values from 0.0 (only the carrier) to 1.0 (only the product)
+  pub mix: SignalRef,
+}
+
+#[derive(Debug, Clone)]
+pub struct Block {
+  pub inputs: Inputs,
+  pub output: SignalRef,
+}
+
+/// Ring modulator multiplying two audio signals
+#[derive(Debug)]
+pub(crate) struct Processor<F: Float> {
+  mix: F,
+  block: Block,
+}
+
+impl<F: Float> Processor<F> {
+  pub fn new(_sample_rate: F, block: Block) -> Self {
+    Processor {
+      mix: F::one(),
+      block,
+    }
+  }
+
+  pub fn reset(&mut self) {}
+
+  pub fn process<'a>(&mut self, signals: &mut SignalBus<'a, F>, _program: &Program<F>) {
+    let Block { inputs, output } = self.block.clone();
+    let Inputs {
+      carrier,
+      modulator,
+      mix,
+    } = inputs;
+
+    signals[mix].if_updated(|value| self.mix = value.max(F::zero()).min(F::one()));
+
+    let dry = signals[carrier].get();
+    let wet = dry * signals[modulator].get();
+    signals[output].set(dry + (wet - dry) * self.mix);
+  }
+}
@@ -0,0 +1,44 @@
+use kiro_synth_dsp::float::Float;
+use kiro_synth_dsp::utilities::sample_hold::SampleHold;
+
+use crate::program::{Program, SignalRef};
+use crate::signal::SignalBus;
+
+#[derive(Debug, Clone)]
+pub struct Inputs {
+  pub input: SignalRef,
+  /// The input is sampled when this goes above zero
+  pub trigger: SignalRef,
+}
+
+#[derive(Debug, Clone)]
+pub struct Block {
+  pub inputs: Inputs,
+  pub output: SignalRef,
+}
+
+#[derive(Debug)]
+pub(crate) struct Processor<F: Float> {
+  sample_hold: SampleHold<F>,
+  block: Block,
+}
+
+impl<F: Float> Processor<F> {
+  pub fn new(_sample_rate: F, block: Block) -> Self {
+    Processor {
+      sample_hold: SampleHold::new(),
+      block,
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.sample_hold.reset()
+  }
+
+  pub fn process<'a>(&mut self, signals: &mut SignalBus<'a, F>, _program: &Program<F>) {
+    let Block { inputs, output } = self.block.clone();
+    let input = signals[inputs.input].get();
+    let trigger = signals[inputs.trigger].get();
+    signals[output].set(self.sample_hold.process(input, trigger));
+  }
+}
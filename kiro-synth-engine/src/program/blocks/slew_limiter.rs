@@ -0,0 +1,50 @@
+use kiro_synth_dsp::float::Float;
+use kiro_synth_dsp::utilities::slew_limiter::SlewLimiter;
+
+use crate::program::{Program, SignalRef};
+use crate::signal::SignalBus;
+
+#[derive(Debug, Clone)]
+pub struct Inputs {
+  pub input: SignalRef,
+  /// Seconds to rise by 1.0
+  pub rise: SignalRef,
+  /// Seconds to fall by 1.0
+  pub fall: SignalRef,
+}
+
+#[derive(Debug, Clone)]
+pub struct Block {
+  pub inputs: Inputs,
+  pub output: SignalRef,
+}
+
+#[derive(Debug)]
+pub(crate) struct Processor<F: Float> {
+  slew_limiter: SlewLimiter<F>,
+  block: Block,
+}
+
+impl<F: Float> Processor<F> {
+  pub fn new(sample_rate: F, block: Block) -> Self {
+    Processor {
+      slew_limiter: SlewLimiter::new(sample_rate),
+      block,
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.slew_limiter.reset(F::zero())
+  }
+
+  pub fn process<'a>(&mut self, signals: &mut SignalBus<'a, F>, _program: &Program<F>) {
+    let Block { inputs, output } = self.block.clone();
+    let Inputs { input, rise, fall } = inputs;
+
+    signals[rise].if_updated(|value| self.slew_limiter.set_rise(value));
+    signals[fall].if_updated(|value| self.slew_limiter.set_fall(value));
+
+    let value = self.slew_limiter.process(signals[input].get());
+    signals[output].set(value);
+  }
+}
@@ -0,0 +1,66 @@
+use kiro_synth_dsp::effects::waveshaper::{Curve, Oversampling, Waveshaper};
+use kiro_synth_dsp::float::Float;
+
+use crate::program::{Program, SignalRef};
+use crate::signal::SignalBus;
+
+#[derive(Debug, Clone)]
+pub struct Inputs {
+  pub input: SignalRef,
+  pub curve: SignalRef,
+  pub drive: SignalRef,
+  pub mix: SignalRef,
+  pub oversampling: SignalRef,
+}
+
+#[derive(Debug, Clone)]
+pub struct Block {
+  pub inputs: Inputs,
+  pub output: SignalRef,
+}
+
+#[derive(Debug)]
+pub(crate) struct Processor<F: Float> {
+  waveshaper: Waveshaper<F>,
+  block: Block,
+}
+
+impl<F: Float> Processor<F> {
+  pub fn new(_sample_rate: F, block: Block) -> Self {
+    Processor {
+      waveshaper: Waveshaper::new(),
+      block,
+    }
+  }
+
+  pub fn reset(&mut self) {
+    self.waveshaper.reset()
+  }
+
+  pub fn process<'a>(&mut self, signals: &mut SignalBus<'a, F>, _program: &Program<F>) {
+    let Block { inputs, output } = self.block.clone();
+    let Inputs {
+      input,
+      curve,
+      drive,
+      mix,
+      oversampling,
+    } = inputs;
+
+    signals[curve].if_updated(|value| {
+      if let Some(curve) = Curve::from(value.round()) {
+        self.waveshaper.set_curve(curve)
+      }
+    });
+    signals[drive].if_updated(|value| self.waveshaper.set_drive(value));
+    signals[mix].if_updated(|value| self.waveshaper.set_mix(value));
+    signals[oversampling].if_updated(|value| {
+      if let Some(oversampling) = Oversampling::from(value.round()) {
+        self.waveshaper.set_oversampling(oversampling)
+      }
+    });
+
+    let input_value = signals[input].get();
+    signals[output].set(self.waveshaper.process(input_value));
+  }
+}
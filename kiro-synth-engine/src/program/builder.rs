@@ -5,7 +5,8 @@ use crate::program::blocks::expr::{self, ExprBuilder, OpRef};
 use crate::program::modulations::Modulations;
 use crate::program::references::{BlockRef, ParamRef, SignalRef, SignalRefs, SourceRef};
 use crate::program::{
-  Block, MaxBlocks, MaxParams, MaxSources, Param, ParamBlock, ParamValues, Program, Source,
+  Block, EffectSlot, EffectsBlock, MaxBlocks, MaxEffectBlocks, MaxEffectParams, MaxGlobalBlocks,
+  MaxGlobalSignals, MaxParams, MaxSources, Param, ParamBlock, ParamValues, Program, Source,
   VoiceBlock,
 };
 use crate::signal::Signal;
@@ -14,9 +15,15 @@ use crate::signal::Signal;
 pub struct ProgramBuilder<'a, F: Float> {
   signal_refs: SignalRefs,
   voice: VoiceBlock,
+  effects: EffectsBlock,
   sources: Vec<Source<'a>, MaxSources>,
   params: Vec<Param<'a, F>, MaxParams>,
   blocks: Vec<Block<F>, MaxBlocks>,
+  global_signals: Vec<SignalRef, MaxGlobalSignals>,
+  global_blocks: Vec<Block<F>, MaxGlobalBlocks>,
+  effect_slots: Vec<EffectSlot, MaxEffectBlocks>,
+  effect_blocks: Vec<Block<F>, MaxEffectBlocks>,
+  effect_params: Vec<ParamBlock, MaxEffectParams>,
   modulations: Modulations<F>,
 }
 
@@ -28,6 +35,9 @@ impl<'a, F: Float> ProgramBuilder<'a, F> {
       key: signal_refs.create(),
       velocity: signal_refs.create(),
       note_pitch: signal_refs.create(),
+      pitch_bend: signal_refs.create(),
+      pressure: signal_refs.create(),
+      timbre: signal_refs.create(),
       gate: signal_refs.create(),
       trigger: signal_refs.create(),
       off: signal_refs.create(),
@@ -35,12 +45,25 @@ impl<'a, F: Float> ProgramBuilder<'a, F> {
       output_right: signal_refs.create(),
     };
 
+    let effects = EffectsBlock {
+      input_left: signal_refs.create(),
+      input_right: signal_refs.create(),
+      output_left: signal_refs.create(),
+      output_right: signal_refs.create(),
+    };
+
     ProgramBuilder {
       signal_refs,
       voice,
+      effects,
       sources: Vec::new(),
       params: Vec::new(),
       blocks: Vec::new(),
+      global_signals: Vec::new(),
+      global_blocks: Vec::new(),
+      effect_slots: Vec::new(),
+      effect_blocks: Vec::new(),
+      effect_params: Vec::new(),
       modulations: Modulations::default(),
     }
   }
@@ -49,6 +72,10 @@ impl<'a, F: Float> ProgramBuilder<'a, F> {
     &self.voice
   }
 
+  pub fn effects(&self) -> &EffectsBlock {
+    &self.effects
+  }
+
   pub fn const_value(&mut self, value: F) -> SignalRef {
     let signal = self.signal_refs.create();
     self.blocks.push(Block::Const { value, signal }).unwrap();
@@ -97,6 +124,13 @@ impl<'a, F: Float> ProgramBuilder<'a, F> {
     self.signal_refs.create()
   }
 
+  /// Create a signal that is generated in the global section and is visible from every voice
+  pub fn global_signal(&mut self) -> SignalRef {
+    let signal = self.signal_refs.create();
+    self.global_signals.push(signal).unwrap();
+    signal
+  }
+
   pub fn modulation<P: Into<ParamRef>>(&mut self, param: P, source_ref: SourceRef, amount: F) {
     self
       .modulations
@@ -116,6 +150,59 @@ impl<'a, F: Float> ProgramBuilder<'a, F> {
     block_ref
   }
 
+  /// Add a block to the global section, which is processed once per sample for all the voices.
+  /// The signals of the global params are visible from every voice, but the outputs of other
+  /// blocks need to be created with `global_signal` to be visible.
+  pub fn global_block(&mut self, block: Block<F>) -> BlockRef {
+    if let Block::Param(ParamBlock {
+      out_signal_ref,
+      mod_signal_ref,
+      ..
+    }) = block
+    {
+      self.global_signals.push(out_signal_ref).unwrap();
+      self.global_signals.push(mod_signal_ref).unwrap();
+    }
+    let block_ref = BlockRef(self.global_blocks.len());
+    self.global_blocks.push(block).unwrap();
+    block_ref
+  }
+
+  /// Create the signals for a slot of the effects chain, with new signals for its inputs.
+  /// The block added with the slot needs to read from its inputs and write into its outputs.
+  pub fn effect_slot(
+    &mut self,
+    enabled: SignalRef,
+    output_left: SignalRef,
+    output_right: SignalRef,
+  ) -> EffectSlot {
+    EffectSlot {
+      input_left: self.signal(),
+      input_right: self.signal(),
+      output_left,
+      output_right,
+      enabled,
+    }
+  }
+
+  /// Add a slot with its block at the end of the effects chain.
+  /// The effects are processed in the same order they are added, after mixing all the voices,
+  /// and they share the signals with the global section.
+  pub fn effect(&mut self, slot: EffectSlot, block: Block<F>) -> BlockRef {
+    let block_ref = BlockRef(self.effect_blocks.len());
+    self.effect_slots.push(slot).unwrap();
+    self.effect_blocks.push(block).unwrap();
+    block_ref
+  }
+
+  /// Add a param for the effects chain. It is processed in the global section before the effects,
+  /// but unlike the global params, its signals are not copied into the voices.
+  pub fn effect_param(&mut self, param: ParamBlock) -> BlockRef {
+    let block_ref = BlockRef(self.effect_params.len());
+    self.effect_params.push(param).unwrap();
+    block_ref
+  }
+
   pub fn out(&mut self, left: SignalRef, right: SignalRef) -> BlockRef {
     let block_ref = BlockRef(self.blocks.len());
     self.blocks.push(Block::Out { left, right }).unwrap();
@@ -126,9 +213,15 @@ impl<'a, F: Float> ProgramBuilder<'a, F> {
     Program {
       signals_count: self.signal_refs.count(),
       voice: self.voice,
+      effects: self.effects,
       sources: self.sources,
       params: self.params,
       blocks: self.blocks,
+      global_signals: self.global_signals,
+      global_blocks: self.global_blocks,
+      effect_slots: self.effect_slots,
+      effect_blocks: self.effect_blocks,
+      effect_params: self.effect_params,
       modulations: self.modulations,
     }
   }
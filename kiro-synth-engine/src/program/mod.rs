@@ -16,11 +16,15 @@ pub use builder::ProgramBuilder;
 use modulations::Modulations;
 pub use references::*;
 
-pub type MaxSignals = consts::U256;
+pub type MaxSignals = consts::U384;
 pub type MaxSources = consts::U32;
 pub type MaxModulations = consts::U4;
-pub type MaxParams = consts::U128;
+pub type MaxParams = consts::U192;
 pub type MaxBlocks = consts::U128;
+pub type MaxGlobalSignals = consts::U128;
+pub type MaxGlobalBlocks = consts::U64;
+pub type MaxEffectBlocks = consts::U32;
+pub type MaxEffectParams = consts::U64;
 
 #[derive(Debug, Clone)]
 pub struct Source<'a> {
@@ -68,18 +72,50 @@ pub enum Block<F: Float> {
 
   Param(ParamBlock),
 
+  ClockDivider(clock_divider::Block),
+
+  Comparator(comparator::Block),
+
+  Compressor(compressor::Block),
+
+  Crossfade(crossfade::Block),
+
   DCA(dca::Block),
 
+  Delay(delay::Block),
+
   EG(envgen::Block),
 
+  EnvelopeFollower(envelope_follower::Block),
+
+  Equalizer(equalizer::Block),
+
   Expr(expr::Block<F>),
 
   Filter(filter::Block),
 
+  KarplusStrong(karplus_strong::Block),
+
   Lfo(lfo::Block),
 
+  Limiter(limiter::Block),
+
+  Mixer(mixer::Block),
+
+  ModFx(modfx::Block),
+
   Osc(osc::Block),
 
+  Reverb(reverb::Block),
+
+  RingMod(ringmod::Block),
+
+  SampleHold(sample_hold::Block),
+
+  SlewLimiter(slew_limiter::Block),
+
+  Waveshaper(waveshaper::Block),
+
   Out { left: SignalRef, right: SignalRef },
 }
 
@@ -88,6 +124,9 @@ pub struct VoiceBlock {
   pub key: SignalRef,
   pub velocity: SignalRef,
   pub note_pitch: SignalRef,
+  pub pitch_bend: SignalRef,
+  pub pressure: SignalRef,
+  pub timbre: SignalRef,
   pub gate: SignalRef,
   pub trigger: SignalRef,
   pub off: SignalRef,
@@ -95,13 +134,40 @@ pub struct VoiceBlock {
   pub output_right: SignalRef,
 }
 
+/// Signals to connect the mix of all the voices to the effects chain
+#[derive(Debug, Clone, Default)]
+pub struct EffectsBlock {
+  pub input_left: SignalRef,
+  pub input_right: SignalRef,
+  pub output_left: SignalRef,
+  pub output_right: SignalRef,
+}
+
+/// Signals of a slot in the effects chain.
+/// The slots are chained in the order they are added, so the output of one slot feeds the input
+/// of the next one. While the `enabled` signal is off, the input is copied to the output.
+#[derive(Debug, Clone, Copy)]
+pub struct EffectSlot {
+  pub input_left: SignalRef,
+  pub input_right: SignalRef,
+  pub output_left: SignalRef,
+  pub output_right: SignalRef,
+  pub enabled: SignalRef,
+}
+
 #[derive(Debug, Clone)]
 pub struct Program<'a, F: Float> {
   signals_count: usize,
   voice: VoiceBlock,
+  effects: EffectsBlock,
   sources: Vec<Source<'a>, MaxSources>,
   params: Vec<Param<'a, F>, MaxParams>,
   blocks: Vec<Block<F>, MaxBlocks>,
+  global_signals: Vec<SignalRef, MaxGlobalSignals>,
+  global_blocks: Vec<Block<F>, MaxGlobalBlocks>,
+  effect_slots: Vec<EffectSlot, MaxEffectBlocks>,
+  effect_blocks: Vec<Block<F>, MaxEffectBlocks>,
+  effect_params: Vec<ParamBlock, MaxEffectParams>,
   modulations: Modulations<F>,
 }
 
@@ -114,6 +180,10 @@ impl<'a, F: Float> Program<'a, F> {
     &self.voice
   }
 
+  pub fn effects(&self) -> &EffectsBlock {
+    &self.effects
+  }
+
   //  pub fn get_params_count(&self) -> usize {
   //    self.params.len()
   //  }
@@ -211,4 +281,29 @@ impl<'a, F: Float> Program<'a, F> {
   pub fn get_blocks(&self) -> &[Block<F>] {
     &*self.blocks
   }
+
+  /// Signals generated by the global section that are copied into every voice
+  pub fn get_global_signals(&self) -> &[SignalRef] {
+    self.global_signals.deref()
+  }
+
+  /// Blocks processed once per sample for all the voices
+  pub fn get_global_blocks(&self) -> &[Block<F>] {
+    self.global_blocks.deref()
+  }
+
+  /// Blocks processed in order after mixing all the voices
+  /// Slots of the effects chain, in the same order as the effect blocks
+  pub fn get_effect_slots(&self) -> &[EffectSlot] {
+    self.effect_slots.deref()
+  }
+
+  pub fn get_effect_blocks(&self) -> &[Block<F>] {
+    self.effect_blocks.deref()
+  }
+
+  /// Params of the effects chain, processed before the effects and not visible from the voices
+  pub fn get_effect_params(&self) -> &[ParamBlock] {
+    self.effect_params.deref()
+  }
 }
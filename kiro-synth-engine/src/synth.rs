@@ -3,46 +3,83 @@ use heapless::Vec;
 use ringbuf::Consumer;
 use typenum::marker_traits::Unsigned;
 
-use crate::event::{Event, Message};
+use kiro_synth_dsp::funcs::random::Random;
+
+use crate::event::{Event, Expression, Message, Pedal, NUM_EXPRESSIONS};
 use crate::float::Float;
+use crate::global_section::GlobalSection;
 use crate::globals::SynthGlobals;
 use crate::program::Program;
+use crate::tuning::TuningSlot;
 use crate::voice::Voice;
 
 pub type MaxVoices = consts::U32;
 
+pub const NUM_CHANNELS: usize = 16;
+
+/// The default range of the voice pitch bend in semitones, as recommended by MPE
+pub const DEFAULT_VOICE_PITCH_BEND_RANGE: f64 = 48.0;
+
+/// How much the velocity of the notes is scaled while the soft pedal is pressed
+pub const SOFT_PEDAL_VELOCITY: f64 = 0.6;
+
 pub struct Synth<'a, F: Float> {
   sample_rate: F,
   events: Consumer<Event<F>>,
+  tuning: TuningSlot,
   program: Program<'a, F>,
   globals: SynthGlobals<F>,
+  global_section: GlobalSection<F>,
   voices: Vec<Voice<F>, MaxVoices>,
   active_voices: Vec<usize, MaxVoices>,
   free_voices: Vec<usize, MaxVoices>,
+  channel_expressions: [[F; NUM_EXPRESSIONS]; NUM_CHANNELS],
+  voice_pitch_bend_range: F,
+  sustain: bool,
+  sostenuto: bool,
+  soft: bool,
 }
 
 impl<'a, F: Float> Synth<'a, F> {
   pub fn new(
     sample_rate: F,
     events: Consumer<Event<F>>,
+    tuning: TuningSlot,
     program: Program<'a, F>,
     globals: SynthGlobals<F>,
   ) -> Self {
+    // every block that generates random values gets a different seed
+    let mut seeds = Random::default();
+
     let mut voices: Vec<Voice<F>, MaxVoices> = Vec::new();
     let mut free_voices: Vec<usize, MaxVoices> = Vec::new();
     for index in 0..MaxVoices::to_usize() {
-      drop(voices.push(Voice::new(sample_rate, &program)));
+      drop(voices.push(Voice::new(sample_rate, &program, &mut seeds)));
       free_voices.push(MaxVoices::to_usize() - index - 1).unwrap();
     }
 
+    let global_section = GlobalSection::new(sample_rate, &program, &mut seeds);
+
+    let mut expressions = [F::zero(); NUM_EXPRESSIONS];
+    for expression in Expression::EXPRESSIONS.iter() {
+      expressions[*expression as usize] = expression.default_value();
+    }
+
     Synth {
       sample_rate,
       events,
+      tuning,
       program,
       globals,
+      global_section,
       voices,
       active_voices: Vec::new(),
       free_voices,
+      channel_expressions: [expressions; NUM_CHANNELS],
+      voice_pitch_bend_range: F::val(DEFAULT_VOICE_PITCH_BEND_RANGE),
+      sustain: false,
+      sostenuto: false,
+      soft: false,
     }
   }
 
@@ -58,19 +95,52 @@ impl<'a, F: Float> Synth<'a, F> {
     self.active_voices.last().map(|index| &self.voices[*index])
   }
 
+  pub fn get_global_section(&self) -> &GlobalSection<F> {
+    &self.global_section
+  }
+
   pub fn get_num_active_voices(&self) -> usize {
     self.active_voices.len()
   }
 
   pub fn prepare(&mut self) {
+    if let Some(frequencies) = self.tuning.try_take() {
+      self.globals.tuning = frequencies;
+      self.retune();
+    }
+
     while let Some(Event {
       timestamp: _,
       message,
     }) = self.events.pop()
     {
       match message {
-        Message::NoteOn { key, velocity } => self.note_on(key, velocity),
-        Message::NoteOff { key, velocity } => self.note_off(key, velocity),
+        Message::NoteOn {
+          channel,
+          key,
+          velocity,
+        } => self.note_on(channel, key, velocity),
+        Message::NoteOff {
+          channel,
+          key,
+          velocity,
+        } => self.note_off(channel, key, velocity),
+        Message::ChannelExpression {
+          channel,
+          expression,
+          value,
+        } => self.channel_expression(channel, expression, value),
+        Message::KeyExpression {
+          channel,
+          key,
+          expression,
+          value,
+        } => self.key_expression(channel, key, expression, value),
+        Message::Pedal { pedal, pressed } => self.pedal(pedal, pressed),
+        Message::VoicePitchBendRange { semitones } => {
+          self.voice_pitch_bend_range = semitones;
+          self.update_voice_pitch_bends();
+        }
         Message::ParamValue { param_ref, value } => {
           if let Some((_, param)) = self.program.get_param_mut(param_ref) {
             println!("{} = {:?}", param.id, value);
@@ -110,26 +180,173 @@ impl<'a, F: Float> Synth<'a, F> {
             .delete_modulation(param_ref, source_ref)
             .unwrap(); // TODO handle error
         }
+        Message::Tempo { bpm } => {
+          if let Some(bpm) = bpm.to_f64() {
+            self.globals.transport.set_bpm(bpm)
+          }
+        }
+        Message::SongPosition { beats } => {
+          if let Some(beats) = beats.to_f64() {
+            self.globals.transport.set_beats(beats)
+          }
+        }
       }
     }
   }
 
-  fn note_on(&mut self, key: u8, velocity: F) {
+  fn note_on(&mut self, channel: u8, key: u8, velocity: F) {
+    // the keys that are not mapped by the tuning do not play
+    if self.globals.tuning.frequency(key).is_none() {
+      return;
+    }
+
+    let velocity = if self.soft {
+      velocity * F::val(SOFT_PEDAL_VELOCITY)
+    } else {
+      velocity
+    };
+
+    let tuning = &self.globals.tuning;
+    self
+      .global_section
+      .note_on(&self.program, tuning, key, velocity);
     if let Some(index) = self.allocate_voice(key, velocity) {
       self.active_voices.push(index).unwrap();
-      self.voices[index].note_on(&self.program, key, velocity);
+      let tuning = &self.globals.tuning;
+      let voice = &mut self.voices[index];
+      voice.note_on(&self.program, tuning, channel, key, velocity);
+      if let Some(expressions) = self.channel_expressions.get(usize::from(channel)) {
+        for expression in Expression::EXPRESSIONS.iter() {
+          let value = expressions[*expression as usize];
+          let range = self.voice_pitch_bend_range;
+          voice.set_expression(&self.program, tuning, *expression, value, range);
+        }
+      }
       println!("{:?}", self.active_voices);
     }
   }
 
-  fn note_off(&mut self, key: u8, _velocity: F) {
+  fn note_off(&mut self, channel: u8, key: u8, _velocity: F) {
     for active_voice_index in 0..self.active_voices.len() {
       let voice_index = self.active_voices[active_voice_index];
       let voice = &mut self.voices[voice_index];
-      if voice.get_key(&self.program) == key {
-        voice.note_off(&self.program)
+      if voice.get_key(&self.program) == key
+        && voice.get_channel() == channel
+        && voice.is_gated(&self.program)
+        && !voice.is_note_off_pending()
+      {
+        if self.sustain || voice.is_sostenuto() {
+          voice.set_note_off_pending(true);
+        } else {
+          voice.note_off(&self.program)
+        }
+      }
+    }
+
+    self.update_global_section_gate();
+  }
+
+  fn pedal(&mut self, pedal: Pedal, pressed: bool) {
+    match pedal {
+      Pedal::Sustain => self.sustain = pressed,
+      Pedal::Sostenuto => {
+        // only the notes playing when the pedal is pressed are held by it
+        if pressed && !self.sostenuto {
+          for voice_index in self.active_voices.iter() {
+            let voice = &mut self.voices[*voice_index];
+            voice.set_sostenuto(voice.is_gated(&self.program));
+          }
+        } else if !pressed {
+          for voice_index in self.active_voices.iter() {
+            self.voices[*voice_index].set_sostenuto(false);
+          }
+        }
+        self.sostenuto = pressed;
+      }
+      Pedal::Soft => self.soft = pressed,
+    }
+
+    if !pressed {
+      self.release_pending_voices();
+    }
+  }
+
+  /// Releases the voices with a pending note off that are not held by any pedal anymore
+  fn release_pending_voices(&mut self) {
+    if self.sustain {
+      return;
+    }
+
+    for voice_index in self.active_voices.iter() {
+      let voice = &mut self.voices[*voice_index];
+      if voice.is_note_off_pending() && !voice.is_sostenuto() {
+        voice.note_off(&self.program);
       }
     }
+
+    self.update_global_section_gate();
+  }
+
+  fn update_global_section_gate(&mut self) {
+    let program = &self.program;
+    let voices = &self.voices;
+    let gated = self
+      .active_voices
+      .iter()
+      .any(|voice_index| voices[*voice_index].is_gated(program));
+    if !gated {
+      self.global_section.note_off(&self.program);
+    }
+  }
+
+  fn channel_expression(&mut self, channel: u8, expression: Expression, value: F) {
+    if let Some(expressions) = self.channel_expressions.get_mut(usize::from(channel)) {
+      expressions[expression as usize] = value;
+    }
+
+    let tuning = &self.globals.tuning;
+    let range = self.voice_pitch_bend_range;
+    for voice_index in self.active_voices.iter() {
+      let voice = &mut self.voices[*voice_index];
+      if voice.get_channel() == channel {
+        voice.set_expression(&self.program, tuning, expression, value, range);
+      }
+    }
+  }
+
+  fn key_expression(&mut self, channel: u8, key: u8, expression: Expression, value: F) {
+    let tuning = &self.globals.tuning;
+    let range = self.voice_pitch_bend_range;
+    for voice_index in self.active_voices.iter() {
+      let voice = &mut self.voices[*voice_index];
+      if voice.get_key(&self.program) == key && voice.get_channel() == channel {
+        voice.set_expression(&self.program, tuning, expression, value, range);
+      }
+    }
+  }
+
+  fn update_voice_pitch_bends(&mut self) {
+    let tuning = &self.globals.tuning;
+    let range = self.voice_pitch_bend_range;
+    for voice_index in self.active_voices.iter() {
+      let voice = &mut self.voices[*voice_index];
+      if let Some(expressions) = self
+        .channel_expressions
+        .get(usize::from(voice.get_channel()))
+      {
+        let value = expressions[Expression::PitchBend as usize];
+        voice.set_expression(&self.program, tuning, Expression::PitchBend, value, range);
+      }
+    }
+  }
+
+  /// Updates the pitch of the notes being played after a change in the tuning
+  fn retune(&mut self) {
+    let tuning = &self.globals.tuning;
+    self.global_section.retune(&self.program, tuning);
+    for voice_index in self.active_voices.iter() {
+      self.voices[*voice_index].retune(&self.program, tuning);
+    }
   }
 
   fn allocate_voice(&mut self, _key: u8, _velocity: F) -> Option<usize> {
@@ -139,13 +356,17 @@ impl<'a, F: Float> Synth<'a, F> {
   pub fn process(&mut self) -> (F, F) {
     let (mut left, mut right) = (F::zero(), F::zero());
 
+    self
+      .global_section
+      .process(&mut self.program, &self.globals);
+
     let mut freed_voices = false;
     let mut active_voice_index = 0;
     while active_voice_index < self.active_voices.len() {
       let voice_index = self.active_voices[active_voice_index];
       let voice = &mut self.voices[voice_index];
 
-      voice.process(&mut self.program, &self.globals);
+      voice.process(&mut self.program, &self.globals, &self.global_section);
       let (voice_left, voice_right) = voice.output(&self.program);
       left = left + voice_left;
       right = right + voice_right;
@@ -163,8 +384,17 @@ impl<'a, F: Float> Synth<'a, F> {
       println!("{:?}", self.active_voices);
     }
 
+    let (left, right) =
+      self
+        .global_section
+        .process_effects(&mut self.program, &self.globals, left, right);
+
     self.program.update_params();
 
+    if let Some(sample_rate) = self.sample_rate.to_f64() {
+      self.globals.transport.advance(sample_rate);
+    }
+
     (left, right)
   }
 }
@@ -172,3 +402,336 @@ impl<'a, F: Float> Synth<'a, F> {
 pub struct VoiceIter<'a, F: Float + 'a, I>(I)
 where
   I: Iterator<Item = &'a Voice<F>>;
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::program::{Block, ParamValues, ProgramBuilder, SignalRef};
+  use crate::tuning::KeyFrequencies;
+  use assert_approx_eq::assert_approx_eq;
+  use ringbuf::{Producer, RingBuffer};
+
+  const SAMPLE_RATE: f64 = 48000.0;
+
+  /// The synth is too big for the stack of the test threads when built without optimisations
+  fn run<T: FnOnce() + Send + 'static>(test: T) {
+    std::thread::Builder::new()
+      .stack_size(64 * 1024 * 1024)
+      .spawn(test)
+      .unwrap()
+      .join()
+      .unwrap();
+  }
+
+  struct TestSynth {
+    synth: Synth<'static, f64>,
+    events: Producer<Event<f64>>,
+    tuning: TuningSlot,
+  }
+
+  impl TestSynth {
+    fn new(program: Program<'static, f64>) -> Self {
+      let (events, consumer) = RingBuffer::new(32).split();
+      let tuning = TuningSlot::new();
+      let globals = SynthGlobals::new();
+      let synth = Synth::new(SAMPLE_RATE, consumer, tuning.clone(), program, globals);
+      TestSynth {
+        synth,
+        events,
+        tuning,
+      }
+    }
+
+    fn send(&mut self, message: Message<f64>) {
+      self.events.push(Event::new(0, message)).unwrap();
+      self.synth.prepare();
+    }
+
+    fn note_on(&mut self, channel: u8, key: u8, velocity: f64) {
+      self.send(Message::NoteOn {
+        channel,
+        key,
+        velocity,
+      });
+    }
+
+    fn note_off(&mut self, channel: u8, key: u8) {
+      self.send(Message::NoteOff {
+        channel,
+        key,
+        velocity: 0.0,
+      });
+    }
+
+    fn pedal(&mut self, pedal: Pedal, pressed: bool) {
+      self.send(Message::Pedal { pedal, pressed });
+    }
+
+    /// The active voices playing a key in a channel, from the oldest to the newest
+    fn voices(&self, channel: u8, key: u8) -> impl Iterator<Item = &Voice<f64>> + '_ {
+      let synth = &self.synth;
+      synth
+        .active_voices
+        .iter()
+        .map(move |index| &synth.voices[*index])
+        .filter(move |voice| voice.get_channel() == channel && voice.get_key(&synth.program) == key)
+    }
+
+    fn voice_signal(&self, channel: u8, key: u8, signal_ref: SignalRef) -> f64 {
+      let voice = self.voices(channel, key).last().unwrap();
+      voice.get_signals()[signal_ref.0].get()
+    }
+
+    fn is_gated(&self, channel: u8, key: u8) -> bool {
+      self
+        .voices(channel, key)
+        .any(|voice| voice.is_gated(&self.synth.program))
+    }
+
+    fn is_global_gated(&self) -> bool {
+      let gate = self.synth.program.voice().gate;
+      self.synth.global_section.get_signal(gate) > 0.0
+    }
+  }
+
+  fn empty_program() -> Program<'static, f64> {
+    ProgramBuilder::new().build()
+  }
+
+  #[test]
+  fn global_outputs_are_visible_from_the_voices() {
+    run(|| {
+      let mut builder = ProgramBuilder::new();
+      let values = ParamValues {
+        initial_value: 0.25,
+        origin: 0.0,
+        min: 0.0,
+        max: 1.0,
+        resolution: 0.01,
+      };
+      let param = builder.param("global", values);
+      builder.global_block(Block::Param(param.clone()));
+      let mut double = builder.expr(|expr| expr.mul_signal_value(param.out_signal_ref, 2.0));
+      double.output = builder.global_signal();
+      let output = double.output;
+      builder.global_block(Block::Expr(double));
+
+      let mut synth = TestSynth::new(builder.build());
+      synth.note_on(0, 60, 1.0);
+      synth.synth.process();
+
+      assert_approx_eq!(synth.voice_signal(0, 60, output), 0.5);
+    });
+  }
+
+  #[test]
+  fn global_gate_follows_the_last_note() {
+    run(|| {
+      let mut synth = TestSynth::new(empty_program());
+      assert!(!synth.is_global_gated());
+
+      synth.note_on(0, 60, 1.0);
+      synth.note_on(0, 64, 1.0);
+      assert!(synth.is_global_gated());
+      let key = synth.synth.program.voice().key;
+      assert_approx_eq!(synth.synth.global_section.get_signal(key), 64.0);
+
+      synth.note_off(0, 64);
+      assert!(synth.is_global_gated());
+
+      synth.note_off(0, 60);
+      assert!(!synth.is_global_gated());
+    });
+  }
+
+  #[test]
+  fn sustain_defers_the_note_off_until_the_pedal_is_released() {
+    run(|| {
+      let mut synth = TestSynth::new(empty_program());
+      synth.note_on(0, 60, 1.0);
+      synth.pedal(Pedal::Sustain, true);
+      synth.note_off(0, 60);
+      assert!(synth.is_gated(0, 60));
+      assert!(synth.is_global_gated());
+
+      synth.pedal(Pedal::Sustain, false);
+      assert!(!synth.is_gated(0, 60));
+      assert!(!synth.is_global_gated());
+    });
+  }
+
+  #[test]
+  fn sostenuto_holds_only_the_notes_gated_when_pressed() {
+    run(|| {
+      let mut synth = TestSynth::new(empty_program());
+      synth.note_on(0, 60, 1.0);
+      synth.pedal(Pedal::Sostenuto, true);
+      synth.note_on(0, 64, 1.0);
+
+      synth.note_off(0, 60);
+      synth.note_off(0, 64);
+      assert!(synth.is_gated(0, 60));
+      assert!(!synth.is_gated(0, 64));
+
+      synth.pedal(Pedal::Sostenuto, false);
+      assert!(!synth.is_gated(0, 60));
+      assert!(!synth.is_global_gated());
+    });
+  }
+
+  #[test]
+  fn retriggered_key_with_a_pending_note_off() {
+    run(|| {
+      let mut synth = TestSynth::new(empty_program());
+      synth.pedal(Pedal::Sustain, true);
+      synth.note_on(0, 60, 1.0);
+      synth.note_off(0, 60);
+      synth.note_on(0, 60, 1.0);
+      assert_eq!(synth.voices(0, 60).count(), 2);
+
+      // the note off of the new note does not wait for the old one
+      synth.note_off(0, 60);
+      let pending = synth
+        .voices(0, 60)
+        .filter(|voice| voice.is_note_off_pending())
+        .count();
+      assert_eq!(pending, 2);
+
+      synth.pedal(Pedal::Sustain, false);
+      assert!(!synth.is_gated(0, 60));
+      assert!(!synth.is_global_gated());
+    });
+  }
+
+  #[test]
+  fn soft_pedal_scales_the_velocity_of_new_notes() {
+    run(|| {
+      let mut synth = TestSynth::new(empty_program());
+      let velocity = synth.synth.program.voice().velocity;
+      synth.note_on(0, 60, 1.0);
+      synth.pedal(Pedal::Soft, true);
+      synth.note_on(0, 64, 0.5);
+      assert_approx_eq!(synth.voice_signal(0, 60, velocity), 1.0);
+      assert_approx_eq!(
+        synth.voice_signal(0, 64, velocity),
+        0.5 * SOFT_PEDAL_VELOCITY
+      );
+
+      synth.pedal(Pedal::Soft, false);
+      synth.note_on(0, 67, 0.5);
+      assert_approx_eq!(synth.voice_signal(0, 67, velocity), 0.5);
+    });
+  }
+
+  #[test]
+  fn channel_expressions_are_applied_at_note_on() {
+    run(|| {
+      let mut synth = TestSynth::new(empty_program());
+      let voice = synth.synth.program.voice().clone();
+      synth.send(Message::ChannelExpression {
+        channel: 1,
+        expression: Expression::Pressure,
+        value: 0.7,
+      });
+      synth.send(Message::ChannelExpression {
+        channel: 1,
+        expression: Expression::Timbre,
+        value: 0.2,
+      });
+      synth.send(Message::ChannelExpression {
+        channel: 1,
+        expression: Expression::PitchBend,
+        value: 0.25,
+      });
+      synth.note_on(1, 69, 1.0);
+
+      assert_approx_eq!(synth.voice_signal(1, 69, voice.pressure), 0.7);
+      assert_approx_eq!(synth.voice_signal(1, 69, voice.timbre), 0.2);
+      assert_approx_eq!(synth.voice_signal(1, 69, voice.pitch_bend), 0.25);
+      // a quarter of the default range of 48 semitones is one octave
+      assert_approx_eq!(synth.voice_signal(1, 69, voice.note_pitch), 880.0);
+    });
+  }
+
+  #[test]
+  fn channel_expressions_do_not_leak_to_other_channels() {
+    run(|| {
+      let mut synth = TestSynth::new(empty_program());
+      let voice = synth.synth.program.voice().clone();
+      synth.send(Message::ChannelExpression {
+        channel: 1,
+        expression: Expression::Timbre,
+        value: 0.2,
+      });
+      synth.note_on(1, 60, 1.0);
+      synth.note_on(2, 60, 1.0);
+      assert_approx_eq!(synth.voice_signal(2, 60, voice.timbre), 0.5);
+
+      synth.send(Message::ChannelExpression {
+        channel: 2,
+        expression: Expression::Pressure,
+        value: 0.9,
+      });
+      assert_approx_eq!(synth.voice_signal(1, 60, voice.pressure), 0.0);
+      assert_approx_eq!(synth.voice_signal(2, 60, voice.pressure), 0.9);
+      assert_approx_eq!(synth.voice_signal(1, 60, voice.timbre), 0.2);
+    });
+  }
+
+  #[test]
+  fn voice_pitch_bend_range_retunes_the_active_voices() {
+    run(|| {
+      let mut synth = TestSynth::new(empty_program());
+      let note_pitch = synth.synth.program.voice().note_pitch;
+      synth.send(Message::ChannelExpression {
+        channel: 1,
+        expression: Expression::PitchBend,
+        value: -0.5,
+      });
+      synth.note_on(1, 69, 1.0);
+      synth.note_on(2, 69, 1.0);
+      assert_approx_eq!(synth.voice_signal(1, 69, note_pitch), 440.0 / 4.0);
+
+      synth.send(Message::VoicePitchBendRange { semitones: 24.0 });
+      assert_approx_eq!(synth.voice_signal(1, 69, note_pitch), 440.0 / 2.0);
+      assert_approx_eq!(synth.voice_signal(2, 69, note_pitch), 440.0);
+    });
+  }
+
+  #[test]
+  fn key_pressure_reaches_only_the_voices_of_the_key_and_channel() {
+    run(|| {
+      let mut synth = TestSynth::new(empty_program());
+      let pressure = synth.synth.program.voice().pressure;
+      synth.note_on(1, 60, 1.0);
+      synth.note_on(1, 64, 1.0);
+      synth.note_on(2, 60, 1.0);
+
+      synth.send(Message::KeyExpression {
+        channel: 1,
+        key: 60,
+        expression: Expression::Pressure,
+        value: 0.8,
+      });
+      assert_approx_eq!(synth.voice_signal(1, 60, pressure), 0.8);
+      assert_approx_eq!(synth.voice_signal(1, 64, pressure), 0.0);
+      assert_approx_eq!(synth.voice_signal(2, 60, pressure), 0.0);
+    });
+  }
+
+  #[test]
+  fn new_tuning_retunes_the_active_voices() {
+    run(|| {
+      let mut synth = TestSynth::new(empty_program());
+      let note_pitch = synth.synth.program.voice().note_pitch;
+      synth.note_on(0, 69, 1.0);
+      assert_approx_eq!(synth.voice_signal(0, 69, note_pitch), 440.0);
+
+      synth.tuning.send(KeyFrequencies::equal_temperament(432.0));
+      synth.synth.prepare();
+      assert_approx_eq!(synth.voice_signal(0, 69, note_pitch), 432.0);
+      synth.note_on(0, 57, 1.0);
+      assert_approx_eq!(synth.voice_signal(0, 57, note_pitch), 216.0);
+    });
+  }
+}
@@ -0,0 +1,179 @@
+use crate::float::Float;
+
+/// A note division used to sync rates to the tempo
+#[derive(Debug, Clone, Copy)]
+pub struct Division {
+  pub name: &'static str,
+  /// Length in beats (quarter notes)
+  pub beats: f64,
+}
+
+impl Division {
+  const DIVISIONS: [Division; 17] = [
+    Division::new("4/1", 16.0),
+    Division::new("2/1", 8.0),
+    Division::new("1/1", 4.0),
+    Division::new("1/2D", 3.0),
+    Division::new("1/2", 2.0),
+    Division::new("1/2T", 4.0 / 3.0),
+    Division::new("1/4D", 1.5),
+    Division::new("1/4", 1.0),
+    Division::new("1/4T", 2.0 / 3.0),
+    Division::new("1/8D", 0.75),
+    Division::new("1/8", 0.5),
+    Division::new("1/8T", 1.0 / 3.0),
+    Division::new("1/16D", 0.375),
+    Division::new("1/16", 0.25),
+    Division::new("1/16T", 1.0 / 6.0),
+    Division::new("1/32", 0.125),
+    Division::new("1/32T", 1.0 / 12.0),
+  ];
+
+  const fn new(name: &'static str, beats: f64) -> Self {
+    Division { name, beats }
+  }
+
+  pub fn count() -> usize {
+    Self::DIVISIONS.len()
+  }
+
+  /// The index of the quarter note division
+  pub fn quarter_index() -> usize {
+    7
+  }
+
+  pub fn from<F: Float>(value: F) -> Option<Self> {
+    value
+      .to_usize()
+      .and_then(|index| Self::DIVISIONS.get(index).copied())
+  }
+
+  /// The frequency in Hz for a given tempo
+  pub fn rate(&self, bpm: f64) -> f64 {
+    bpm / (60.0 * self.beats)
+  }
+}
+
+/// Tempo and song position shared by all the voices.
+/// The position keeps running at the current tempo between updates from the host.
+#[derive(Debug, Clone)]
+pub struct Transport {
+  bpm: f64,
+  beats: f64,
+}
+
+impl Default for Transport {
+  fn default() -> Self {
+    Transport {
+      bpm: 120.0,
+      beats: 0.0,
+    }
+  }
+}
+
+impl Transport {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// The tempo in beats per minute
+  pub fn bpm(&self) -> f64 {
+    self.bpm
+  }
+
+  /// The song position in beats (quarter notes)
+  pub fn beats(&self) -> f64 {
+    self.beats
+  }
+
+  pub fn set_bpm(&mut self, bpm: f64) {
+    if bpm > 0.0 {
+      self.bpm = bpm;
+    }
+  }
+
+  pub fn set_beats(&mut self, beats: f64) {
+    self.beats = beats.max(0.0);
+  }
+
+  /// The position between [0.0, 1.0) inside a cycle of the given division
+  pub fn cycle_position(&self, division: &Division) -> f64 {
+    (self.beats / division.beats).fract()
+  }
+
+  /// Move the position one sample forward
+  pub fn advance(&mut self, sample_rate: f64) {
+    self.beats += self.bpm / (60.0 * sample_rate);
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use assert_approx_eq::assert_approx_eq;
+
+  #[test]
+  fn division_table() {
+    assert_eq!(Division::count(), 17);
+
+    let quarter = Division::from(Division::quarter_index() as f64).unwrap();
+    assert_eq!(quarter.name, "1/4");
+    assert_approx_eq!(quarter.beats, 1.0);
+
+    // the straight divisions go from the longest to the shortest
+    let straight = ["4/1", "2/1", "1/1", "1/2", "1/4", "1/8", "1/16", "1/32"];
+    let mut last_beats = 32.0;
+    for name in straight.iter() {
+      let division = Division::DIVISIONS
+        .iter()
+        .find(|d| d.name == *name)
+        .unwrap();
+      assert_approx_eq!(division.beats, last_beats / 2.0);
+      last_beats = division.beats;
+    }
+
+    // the dotted and triplet divisions are relative to the straight one
+    let eighth = Division::from(10.0).unwrap();
+    assert_eq!(eighth.name, "1/8");
+    let dotted = Division::from(9.0).unwrap();
+    assert_approx_eq!(dotted.beats, eighth.beats * 1.5);
+    let triplet = Division::from(11.0).unwrap();
+    assert_approx_eq!(triplet.beats, eighth.beats * 2.0 / 3.0);
+
+    assert!(Division::from(Division::count() as f64).is_none());
+    assert!(Division::from(-1.0).is_none());
+  }
+
+  #[test]
+  fn division_rate() {
+    let quarter = Division::from(Division::quarter_index() as f64).unwrap();
+    assert_approx_eq!(quarter.rate(120.0), 2.0);
+    let whole = Division::from(2.0).unwrap();
+    assert_approx_eq!(whole.rate(120.0), 0.5);
+    let sixteenth_triplet = Division::from(14.0).unwrap();
+    assert_approx_eq!(sixteenth_triplet.rate(60.0), 6.0);
+  }
+
+  #[test]
+  fn transport_position() {
+    let mut transport = Transport::new();
+    assert_approx_eq!(transport.bpm(), 120.0);
+
+    // half a second at 120 bpm is one beat
+    for _ in 0..500 {
+      transport.advance(1000.0);
+    }
+    assert_approx_eq!(transport.beats(), 1.0, 1e-9);
+
+    let half = Division::from(4.0).unwrap();
+    assert_approx_eq!(transport.cycle_position(&half), 0.5, 1e-9);
+
+    transport.set_bpm(0.0);
+    assert_approx_eq!(transport.bpm(), 120.0);
+    transport.set_bpm(60.0);
+    transport.set_beats(-2.0);
+    assert_approx_eq!(transport.beats(), 0.0);
+    transport.set_beats(6.5);
+    assert_approx_eq!(transport.cycle_position(&half), 0.25);
+  }
+}
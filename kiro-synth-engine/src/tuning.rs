@@ -0,0 +1,113 @@
+//! The frequencies played by the keys.
+//!
+//! The tuning itself (scales, keyboard mappings, master tune and transpose) is managed by the host,
+//! which sends the resulting frequency of every key, so the engine does not need to allocate for it.
+
+use core::fmt;
+use std::sync::{Arc, Mutex};
+
+pub const NUM_KEYS: usize = 128;
+
+/// Frequency of A4 in the standard tuning
+pub const DEFAULT_REFERENCE_FREQUENCY: f64 = 440.0;
+
+/// The frequency in Hz played by every key, or None if the key is not mapped
+pub type FrequencyTable = [Option<f64>; NUM_KEYS];
+
+#[derive(Clone, Copy)]
+pub struct KeyFrequencies {
+  frequencies: FrequencyTable,
+}
+
+impl Default for KeyFrequencies {
+  fn default() -> Self {
+    KeyFrequencies::equal_temperament(DEFAULT_REFERENCE_FREQUENCY)
+  }
+}
+
+impl fmt::Debug for KeyFrequencies {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.frequencies.iter()).finish()
+  }
+}
+
+impl KeyFrequencies {
+  pub fn new(frequencies: FrequencyTable) -> Self {
+    KeyFrequencies { frequencies }
+  }
+
+  /// The twelve tone equal temperament with the given frequency for A4
+  pub fn equal_temperament(reference_frequency: f64) -> Self {
+    let mut frequencies = [None; NUM_KEYS];
+    for (key, frequency) in frequencies.iter_mut().enumerate() {
+      *frequency = Some(reference_frequency * 2.0f64.powf((key as f64 - 69.0) / 12.0));
+    }
+    KeyFrequencies { frequencies }
+  }
+
+  /// The frequency in Hz for a key, or None if the key is not mapped
+  pub fn frequency(&self, key: u8) -> Option<f64> {
+    self.frequencies.get(key as usize).copied().flatten()
+  }
+}
+
+/// Hands the frequencies of the keys from the host to the synth, outside of the events,
+/// so the events stay small. The host replaces the pending frequencies on every change,
+/// and the synth takes the latest ones without waiting while the host is writing them.
+#[derive(Debug, Clone, Default)]
+pub struct TuningSlot(Arc<Mutex<Option<KeyFrequencies>>>);
+
+impl TuningSlot {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Replaces the frequencies pending to be taken by the synth
+  pub fn send(&self, frequencies: KeyFrequencies) {
+    let mut pending = self.0.lock().unwrap_or_else(|err| err.into_inner());
+    *pending = Some(frequencies);
+  }
+
+  /// Takes the pending frequencies, if any, without blocking
+  pub fn try_take(&self) -> Option<KeyFrequencies> {
+    self
+      .0
+      .try_lock()
+      .ok()
+      .and_then(|mut pending| pending.take())
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use assert_approx_eq::assert_approx_eq;
+
+  #[test]
+  fn key_frequencies_default_is_equal_temperament() {
+    let frequencies = KeyFrequencies::default();
+    assert_approx_eq!(frequencies.frequency(69).unwrap(), 440.0);
+    assert_approx_eq!(frequencies.frequency(60).unwrap(), 261.6256, 1e-4);
+    assert_approx_eq!(frequencies.frequency(127).unwrap(), 12543.854, 1e-3);
+    assert_eq!(frequencies.frequency(128), None);
+
+    let mut table = [None; NUM_KEYS];
+    table[60] = Some(256.0);
+    let frequencies = KeyFrequencies::new(table);
+    assert_approx_eq!(frequencies.frequency(60).unwrap(), 256.0);
+    assert_eq!(frequencies.frequency(61), None);
+  }
+
+  #[test]
+  fn tuning_slot_keeps_the_latest_frequencies() {
+    let slot = TuningSlot::new();
+    assert!(slot.try_take().is_none());
+
+    let receiver = slot.clone();
+    slot.send(KeyFrequencies::equal_temperament(432.0));
+    slot.send(KeyFrequencies::equal_temperament(415.0));
+    let frequencies = receiver.try_take().unwrap();
+    assert_approx_eq!(frequencies.frequency(69).unwrap(), 415.0);
+    assert!(receiver.try_take().is_none());
+  }
+}
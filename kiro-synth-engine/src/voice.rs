@@ -1,20 +1,33 @@
 use core::ops::DerefMut;
 use heapless::Vec;
 
+use kiro_synth_dsp::funcs::random::Random;
+
+use crate::event::Expression;
 use crate::float::Float;
+use crate::global_section::GlobalSection;
 use crate::globals::SynthGlobals;
-use crate::key_freqs::KEY_FREQ;
 use crate::processor::Processor;
 use crate::program::{Block, MaxBlocks, MaxSignals, Program};
 use crate::signal::{Signal, SignalBus};
+use crate::tuning::KeyFrequencies;
 
 pub struct Voice<F: Float> {
   signals: Vec<Signal<F>, MaxSignals>,
   processors: Vec<Processor<F>, MaxBlocks>,
+  /// The MIDI channel of the note
+  channel: u8,
+  /// The pitch bend of the voice expression in semitones
+  pitch_shift: F,
+  /// The note off was received but the voice is held by a pedal
+  note_off_pending: bool,
+  /// The voice was playing when the sostenuto pedal was pressed
+  sostenuto: bool,
 }
 
 impl<F: Float> Voice<F> {
-  pub(crate) fn new(sample_rate: F, program: &Program<F>) -> Self {
+  /// The seeds for the blocks that generate random values are taken from `seeds`
+  pub(crate) fn new(sample_rate: F, program: &Program<F>, seeds: &mut Random) -> Self {
     let mut signals: Vec<Signal<F>, MaxSignals> = Vec::new();
     for _ in 0..program.get_signals_count() {
       signals.push(Signal::default()).unwrap();
@@ -25,7 +38,8 @@ impl<F: Float> Voice<F> {
       if let Block::Const { value, signal } = block {
         signals[signal.0].set(*value)
       } else {
-        processors.push(Processor::new(sample_rate, block)).unwrap();
+        let processor = Processor::new(sample_rate, block, seeds.next_u32());
+        processors.push(processor).unwrap();
       }
     }
 
@@ -34,6 +48,10 @@ impl<F: Float> Voice<F> {
     Voice {
       signals,
       processors,
+      channel: 0,
+      pitch_shift: F::zero(),
+      note_off_pending: false,
+      sostenuto: false,
     }
   }
 
@@ -44,11 +62,35 @@ impl<F: Float> Voice<F> {
   pub(crate) fn get_key(&self, program: &Program<F>) -> u8 {
     self.signals[program.voice().key.0].get().to_u8().unwrap()
   }
+
+  pub(crate) fn get_channel(&self) -> u8 {
+    self.channel
+  }
   //
   //  pub fn get_velocity(&self, program: &Program<F>) -> F {
   //    self.signals[program.voice().velocity.0].get()
   //  }
 
+  pub(crate) fn is_gated(&self, program: &Program<F>) -> bool {
+    self.signals[program.voice().gate.0].get() > F::zero()
+  }
+
+  pub(crate) fn is_note_off_pending(&self) -> bool {
+    self.note_off_pending
+  }
+
+  pub(crate) fn set_note_off_pending(&mut self, pending: bool) {
+    self.note_off_pending = pending;
+  }
+
+  pub(crate) fn is_sostenuto(&self) -> bool {
+    self.sostenuto
+  }
+
+  pub(crate) fn set_sostenuto(&mut self, sostenuto: bool) {
+    self.sostenuto = sostenuto;
+  }
+
   pub(crate) fn is_off(&self, program: &Program<F>) -> bool {
     self.signals[program.voice().off.0].get() == F::one()
   }
@@ -73,23 +115,72 @@ impl<F: Float> Voice<F> {
     }
   }
 
-  pub(crate) fn note_on(&mut self, program: &Program<F>, key: u8, velocity: F) {
+  pub(crate) fn note_on(
+    &mut self,
+    program: &Program<F>,
+    tuning: &KeyFrequencies,
+    channel: u8,
+    key: u8,
+    velocity: F,
+  ) {
     self.reset(program);
+    self.channel = channel;
+    self.pitch_shift = F::zero();
+    self.note_off_pending = false;
+    self.sostenuto = false;
     let voice = program.voice();
     self.signals[voice.key.0].set(F::val(key));
     self.signals[voice.velocity.0].set(velocity);
-    self.signals[voice.note_pitch.0].set(F::val(KEY_FREQ[(key & 0x7f) as usize]));
+    self.retune(program, tuning);
     self.signals[voice.gate.0].set(F::one());
     self.signals[voice.trigger.0].set(F::one());
   }
 
+  pub(crate) fn retune(&mut self, program: &Program<F>, tuning: &KeyFrequencies) {
+    if let Some(frequency) = tuning.frequency(self.get_key(program)) {
+      let shift = F::val(2.0).powf(self.pitch_shift / F::val(12.0));
+      self.signals[program.voice().note_pitch.0].set(F::val(frequency) * shift);
+    }
+  }
+
+  /// Changes an expression of the voice, where the pitch bend is scaled by `pitch_bend_range` in semitones
+  pub(crate) fn set_expression(
+    &mut self,
+    program: &Program<F>,
+    tuning: &KeyFrequencies,
+    expression: Expression,
+    value: F,
+    pitch_bend_range: F,
+  ) {
+    let voice = program.voice();
+    match expression {
+      Expression::PitchBend => {
+        self.signals[voice.pitch_bend.0].set(value);
+        self.pitch_shift = value * pitch_bend_range;
+        self.retune(program, tuning);
+      }
+      Expression::Pressure => self.signals[voice.pressure.0].set(value),
+      Expression::Timbre => self.signals[voice.timbre.0].set(value),
+    }
+  }
+
   pub(crate) fn note_off(&mut self, program: &Program<F>) {
+    self.note_off_pending = false;
     self.signals[program.voice().gate.0].set(F::zero());
   }
 
-  pub(crate) fn process(&mut self, program: &mut Program<F>, synth_globals: &SynthGlobals<F>) {
+  pub(crate) fn process(
+    &mut self,
+    program: &mut Program<F>,
+    synth_globals: &SynthGlobals<F>,
+    global_section: &GlobalSection<F>,
+  ) {
     let mut signals = SignalBus::new(self.signals.deref_mut());
 
+    for signal_ref in program.get_global_signals() {
+      signals[*signal_ref].set(global_section.get_signal(*signal_ref));
+    }
+
     for processor in self.processors.iter_mut() {
       processor.process(&mut signals, program, synth_globals)
     }
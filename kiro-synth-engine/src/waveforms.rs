@@ -2,9 +2,11 @@ use heapless::consts;
 use heapless::Vec;
 
 use kiro_synth_dsp::oscillators::osc_waveform::OscWaveform;
+use kiro_synth_dsp::waveforms::sample_and_hold::SampleAndHold;
 use kiro_synth_dsp::waveforms::saw_blep::{self, SawBlep};
 use kiro_synth_dsp::waveforms::saw_trivial::SawTrivial;
 use kiro_synth_dsp::waveforms::sine_parabolic::SineParabolic;
+use kiro_synth_dsp::waveforms::smooth_random::SmoothRandom;
 use kiro_synth_dsp::waveforms::triangle_dpw2x::TriangleDpw2x;
 use kiro_synth_dsp::waveforms::triangle_trivial::TriangleTrivial;
 
@@ -68,6 +70,8 @@ impl<F: Float> LfoWaveforms<F> {
           OscWaveform::TriangleTrivial(TriangleTrivial::default()),
         ),
         ("saw", OscWaveform::SawTrivial(SawTrivial::default())),
+        ("s&h", OscWaveform::SampleAndHold(SampleAndHold::default())),
+        ("rnd", OscWaveform::SmoothRandom(SmoothRandom::default())),
       ])
       .ok();
     LfoWaveforms(waveforms)
@@ -1,30 +1,52 @@
 mod audio;
 mod midi;
 mod synth;
+mod tuning;
 pub mod ui;
 
+use std::fs;
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use ringbuf::RingBuffer;
 
+use kiro_midi_core::controllers::{
+  ControllerMessage, Controllers, ParameterChange, RPN_COARSE_TUNING, RPN_FINE_TUNING,
+  RPN_MPE_CONFIGURATION, RPN_PITCH_BEND_SENSITIVITY,
+};
 use kiro_midi_core::messages::Message as MidiMessage;
+use kiro_midi_core::tuning::TuningMessage;
 use kiro_synth_dsp::float::Float;
-use kiro_synth_engine::event::Event;
+use kiro_synth_engine::event::{Event, Expression, Pedal};
 use kiro_synth_engine::globals::SynthGlobals;
 use kiro_synth_engine::program::Program;
 use kiro_synth_engine::synth::Synth;
+use kiro_synth_engine::tuning::{TuningSlot, DEFAULT_REFERENCE_FREQUENCY};
 
 use crate::audio::AudioDriver;
 use crate::midi::drivers::{MidiDriver, MidiHandler};
 use crate::midi::mapper::MidiMapper;
+use crate::midi::mpe::{ChannelRole, MpeZones};
+use crate::midi::tempo::{TempoTracker, TempoUpdate};
 use crate::synth::program::kiro::KiroModule;
 use crate::synth::{SynthAudioHandler, SynthClient, SynthClientMutex, SynthFeedback};
+use crate::tuning::{KeyboardMapping, Scale, Tuning};
 use crate::ui::data::AppData;
 
 const SAMPLE_RATE: u32 = 44100;
 
-const MIDI_BUFFER_SIZE: usize = 512;
+/// The controllers of the pedals are on/off switches, pressed from this value
+const PEDAL_PRESSED_THRESHOLD: u8 = 64;
+
+/// The controller for the volume of a channel, mapped to the master amplitude
+const MIDI_CHANNEL_VOLUME: u8 = 7;
+
+/// The controller used by the MPE controllers for the timbre of every note
+const MPE_TIMBRE_CONTROLLER: u8 = 74;
+
+// Enough for a MIDI Tuning Standard message that retunes all the keys
+const MIDI_BUFFER_SIZE: usize = 1024;
 static mut MIDI_BUFFER: [u8; MIDI_BUFFER_SIZE] = [0; MIDI_BUFFER_SIZE];
 
 fn main() -> Result<()> {
@@ -38,6 +60,7 @@ fn main() -> Result<()> {
 
   let events_ring_buffer = RingBuffer::<Event<f32>>::new(1024);
   let (events_producer, events_consumer) = events_ring_buffer.split();
+  let tuning_slot = TuningSlot::new();
 
   // FEEDBACK
 
@@ -49,9 +72,20 @@ fn main() -> Result<()> {
   let synth_client = Arc::new(Mutex::new(SynthClient::new(
     synth_globals.clone(),
     events_producer,
+    tuning_slot.clone(),
     feedback_consumer,
   )));
 
+  // TUNING
+
+  let (options, files): (Vec<String>, Vec<String>) = std::env::args()
+    .skip(1)
+    .partition(|arg| arg.starts_with("--"));
+
+  if let Some(tuning) = load_tuning(files.into_iter())? {
+    synth_client.lock().unwrap().send_tuning(tuning);
+  }
+
   // PROGRAM
 
   let (program, module) = KiroModule::new_program(
@@ -67,12 +101,22 @@ fn main() -> Result<()> {
   // MIDI
 
   let midi_mapper = create_midi_mapper(&program, &module);
-  let handler = EventsMidiHandler::new(midi_mapper, synth_client.clone());
+  let mut mpe_zones = MpeZones::new();
+  if options.iter().any(|option| option == "--mpe") {
+    mpe_zones.set_lower_zone(15);
+  }
+  let handler = EventsMidiHandler::new(midi_mapper, mpe_zones, synth_client.clone());
   let _midi_driver = MidiDriver::new("kiro-synth", midi_buffer, handler)?;
 
   // SYNTH
 
-  let synth = Synth::new(SAMPLE_RATE as f32, events_consumer, program, synth_globals);
+  let synth = Synth::new(
+    SAMPLE_RATE as f32,
+    events_consumer,
+    tuning_slot,
+    program,
+    synth_globals,
+  );
 
   // AUDIO
 
@@ -86,26 +130,88 @@ fn main() -> Result<()> {
   Ok(())
 }
 
+/// Loads the tuning from the Scala files in the arguments: a scale and optionally a keyboard mapping
+fn load_tuning(mut args: impl Iterator<Item = String>) -> Result<Option<Tuning>> {
+  let scale = match args.next() {
+    Some(path) => Scale::parse(&fs::read_to_string(&path)?)
+      .map_err(|err| anyhow!("Error loading the scale {}: {:?}", path, err))?,
+    None => return Ok(None),
+  };
+
+  let mapping = match args.next() {
+    Some(path) => KeyboardMapping::parse(&fs::read_to_string(&path)?)
+      .map_err(|err| anyhow!("Error loading the keyboard mapping {}: {:?}", path, err))?,
+    None => KeyboardMapping::default(),
+  };
+
+  let tuning = Tuning::new(scale, mapping).map_err(|err| anyhow!("Invalid tuning: {:?}", err))?;
+  println!("Tuning: {}", tuning.scale().description);
+  Ok(Some(tuning))
+}
+
 struct EventsMidiHandler {
   midi_mapper: MidiMapper<f32>,
+  tempo_tracker: TempoTracker,
+  controllers: Controllers,
+  mpe_zones: MpeZones,
   synth_client: Arc<Mutex<SynthClient<f32>>>,
 }
 
 impl EventsMidiHandler {
-  pub fn new(midi_mapper: MidiMapper<f32>, synth_client: Arc<Mutex<SynthClient<f32>>>) -> Self {
+  pub fn new(
+    midi_mapper: MidiMapper<f32>,
+    mpe_zones: MpeZones,
+    synth_client: Arc<Mutex<SynthClient<f32>>>,
+  ) -> Self {
     EventsMidiHandler {
       midi_mapper,
+      tempo_tracker: TempoTracker::new(),
+      controllers: Controllers::new(),
+      mpe_zones,
       synth_client,
     }
   }
+
+  fn is_mpe_member(&self, channel: u8) -> bool {
+    self.mpe_zones.channel_role(channel) == ChannelRole::Member
+  }
+
+  fn send_channel_expression(&mut self, channel: u8, expression: Expression, value: f32) {
+    self
+      .synth_client
+      .lock()
+      .unwrap()
+      .send_channel_expression(channel, expression, value);
+  }
+
+  fn send_tempo_update(&mut self, update: TempoUpdate) {
+    if let Some(bpm) = update.bpm {
+      println!("Tempo: {:.2} bpm", bpm);
+      self.synth_client.lock().unwrap().send_tempo(bpm as f32);
+    }
+    if let Some(beats) = update.beats {
+      self
+        .synth_client
+        .lock()
+        .unwrap()
+        .send_song_position(beats as f32);
+    }
+  }
 }
 
 impl MidiHandler for EventsMidiHandler {
   fn on_message(&mut self, timestamp: u64, message: MidiMessage) {
-    println!("{:014}: {:?}", timestamp, message);
+    // The timestamps from the drivers are not comparable, so the tempo is measured in the host clock
+    let tempo_update = self.tempo_tracker.process(Instant::now(), &message);
+    self.send_tempo_update(tempo_update);
+
+    if !matches!(message, MidiMessage::TimingClock) {
+      println!("{:014}: {:?}", timestamp, message);
+    }
+
     match message {
       MidiMessage::NoteOn {
-        channel: _,
+        channel,
         key,
         velocity,
       } => {
@@ -113,10 +219,10 @@ impl MidiHandler for EventsMidiHandler {
           .synth_client
           .lock()
           .unwrap()
-          .send_note_on(key, velocity as f32 / 127.0);
+          .send_note_on(channel, key, velocity as f32 / 127.0);
       }
       MidiMessage::NoteOff {
-        channel: _,
+        channel,
         key,
         velocity,
       } => {
@@ -124,21 +230,120 @@ impl MidiHandler for EventsMidiHandler {
           .synth_client
           .lock()
           .unwrap()
-          .send_note_off(key, velocity as f32 / 127.0);
+          .send_note_off(channel, key, velocity as f32 / 127.0);
+      }
+      MidiMessage::PitchBend { channel, value } if self.is_mpe_member(channel) => {
+        let value = ((f32::from(value) - 8192.0) / 8192.0).max(-1.0);
+        self.send_channel_expression(channel, Expression::PitchBend, value);
       }
       MidiMessage::PitchBend { channel: _, value } => {
         if let Some(event) = self.midi_mapper.map_midi_pitch_bend(value) {
           self.synth_client.lock().unwrap().send_event(event);
         }
       }
+      MidiMessage::PolyphonicKeyPressure {
+        channel,
+        key,
+        value,
+      } => {
+        self.synth_client.lock().unwrap().send_key_expression(
+          channel,
+          key,
+          Expression::Pressure,
+          f32::from(value) / 127.0,
+        );
+      }
+      MidiMessage::ChannelPressure { channel, value } if self.is_mpe_member(channel) => {
+        self.send_channel_expression(channel, Expression::Pressure, f32::from(value) / 127.0);
+      }
       MidiMessage::ControlChange {
-        channel: _,
+        channel,
+        controller: MPE_TIMBRE_CONTROLLER,
+        value,
+      } if self.is_mpe_member(channel) => {
+        self.send_channel_expression(channel, Expression::Timbre, f32::from(value) / 127.0);
+      }
+      MidiMessage::ControlChange {
+        channel,
         controller,
         value,
       } => {
         if let Some(event) = self.midi_mapper.map_midi_controller(controller, value) {
           self.synth_client.lock().unwrap().send_event(event);
         }
+
+        if let Some(pedal) = controller_pedal(controller) {
+          let pressed = value >= PEDAL_PRESSED_THRESHOLD;
+          self.synth_client.lock().unwrap().send_pedal(pedal, pressed);
+        }
+
+        match self.controllers.process(channel, controller, value) {
+          Some(ControllerMessage::Controller14 {
+            controller, value, ..
+          }) => {
+            if let Some(event) = self.midi_mapper.map_midi_controller14(controller, value) {
+              self.synth_client.lock().unwrap().send_event(event);
+            }
+          }
+          Some(ControllerMessage::Rpn {
+            channel,
+            parameter: RPN_PITCH_BEND_SENSITIVITY,
+            change: ParameterChange::Value(value),
+          }) => {
+            let mut synth_client = self.synth_client.lock().unwrap();
+            if self.is_mpe_member(channel) {
+              let semitones = f32::from(value >> 7) + f32::from(value & 0x7f) * 0.01;
+              synth_client.send_voice_pitch_bend_range(semitones);
+            } else {
+              let events = self.midi_mapper.map_midi_pitch_bend_sensitivity(value);
+              for event in events {
+                synth_client.send_event(event);
+              }
+            }
+          }
+          Some(ControllerMessage::Rpn {
+            parameter: RPN_FINE_TUNING,
+            change: ParameterChange::Value(value),
+            ..
+          }) => {
+            // the master tune in cents from -100 to +100, centered at 0x2000
+            let cents = (f64::from(value) - 8192.0) / 8192.0 * 100.0;
+            let frequency = DEFAULT_REFERENCE_FREQUENCY * 2.0f64.powf(cents / 1200.0);
+            println!("Reference frequency: {:.2} Hz", frequency);
+            self
+              .synth_client
+              .lock()
+              .unwrap()
+              .send_reference_frequency(frequency);
+          }
+          Some(ControllerMessage::Rpn {
+            parameter: RPN_COARSE_TUNING,
+            change: ParameterChange::Value(value),
+            ..
+          }) => {
+            // the transpose in semitones is in the MSB, centered at 64
+            let semitones = (value >> 7) as i8 - 64;
+            println!("Transpose: {} semitones", semitones);
+            self.synth_client.lock().unwrap().send_transpose(semitones);
+          }
+          Some(ControllerMessage::Rpn {
+            channel,
+            parameter: RPN_MPE_CONFIGURATION,
+            change: ParameterChange::Value(value),
+          }) => {
+            if self.mpe_zones.configure(channel, (value >> 7) as u8) {
+              println!("MPE: {:?}", self.mpe_zones);
+            }
+          }
+          _ => {}
+        }
+      }
+      MidiMessage::ResetAllControllers { channel } => {
+        self.controllers.reset(channel);
+        let mut synth_client = self.synth_client.lock().unwrap();
+        for pedal in [Pedal::Sustain, Pedal::Sostenuto, Pedal::Soft].iter() {
+          synth_client.send_pedal(*pedal, false);
+        }
       }
       _ => {}
     };
@@ -146,14 +351,48 @@ impl MidiHandler for EventsMidiHandler {
 
   fn on_sysex(&mut self, timestamp: u64, data: &[u8]) {
     println!("{:014}: {:?}", timestamp, data);
-    // TODO unimplemented!()
+
+    if let Some(message) = TuningMessage::decode(data) {
+      self
+        .synth_client
+        .lock()
+        .unwrap()
+        .update_tuning(|tuning| match message {
+          TuningMessage::SingleNote { notes, .. } => {
+            for note in notes {
+              if let Some(frequency) = note.frequency.frequency() {
+                tuning.set_key_frequency(note.key, frequency);
+              }
+            }
+          }
+          TuningMessage::ScaleOctave { offsets, .. } => tuning.set_octave_offsets(&offsets),
+        });
+    }
+  }
+}
+
+fn controller_pedal(controller: u8) -> Option<Pedal> {
+  match controller {
+    64 => Some(Pedal::Sustain),
+    66 => Some(Pedal::Sostenuto),
+    67 => Some(Pedal::Soft),
+    _ => None,
   }
 }
 
 fn create_midi_mapper<F: Float>(program: &Program<F>, module: &KiroModule) -> MidiMapper<F> {
   let mut midi_mapper = MidiMapper::new();
 
-  midi_mapper.pitch_bend(program.get_param(module.params.pitch_bend.reference));
+  midi_mapper.pitch_bend(program.get_param(module.params.pitch_bend.bend.reference));
+  midi_mapper.pitch_bend_range(
+    module.params.pitch_bend.up.reference,
+    module.params.pitch_bend.down.reference,
+  );
+
+  midi_mapper.controller(
+    MIDI_CHANNEL_VOLUME,
+    program.get_param(module.params.master.amplitude.reference),
+  );
 
   // midi_mapper.rel_controller(21, program.get_param(module.params.osc1.amplitude.reference));
   // midi_mapper.rel_controller(22, program.get_param(module.params.osc1.shape.reference));
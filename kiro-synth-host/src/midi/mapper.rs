@@ -1,6 +1,7 @@
 use heapless::consts;
 use heapless::{FnvIndexMap, Vec};
 
+use kiro_midi_core::controllers::NUM_CONTROLLERS_14BIT;
 use kiro_midi_core::types::{U14, U7};
 use kiro_synth_dsp::float::Float;
 use kiro_synth_engine::event::{Event, Message as SynthMessage};
@@ -35,10 +36,8 @@ impl<F: Float> Transform<F> {
       //   (value / *resolution).round() * *resolution
       // },
       Transform::MinMaxU14(min, max, resolution) => {
-        let midi_value = F::val(midi_value & 0x3fff);
-        let range = *max - *min;
-        let value = midi_value * F::val(1.0 / 16383.0) * range + *min;
-        (value / *resolution).round() * *resolution
+        let midi_value = F::val(midi_value & 0x3fff) * F::val(1.0 / 16383.0);
+        Self::min_max(midi_value, *min, *max, *resolution)
       }
       Transform::Relative64(resolution) => {
         let midi_value = F::val(midi_value & 0x7f);
@@ -46,6 +45,22 @@ impl<F: Float> Transform<F> {
       }
     }
   }
+
+  /// Scales a 7-bit value, for the controllers that have no LSB
+  fn param_value_from_u7(&self, midi_value: usize) -> F {
+    match self {
+      Transform::MinMaxU14(min, max, resolution) => {
+        let midi_value = F::val(midi_value & 0x7f) * F::val(1.0 / 127.0);
+        Self::min_max(midi_value, *min, *max, *resolution)
+      }
+      Transform::Relative64(_) => self.param_value_from(midi_value),
+    }
+  }
+
+  fn min_max(value: F, min: F, max: F, resolution: F) -> F {
+    let value = value * (max - min) + min;
+    (value / resolution).round() * resolution
+  }
 }
 
 pub struct PitchBendMapping<F: Float> {
@@ -53,6 +68,11 @@ pub struct PitchBendMapping<F: Float> {
   transform: Transform<F>,
 }
 
+pub struct PitchBendRangeMapping {
+  pub up_param_ref: ParamRef,
+  pub down_param_ref: ParamRef,
+}
+
 pub struct ControllerMapping<F: Float> {
   pub param_ref: ParamRef,
   pub controller: MidiController,
@@ -61,6 +81,7 @@ pub struct ControllerMapping<F: Float> {
 
 pub struct MidiMapper<F: Float> {
   pitch_bend_mapping: Option<PitchBendMapping<F>>,
+  pitch_bend_range_mapping: Option<PitchBendRangeMapping>,
   controller_mappings: Vec<ControllerMapping<F>, MaxMappings>,
   controller_to_param: FnvIndexMap<MidiController, MappingIndex, MaxMappings>,
   param_to_controller: FnvIndexMap<ParamRef, MappingIndex, MaxMappings>,
@@ -70,6 +91,7 @@ impl<F: Float> MidiMapper<F> {
   pub fn new() -> Self {
     MidiMapper {
       pitch_bend_mapping: None,
+      pitch_bend_range_mapping: None,
       controller_mappings: Vec::new(),
       controller_to_param: FnvIndexMap::new(),
       param_to_controller: FnvIndexMap::new(),
@@ -97,12 +119,43 @@ impl<F: Float> MidiMapper<F> {
     })
   }
 
-  // pub fn controller<'a>(&mut self, midi_controller: MidiController, param_info: Option<(ParamRef, &Param<'a, F>)>) {
-  //   if let Some((param_ref, param)) = param_info {
-  //     let transform = Transform::MinMaxU7(param.values.min, param.values.max, param.values.resolution);
-  //     self.add_controller_mapping(midi_controller, param_ref, transform)
-  //   }
-  // }
+  pub fn pitch_bend_range(&mut self, up_param_ref: ParamRef, down_param_ref: ParamRef) {
+    self.pitch_bend_range_mapping = Some(PitchBendRangeMapping {
+      up_param_ref,
+      down_param_ref,
+    })
+  }
+
+  /// Maps the pitch bend sensitivity (RPN 0), with the semitones in the MSB and the cents in the LSB,
+  /// into the events that set both the up and down ranges.
+  pub fn map_midi_pitch_bend_sensitivity(&self, midi_value: U14) -> Vec<Event<F>, consts::U2> {
+    let mut events = Vec::new();
+    if let Some(mapping) = self.pitch_bend_range_mapping.as_ref() {
+      let semitones = F::val(midi_value >> 7) + F::val(midi_value & 0x7f) * F::val(0.01);
+      for param_ref in [mapping.up_param_ref, mapping.down_param_ref].iter() {
+        let message = SynthMessage::ParamValue {
+          param_ref: *param_ref,
+          value: semitones,
+        };
+        events.push(Event::new(0u64, message)).ok();
+      }
+    }
+    events
+  }
+
+  /// Maps a controller into the full range of a param,
+  /// with 14-bit resolution for the controllers from 0 to 31 when their LSB is sent
+  pub fn controller<'a>(
+    &mut self,
+    midi_controller: MidiController,
+    param_info: Option<(ParamRef, &Param<'a, F>)>,
+  ) {
+    if let Some((param_ref, param)) = param_info {
+      let transform =
+        Transform::MinMaxU14(param.values.min, param.values.max, param.values.resolution);
+      self.add_controller_mapping(midi_controller, param_ref, transform)
+    }
+  }
 
   pub fn rel_controller<'a>(
     &mut self,
@@ -145,28 +198,51 @@ impl<F: Float> MidiMapper<F> {
       .map(|mapping_index| &self.controller_mappings[*mapping_index])
   }
 
+  /// Maps the 7-bit value of a control change for the relative controllers,
+  /// and for the absolute controllers without LSB (from 32 to 127)
   pub fn map_midi_controller(
     &self,
     controller: MidiController,
     midi_value: U7,
   ) -> Option<Event<F>> {
-    self.get_controller_mapping(controller).and_then(|mapping| {
-      let is_relative = Self::is_relative_controller(mapping);
-      let value = mapping.transform.param_value_from(midi_value as usize);
-      let maybe_message = if is_relative {
-        Some(SynthMessage::ParamChange {
-          param_ref: mapping.param_ref,
-          change: value,
-        })
-        .filter(|_| value != F::zero())
-      } else {
-        Some(SynthMessage::ParamValue {
+    self
+      .get_controller_mapping(controller)
+      .and_then(|mapping| {
+        if Self::is_relative_controller(mapping) {
+          let change = mapping.transform.param_value_from(midi_value as usize);
+          Some(SynthMessage::ParamChange {
+            param_ref: mapping.param_ref,
+            change,
+          })
+          .filter(|_| change != F::zero())
+        } else if controller >= NUM_CONTROLLERS_14BIT {
+          Some(SynthMessage::ParamValue {
+            param_ref: mapping.param_ref,
+            value: mapping.transform.param_value_from_u7(midi_value as usize),
+          })
+        } else {
+          None
+        }
+      })
+      .map(|message| Event::new(0u64, message))
+  }
+
+  /// Maps the 14-bit value of a controller combined with its LSB for the absolute controllers
+  pub fn map_midi_controller14(
+    &self,
+    controller: MidiController,
+    midi_value: U14,
+  ) -> Option<Event<F>> {
+    self
+      .get_controller_mapping(controller)
+      .filter(|mapping| !Self::is_relative_controller(mapping))
+      .map(|mapping| {
+        let message = SynthMessage::ParamValue {
           param_ref: mapping.param_ref,
-          value,
-        })
-      };
-      maybe_message.map(|message| Event::new(0u64, message))
-    })
+          value: mapping.transform.param_value_from(midi_value as usize),
+        };
+        Event::new(0u64, message)
+      })
   }
 
   fn is_relative_controller(mapping: &ControllerMapping<F>) -> bool {
@@ -176,3 +252,51 @@ impl<F: Float> MidiMapper<F> {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use assert_approx_eq::assert_approx_eq;
+  use kiro_synth_engine::program::{ParamValues, Program, ProgramBuilder};
+
+  fn program() -> (Program<'static, f64>, ParamRef) {
+    let mut builder = ProgramBuilder::new();
+    let values = ParamValues {
+      initial_value: 0.0,
+      origin: 0.0,
+      min: -1.0,
+      max: 1.0,
+      resolution: 0.001,
+    };
+    let param = builder.param("param", values);
+    (builder.build(), param.reference)
+  }
+
+  fn param_value(event: Option<Event<f64>>) -> f64 {
+    match event.map(|event| event.message) {
+      Some(SynthMessage::ParamValue { value, .. }) => value,
+      other => panic!("Unexpected message: {:?}", other),
+    }
+  }
+
+  #[test]
+  fn absolute_controller_with_lsb() {
+    let (program, param_ref) = program();
+    let mut mapper = MidiMapper::new();
+    mapper.controller(7, program.get_param(param_ref));
+
+    assert!(mapper.map_midi_controller(7, 127).is_none());
+    assert_approx_eq!(param_value(mapper.map_midi_controller14(7, 0)), -1.0);
+    assert_approx_eq!(param_value(mapper.map_midi_controller14(7, 0x3fff)), 1.0);
+  }
+
+  #[test]
+  fn absolute_controller_without_lsb() {
+    let (program, param_ref) = program();
+    let mut mapper = MidiMapper::new();
+    mapper.controller(80, program.get_param(param_ref));
+
+    assert_approx_eq!(param_value(mapper.map_midi_controller(80, 0)), -1.0);
+    assert_approx_eq!(param_value(mapper.map_midi_controller(80, 127)), 1.0);
+  }
+}
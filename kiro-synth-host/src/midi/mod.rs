@@ -1,2 +1,4 @@
 pub mod drivers;
 pub mod mapper;
+pub mod mpe;
+pub mod tempo;
@@ -0,0 +1,87 @@
+use kiro_midi_core::types::U4;
+
+const LOWER_ZONE_MASTER: U4 = 0;
+const UPPER_ZONE_MASTER: U4 = 15;
+
+/// The channels available for the members of both zones
+const MAX_MEMBER_CHANNELS: u8 = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChannelRole {
+  /// The channel for the messages that affect all the notes of the zone
+  Master,
+  /// A channel for the notes and their expressions
+  Member,
+  /// The channel is not part of any zone
+  None,
+}
+
+/// The MIDI Polyphonic Expression (MPE) zones.
+///
+/// The lower zone has the master in the first channel and its members from the second channel upwards.
+/// The upper zone has the master in the last channel and its members from the previous channel downwards.
+/// When both zones overlap, the zone configured last keeps its channels and the other one shrinks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MpeZones {
+  lower_members: u8,
+  upper_members: u8,
+}
+
+impl MpeZones {
+  /// Creates the zones disabled, so MPE is not used
+  pub fn new() -> Self {
+    MpeZones {
+      lower_members: 0,
+      upper_members: 0,
+    }
+  }
+
+  /// Changes the number of member channels of the lower zone (0 disables it)
+  pub fn set_lower_zone(&mut self, members: u8) {
+    self.lower_members = members.min(MAX_MEMBER_CHANNELS);
+    self.upper_members = self
+      .upper_members
+      .min((MAX_MEMBER_CHANNELS - 1).saturating_sub(self.lower_members));
+  }
+
+  /// Changes the number of member channels of the upper zone (0 disables it)
+  pub fn set_upper_zone(&mut self, members: u8) {
+    self.upper_members = members.min(MAX_MEMBER_CHANNELS);
+    self.lower_members = self
+      .lower_members
+      .min((MAX_MEMBER_CHANNELS - 1).saturating_sub(self.upper_members));
+  }
+
+  /// Applies the MPE Configuration Message (RPN 6) received in a channel.
+  /// Returns false if the channel is not the master of any zone.
+  pub fn configure(&mut self, channel: U4, members: u8) -> bool {
+    match channel {
+      LOWER_ZONE_MASTER => self.set_lower_zone(members),
+      UPPER_ZONE_MASTER => self.set_upper_zone(members),
+      _ => return false,
+    }
+    true
+  }
+
+  pub fn channel_role(&self, channel: U4) -> ChannelRole {
+    let lower_end = LOWER_ZONE_MASTER + self.lower_members;
+    let upper_start = UPPER_ZONE_MASTER - self.upper_members;
+    if (self.lower_members > 0 && channel == LOWER_ZONE_MASTER)
+      || (self.upper_members > 0 && channel == UPPER_ZONE_MASTER)
+    {
+      ChannelRole::Master
+    } else if (channel > LOWER_ZONE_MASTER && channel <= lower_end)
+      || (channel >= upper_start && channel < UPPER_ZONE_MASTER)
+    {
+      ChannelRole::Member
+    } else {
+      ChannelRole::None
+    }
+  }
+}
+
+impl Default for MpeZones {
+  fn default() -> Self {
+    Self::new()
+  }
+}
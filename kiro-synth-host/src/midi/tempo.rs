@@ -0,0 +1,280 @@
+use std::time::{Duration, Instant};
+
+use kiro_midi_core::messages::Message;
+
+const CLOCKS_PER_BEAT: u64 = 24;
+const CLOCKS_PER_MIDI_BEAT: u64 = 6;
+
+/// Number of clock periods used to average the tempo
+const MAX_PERIODS: usize = CLOCKS_PER_BEAT as usize;
+
+/// Longer periods between clocks mean that the clock was stopped (less than 2.5 bpm)
+const MAX_PERIOD: Duration = Duration::from_secs(1);
+
+/// Minimum change to notify a new tempo
+const BPM_RESOLUTION: f64 = 0.01;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TempoUpdate {
+  /// New tempo in beats per minute
+  pub bpm: Option<f64>,
+  /// New song position in beats (quarter notes)
+  pub beats: Option<f64>,
+}
+
+/// Derives the tempo and the song position from the MIDI clock messages
+pub struct TempoTracker {
+  last_clock: Option<Instant>,
+  periods: [f64; MAX_PERIODS],
+  periods_len: usize,
+  periods_index: usize,
+  periods_sum: f64,
+  bpm: Option<f64>,
+  clocks: u64,
+  song_clocks: u64,
+  playing: bool,
+}
+
+impl TempoTracker {
+  pub fn new() -> Self {
+    TempoTracker {
+      last_clock: None,
+      periods: [0.0; MAX_PERIODS],
+      periods_len: 0,
+      periods_index: 0,
+      periods_sum: 0.0,
+      bpm: None,
+      clocks: 0,
+      song_clocks: 0,
+      playing: false,
+    }
+  }
+
+  /// The song position in beats (quarter notes)
+  pub fn beats(&self) -> f64 {
+    self.song_clocks as f64 / CLOCKS_PER_BEAT as f64
+  }
+
+  /// Process a MIDI message received at `now`,
+  /// and return the changes that need to be notified to the synth.
+  pub fn process(&mut self, now: Instant, message: &Message) -> TempoUpdate {
+    match *message {
+      Message::TimingClock => self.clock(now),
+      Message::Start => {
+        self.playing = true;
+        self.song_clocks = 0;
+        self.position_update()
+      }
+      Message::Continue => {
+        self.playing = true;
+        self.position_update()
+      }
+      Message::Stop => {
+        self.playing = false;
+        TempoUpdate::default()
+      }
+      Message::SongPositionPointer { beats } => {
+        self.song_clocks = u64::from(beats) * CLOCKS_PER_MIDI_BEAT;
+        self.position_update()
+      }
+      _ => TempoUpdate::default(),
+    }
+  }
+
+  fn clock(&mut self, now: Instant) -> TempoUpdate {
+    let mut update = TempoUpdate::default();
+
+    match self.last_clock {
+      Some(last_clock) if now.duration_since(last_clock) <= MAX_PERIOD => {
+        let period = now.duration_since(last_clock).as_secs_f64();
+        self.add_period(period);
+      }
+      _ => self.reset_periods(),
+    }
+    self.last_clock = Some(now);
+
+    self.clocks += 1;
+    if self.clocks % CLOCKS_PER_MIDI_BEAT == 0 {
+      update.bpm = self.update_bpm();
+    }
+
+    // The song position is resynchronised on every beat
+    if self.playing {
+      if self.song_clocks % CLOCKS_PER_BEAT == 0 {
+        update.beats = Some(self.beats());
+      }
+      self.song_clocks += 1;
+    }
+
+    update
+  }
+
+  fn position_update(&self) -> TempoUpdate {
+    TempoUpdate {
+      bpm: None,
+      beats: Some(self.beats()),
+    }
+  }
+
+  fn add_period(&mut self, period: f64) {
+    if self.periods_len < MAX_PERIODS {
+      self.periods_len += 1;
+    } else {
+      self.periods_sum -= self.periods[self.periods_index];
+    }
+    self.periods[self.periods_index] = period;
+    self.periods_sum += period;
+    self.periods_index = (self.periods_index + 1) % MAX_PERIODS;
+  }
+
+  fn reset_periods(&mut self) {
+    self.periods_len = 0;
+    self.periods_index = 0;
+    self.periods_sum = 0.0;
+  }
+
+  fn update_bpm(&mut self) -> Option<f64> {
+    if self.periods_len == 0 || self.periods_sum <= 0.0 {
+      return None;
+    }
+
+    let period = self.periods_sum / self.periods_len as f64;
+    let bpm = 60.0 / (period * CLOCKS_PER_BEAT as f64);
+    let changed = match self.bpm {
+      Some(last_bpm) => (bpm - last_bpm).abs() >= BPM_RESOLUTION,
+      None => true,
+    };
+
+    if changed {
+      self.bpm = Some(bpm);
+      self.bpm
+    } else {
+      None
+    }
+  }
+}
+
+impl Default for TempoTracker {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use assert_approx_eq::assert_approx_eq;
+
+  /// The period between clocks in nanoseconds for a tempo
+  fn clock_period(bpm: f64) -> u64 {
+    (60.0e9 / (bpm * CLOCKS_PER_BEAT as f64)).round() as u64
+  }
+
+  /// Sends clocks with the given periods in nanoseconds, returning the last tempo update
+  fn clocks(
+    tracker: &mut TempoTracker,
+    now: &mut Instant,
+    periods: impl Iterator<Item = u64>,
+  ) -> Option<f64> {
+    let mut bpm = None;
+    for period in periods {
+      *now += Duration::from_nanos(period);
+      bpm = tracker.process(*now, &Message::TimingClock).bpm.or(bpm);
+    }
+    bpm
+  }
+
+  #[test]
+  fn tempo_from_the_clock() {
+    let mut tracker = TempoTracker::new();
+    let mut now = Instant::now();
+
+    // the first clock has no period and the tempo is updated every 6 clocks
+    let period = clock_period(120.0);
+    assert_eq!(clocks(&mut tracker, &mut now, (0..5).map(|_| period)), None);
+    let bpm = clocks(&mut tracker, &mut now, (0..1).map(|_| period));
+    assert_approx_eq!(bpm.unwrap(), 120.0, 1e-3);
+
+    // unchanged tempos are not notified again
+    assert_eq!(clocks(&mut tracker, &mut now, (0..6).map(|_| period)), None);
+  }
+
+  #[test]
+  fn tempo_is_smoothed() {
+    let mut tracker = TempoTracker::new();
+    let mut now = Instant::now();
+
+    let period = clock_period(120.0);
+    let jitter = period / 10;
+    let periods = (0..48).map(|i| {
+      if i % 2 == 0 {
+        period + jitter
+      } else {
+        period - jitter
+      }
+    });
+    clocks(&mut tracker, &mut now, periods);
+    assert_approx_eq!(tracker.bpm.unwrap(), 120.0, 1e-3);
+
+    // a tempo change is averaged over a beat
+    let period = clock_period(60.0);
+    let bpm = clocks(&mut tracker, &mut now, (0..12).map(|_| period));
+    assert!(bpm.unwrap() > 60.0 && bpm.unwrap() < 120.0);
+    let bpm = clocks(&mut tracker, &mut now, (0..12).map(|_| period));
+    assert_approx_eq!(bpm.unwrap(), 60.0, 1e-3);
+  }
+
+  #[test]
+  fn stopped_clock_resets_the_tempo() {
+    let mut tracker = TempoTracker::new();
+    let mut now = Instant::now();
+
+    clocks(&mut tracker, &mut now, (0..24).map(|_| clock_period(120.0)));
+    now += MAX_PERIOD * 2;
+
+    let period = clock_period(90.0);
+    let bpm = clocks(&mut tracker, &mut now, (0..6).map(|_| period));
+    assert_approx_eq!(bpm.unwrap(), 90.0, 1e-3);
+  }
+
+  #[test]
+  fn song_position_with_start_stop_and_continue() {
+    let mut tracker = TempoTracker::new();
+    let mut now = Instant::now();
+    let period = Duration::from_nanos(clock_period(120.0));
+
+    let mut clock = |tracker: &mut TempoTracker| {
+      now += period;
+      tracker.process(now, &Message::TimingClock).beats
+    };
+
+    // the clocks do not move the song position until it starts
+    assert_eq!(clock(&mut tracker), None);
+    assert_approx_eq!(tracker.beats(), 0.0);
+
+    let update = tracker.process(Instant::now(), &Message::Start);
+    assert_eq!(update.beats, Some(0.0));
+    assert_eq!(clock(&mut tracker), Some(0.0));
+    for _ in 1..24 {
+      assert_eq!(clock(&mut tracker), None);
+    }
+    assert_eq!(clock(&mut tracker), Some(1.0));
+
+    let update = tracker.process(Instant::now(), &Message::Stop);
+    assert_eq!(update, TempoUpdate::default());
+    for _ in 0..48 {
+      assert_eq!(clock(&mut tracker), None);
+    }
+
+    let update = tracker.process(Instant::now(), &Message::Continue);
+    assert_approx_eq!(update.beats.unwrap(), 25.0 / 24.0);
+
+    // the song position pointer counts sixteenth notes
+    let update = tracker.process(Instant::now(), &Message::SongPositionPointer { beats: 8 });
+    assert_eq!(update.beats, Some(2.0));
+    assert_eq!(clock(&mut tracker), Some(2.0));
+
+    let update = tracker.process(Instant::now(), &Message::Start);
+    assert_eq!(update.beats, Some(0.0));
+  }
+}
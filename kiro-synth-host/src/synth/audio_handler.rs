@@ -71,6 +71,14 @@ impl<'a> AudioHandler for SynthAudioHandler<'a> {
           let signal_index: usize = param.mod_signal_ref.into();
           modulations[index] = signals[signal_index].get();
         }
+
+        // the signals of the effect params are only in the global section
+        let global_signals = self.synth.get_global_section().get_signals();
+        for param in program.get_effect_params().iter() {
+          let index: usize = param.reference.into();
+          let signal_index: usize = param.mod_signal_ref.into();
+          modulations[index] = global_signals[signal_index].get();
+        }
       }
     }
     let num_active_voices = self.synth.get_num_active_voices();
@@ -4,16 +4,20 @@ use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 use ringbuf::{Consumer, Producer};
 
 use kiro_synth_dsp::float::Float;
-use kiro_synth_engine::event::{Event, Message};
+use kiro_synth_engine::event::{Event, Expression, Message, Pedal};
 use kiro_synth_engine::globals::SynthGlobals;
 use kiro_synth_engine::program::{ParamRef, SourceRef};
+use kiro_synth_engine::tuning::TuningSlot;
 use kiro_synth_engine::waveforms::{LfoWaveforms, OscWaveforms};
 
 use crate::synth::SynthFeedback;
+use crate::tuning::Tuning;
 
 pub struct SynthClient<F: Float> {
   globals: SynthGlobals<F>,
+  tuning: Tuning,
   events: Producer<Event<F>>,
+  key_frequencies: TuningSlot,
   feedback: Consumer<SynthFeedback>,
 }
 
@@ -21,11 +25,14 @@ impl<F: Float> SynthClient<F> {
   pub fn new(
     globals: SynthGlobals<F>,
     events: Producer<Event<F>>,
+    key_frequencies: TuningSlot,
     feedback: Consumer<SynthFeedback>,
   ) -> Self {
     SynthClient {
       globals,
+      tuning: Tuning::default(),
       events,
+      key_frequencies,
       feedback,
     }
   }
@@ -42,16 +49,92 @@ impl<F: Float> SynthClient<F> {
     drop(self.events.push(event));
   }
 
-  pub fn send_note_on(&mut self, key: u8, velocity: F) {
-    let message = Message::NoteOn { key, velocity };
+  pub fn send_note_on(&mut self, channel: u8, key: u8, velocity: F) {
+    let message = Message::NoteOn {
+      channel,
+      key,
+      velocity,
+    };
+    self.send_event(Event::new(0u64, message));
+  }
+
+  pub fn send_note_off(&mut self, channel: u8, key: u8, velocity: F) {
+    let message = Message::NoteOff {
+      channel,
+      key,
+      velocity,
+    };
+    self.send_event(Event::new(0u64, message));
+  }
+
+  pub fn send_channel_expression(&mut self, channel: u8, expression: Expression, value: F) {
+    let message = Message::ChannelExpression {
+      channel,
+      expression,
+      value,
+    };
+    self.send_event(Event::new(0u64, message));
+  }
+
+  pub fn send_key_expression(&mut self, channel: u8, key: u8, expression: Expression, value: F) {
+    let message = Message::KeyExpression {
+      channel,
+      key,
+      expression,
+      value,
+    };
+    self.send_event(Event::new(0u64, message));
+  }
+
+  pub fn send_pedal(&mut self, pedal: Pedal, pressed: bool) {
+    let message = Message::Pedal { pedal, pressed };
     self.send_event(Event::new(0u64, message));
   }
 
-  pub fn send_note_off(&mut self, key: u8, velocity: F) {
-    let message = Message::NoteOff { key, velocity };
+  pub fn send_voice_pitch_bend_range(&mut self, semitones: F) {
+    let message = Message::VoicePitchBendRange { semitones };
     self.send_event(Event::new(0u64, message));
   }
 
+  pub fn send_tempo(&mut self, bpm: F) {
+    let message = Message::Tempo { bpm };
+    self.send_event(Event::new(0u64, message));
+  }
+
+  pub fn send_song_position(&mut self, beats: F) {
+    let message = Message::SongPosition { beats };
+    self.send_event(Event::new(0u64, message));
+  }
+
+  /// Replaces the scale and keyboard mapping, but keeps the reference frequency and transpose
+  pub fn send_tuning(&mut self, tuning: Tuning) {
+    let mut tuning = tuning;
+    tuning.set_reference_frequency(self.tuning.reference_frequency());
+    tuning.set_transpose(self.tuning.transpose());
+    self.tuning = tuning;
+    self.send_key_frequencies();
+  }
+
+  /// Changes the current tuning and sends the result
+  pub fn update_tuning(&mut self, update: impl FnOnce(&mut Tuning)) {
+    update(&mut self.tuning);
+    self.send_key_frequencies();
+  }
+
+  /// Frequency of A4 in Hz
+  pub fn send_reference_frequency(&mut self, frequency: f64) {
+    self.update_tuning(|tuning| tuning.set_reference_frequency(frequency));
+  }
+
+  pub fn send_transpose(&mut self, semitones: i8) {
+    self.update_tuning(|tuning| tuning.set_transpose(semitones));
+  }
+
+  /// The frequencies go through their own slot, where only the latest table is kept until the engine takes it
+  fn send_key_frequencies(&self) {
+    self.key_frequencies.send(self.tuning.key_frequencies());
+  }
+
   pub fn send_param_value(&mut self, param_ref: ParamRef, value: F) {
     let message = Message::ParamValue { param_ref, value };
     self.send_event(Event::new(0u64, message));
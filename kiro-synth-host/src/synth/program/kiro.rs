@@ -1,53 +1,112 @@
+use kiro_synth_dsp::effects::waveshaper::{Curve, Oversampling};
+use kiro_synth_dsp::filters::freq_control::FreqControl;
 use kiro_synth_dsp::float::Float;
-use kiro_synth_engine::program::blocks::{dca, envgen, filter, lfo, osc};
-use kiro_synth_engine::program::{
-  Block, ParamBlock, Program, ProgramBuilder, SignalRef, SourceRef,
+use kiro_synth_dsp::meters::envelope_follower::Detector;
+use kiro_synth_engine::program::blocks::{
+  compressor, dca, delay, envelope_follower, envgen, equalizer, filter, lfo, limiter, mixer, modfx,
+  osc, reverb, waveshaper,
 };
+use kiro_synth_engine::program::{Block, Program, ProgramBuilder, SignalRef, SourceRef};
+use kiro_synth_engine::transport::Division;
 
-use crate::synth::program::params::{DcaParams, EnvGenParams, FilterParams, LfoParams, OscParams};
+use crate::synth::program::params::{
+  CompressorParams, DcaParams, DelayParams, EnvFollowerParams, EnvGenParams, EqualizerParams,
+  FilterParams, LfoParams, LimiterParams, MixerChannelParams, ModFxParams, OscParams,
+  PitchBendParams, ReverbParams, WaveshaperParams,
+};
 use crate::synth::program::values;
 
 pub struct KiroParams {
-  pub pitch_bend: ParamBlock,
+  pub pitch_bend: PitchBendParams,
 
   pub lfo1: LfoParams,
   pub lfo2: LfoParams,
 
+  pub glfo1: LfoParams,
+
   pub eg1: EnvGenParams,
 
+  pub envf1: EnvFollowerParams,
+
   pub osc1: OscParams,
   pub osc2: OscParams,
   pub osc3: OscParams,
   pub osc4: OscParams,
 
+  pub mix1: MixerChannelParams,
+  pub mix2: MixerChannelParams,
+  pub mix3: MixerChannelParams,
+  pub mix4: MixerChannelParams,
+
   pub filter1: FilterParams,
 
+  pub shaper: WaveshaperParams,
+
   pub dca: DcaParams,
+
+  pub modfx: ModFxParams,
+
+  pub delay: DelayParams,
+
+  pub reverb: ReverbParams,
+
+  pub eq: EqualizerParams,
+
+  pub comp: CompressorParams,
+
+  pub master: DcaParams,
+
+  pub limiter: LimiterParams,
 }
 
 pub struct KiroSignals {
   pub lfo1: SignalRef,
   pub lfo2: SignalRef,
+  pub glfo1: SignalRef,
   pub eg1_normal: SignalRef,
   pub eg1_biased: SignalRef,
+  pub envf1: SignalRef,
   pub osc1: SignalRef,
   pub osc2: SignalRef,
   pub osc3: SignalRef,
   pub osc4: SignalRef,
+  pub mixer_left: SignalRef,
+  pub mixer_right: SignalRef,
+  pub mixer_send: SignalRef,
   pub filter1: SignalRef,
+  pub shaper: SignalRef,
   pub dca_left: SignalRef,
   pub dca_right: SignalRef,
+  pub modfx_left: SignalRef,
+  pub modfx_right: SignalRef,
+  pub delay_left: SignalRef,
+  pub delay_right: SignalRef,
+  pub reverb_left: SignalRef,
+  pub reverb_right: SignalRef,
+  pub eq_left: SignalRef,
+  pub eq_right: SignalRef,
+  pub comp_left: SignalRef,
+  pub comp_right: SignalRef,
+  pub master_left: SignalRef,
+  pub master_right: SignalRef,
+  pub limiter_left: SignalRef,
+  pub limiter_right: SignalRef,
 }
 
 pub struct KiroSources {
   pub lfo1: SourceRef,
   pub lfo2: SourceRef,
+  pub glfo1: SourceRef,
   pub eg1_normal: SourceRef,
   pub eg1_biased: SourceRef,
+  pub envf1: SourceRef,
   pub osc1: SourceRef,
   pub osc2: SourceRef,
   pub osc3: SourceRef,
   pub osc4: SourceRef,
+  pub voice_bend: SourceRef,
+  pub voice_pressure: SourceRef,
+  pub voice_timbre: SourceRef,
 }
 
 pub struct KiroModule {
@@ -78,25 +137,54 @@ impl KiroModule {
     let voice = program.voice().clone();
 
     let zero = program.const_zero();
-    // let one = program.const_one();
+    let one = program.const_one();
 
     let num_filters = filter::Mode::count();
 
     let params = KiroParams {
-      pitch_bend: program.param("pitch-bend", values::pitch_bend()),
+      pitch_bend: PitchBendParams {
+        bend: program.param("pitch-bend", values::pitch_bend()),
+        up: program.param("pitch-bend-up", values::pitch_bend_range()),
+        down: program.param("pitch-bend-down", values::pitch_bend_range()),
+      },
 
       lfo1: LfoParams {
         shape: program.param("lfo1-shape", values::enumeration(num_lfo_shapes)),
+        mode: program.param("lfo1-mode", values::lfo_mode()),
         rate: program.param("lfo1-rate", values::lfo_rate()),
         phase: program.param("lfo1-phase", values::lfo_phase()),
         depth: program.param("lfo1-depth", values::amplitude()),
+        delay: program.param("lfo1-delay", values::lfo_time()),
+        fade_in: program.param("lfo1-fade-in", values::lfo_time()),
+        sync: program.param("lfo1-sync", values::boolean(false)),
+        division: program.param("lfo1-division", values::lfo_division()),
       },
 
       lfo2: LfoParams {
         shape: program.param("lfo2-shape", values::enumeration(num_lfo_shapes)),
+        mode: program.param("lfo2-mode", values::lfo_mode()),
         rate: program.param("lfo2-rate", values::lfo_rate()),
         phase: program.param("lfo2-phase", values::lfo_phase()),
         depth: program.param("lfo2-depth", values::amplitude()),
+        delay: program.param("lfo2-delay", values::lfo_time()),
+        fade_in: program.param("lfo2-fade-in", values::lfo_time()),
+        sync: program.param("lfo2-sync", values::boolean(false)),
+        division: program.param("lfo2-division", values::lfo_division()),
+      },
+
+      glfo1: LfoParams {
+        shape: program.param("glfo1-shape", values::enumeration(num_lfo_shapes)),
+        mode: program.param(
+          "glfo1-mode",
+          values::lfo_mode().with_initial_value(F::zero()),
+        ),
+        rate: program.param("glfo1-rate", values::lfo_rate()),
+        phase: program.param("glfo1-phase", values::lfo_phase()),
+        depth: program.param("glfo1-depth", values::amplitude()),
+        delay: program.param("glfo1-delay", values::lfo_time()),
+        fade_in: program.param("glfo1-fade-in", values::lfo_time()),
+        sync: program.param("glfo1-sync", values::boolean(false)),
+        division: program.param("glfo1-division", values::lfo_division()),
       },
 
       eg1: EnvGenParams {
@@ -110,6 +198,12 @@ impl KiroModule {
         dca_mod: program.param("eg1-dca-mod", values::eg1_dca_amp_mod()),
       },
 
+      envf1: EnvFollowerParams {
+        detector: program.param("envf1-detector", values::enumeration(Detector::count())),
+        attack: program.param("envf1-attack", values::envf_attack()),
+        release: program.param("envf1-release", values::envf_release()),
+      },
+
       osc1: OscParams {
         shape: program.param(
           "osc1-shape",
@@ -157,6 +251,34 @@ impl KiroModule {
         cents: program.param("osc4-cents", values::cents()),
       },
 
+      mix1: MixerChannelParams {
+        level: program.param("mix1-level", values::amplitude()),
+        pan: program.param("mix1-pan", values::pan()),
+        send: program.param("mix1-send", values::amplitude()),
+        mute: program.param("mix1-mute", values::boolean(false)),
+      },
+
+      mix2: MixerChannelParams {
+        level: program.param("mix2-level", values::amplitude()),
+        pan: program.param("mix2-pan", values::pan()),
+        send: program.param("mix2-send", values::amplitude()),
+        mute: program.param("mix2-mute", values::boolean(false)),
+      },
+
+      mix3: MixerChannelParams {
+        level: program.param("mix3-level", values::amplitude()),
+        pan: program.param("mix3-pan", values::pan()),
+        send: program.param("mix3-send", values::amplitude()),
+        mute: program.param("mix3-mute", values::boolean(false)),
+      },
+
+      mix4: MixerChannelParams {
+        level: program.param("mix4-level", values::amplitude()),
+        pan: program.param("mix4-pan", values::pan()),
+        send: program.param("mix4-send", values::amplitude()),
+        mute: program.param("mix4-mute", values::boolean(false)),
+      },
+
       filter1: FilterParams {
         mode: program.param(
           "filt1-mode",
@@ -166,6 +288,22 @@ impl KiroModule {
         q: program.param("filt1-q", values::filt_q()),
       },
 
+      shaper: WaveshaperParams {
+        curve: program.param("shaper-curve", values::enumeration(Curve::count())),
+        drive: program.param(
+          "shaper-drive",
+          values::amplitude().with_initial_value(F::zero()),
+        ),
+        mix: program.param(
+          "shaper-mix",
+          values::amplitude().with_initial_value(F::zero()),
+        ),
+        oversampling: program.param(
+          "shaper-oversampling",
+          values::enumeration(Oversampling::count()),
+        ),
+      },
+
       dca: DcaParams {
         amplitude: program.param(
           "dca-amplitude-db",
@@ -173,39 +311,182 @@ impl KiroModule {
         ),
         pan: program.param("dca-pan", values::pan()),
       },
+
+      modfx: ModFxParams {
+        on: program.param("modfx-on", values::boolean(true)),
+        mode: program.param("modfx-mode", values::enumeration(modfx::Mode::count())),
+        shape: program.param("modfx-shape", values::enumeration(num_lfo_shapes)),
+        rate: program.param(
+          "modfx-rate",
+          values::lfo_rate().with_initial_value(F::val(0.5)),
+        ),
+        depth: program.param(
+          "modfx-depth",
+          values::amplitude().with_initial_value(F::val(0.5)),
+        ),
+        feedback: program.param("modfx-feedback", values::modfx_feedback()),
+        spread: program.param(
+          "modfx-spread",
+          values::amplitude().with_initial_value(F::val(0.5)),
+        ),
+        mix: program.param(
+          "modfx-mix",
+          values::amplitude().with_initial_value(F::zero()),
+        ),
+      },
+
+      delay: DelayParams {
+        on: program.param("delay-on", values::boolean(true)),
+        mode: program.param("delay-mode", values::enumeration(2)),
+        time_left: program.param("delay-time-left", values::delay_time(0.25)),
+        time_right: program.param("delay-time-right", values::delay_time(0.375)),
+        sync: program.param("delay-sync", values::boolean(false)),
+        // 1/8 and 1/8D by default
+        division_left: program.param(
+          "delay-division-left",
+          values::lfo_division().with_initial_value(F::val(Division::quarter_index() + 3)),
+        ),
+        division_right: program.param(
+          "delay-division-right",
+          values::lfo_division().with_initial_value(F::val(Division::quarter_index() + 2)),
+        ),
+        feedback: program.param("delay-feedback", values::delay_feedback()),
+        cross_feedback: program.param(
+          "delay-cross-feedback",
+          values::amplitude().with_initial_value(F::zero()),
+        ),
+        lowpass: program.param(
+          "delay-lowpass",
+          values::filt_freq().with_initial_value(FreqControl::max_frequency()),
+        ),
+        highpass: program.param(
+          "delay-highpass",
+          values::filt_freq().with_initial_value(FreqControl::min_frequency()),
+        ),
+        mix: program.param(
+          "delay-mix",
+          values::amplitude().with_initial_value(F::zero()),
+        ),
+      },
+
+      reverb: ReverbParams {
+        on: program.param("reverb-on", values::boolean(true)),
+        size: program.param(
+          "reverb-size",
+          values::amplitude().with_initial_value(F::val(0.5)),
+        ),
+        decay: program.param("reverb-decay", values::reverb_decay()),
+        damping: program.param(
+          "reverb-damping",
+          values::amplitude().with_initial_value(F::val(0.5)),
+        ),
+        predelay: program.param("reverb-predelay", values::reverb_predelay()),
+        width: program.param("reverb-width", values::amplitude()),
+        mix: program.param(
+          "reverb-mix",
+          values::amplitude().with_initial_value(F::zero()),
+        ),
+      },
+
+      eq: EqualizerParams {
+        on: program.param("eq-on", values::boolean(true)),
+        low_frequency: program.param("eq-low-frequency", values::eq_frequency(0)),
+        low_gain: program.param("eq-low-gain", values::eq_gain()),
+        low_q: program.param("eq-low-q", values::eq_q()),
+        mid1_frequency: program.param("eq-mid1-frequency", values::eq_frequency(1)),
+        mid1_gain: program.param("eq-mid1-gain", values::eq_gain()),
+        mid1_q: program.param("eq-mid1-q", values::eq_q()),
+        mid2_frequency: program.param("eq-mid2-frequency", values::eq_frequency(2)),
+        mid2_gain: program.param("eq-mid2-gain", values::eq_gain()),
+        mid2_q: program.param("eq-mid2-q", values::eq_q()),
+        high_frequency: program.param("eq-high-frequency", values::eq_frequency(3)),
+        high_gain: program.param("eq-high-gain", values::eq_gain()),
+        high_q: program.param("eq-high-q", values::eq_q()),
+      },
+
+      comp: CompressorParams {
+        on: program.param("comp-on", values::boolean(true)),
+        threshold: program.param("comp-threshold", values::comp_threshold()),
+        ratio: program.param("comp-ratio", values::comp_ratio()),
+        attack: program.param("comp-attack", values::comp_attack()),
+        release: program.param("comp-release", values::comp_release()),
+        knee: program.param("comp-knee", values::comp_knee()),
+        makeup: program.param("comp-makeup", values::comp_makeup()),
+      },
+
+      master: DcaParams {
+        amplitude: program.param("master-amplitude-db", values::amplitude_db()),
+        pan: program.param("master-pan", values::pan()),
+      },
+
+      limiter: LimiterParams {
+        on: program.param("limiter-on", values::boolean(true)),
+        ceiling: program.param("limiter-ceiling", values::limiter_ceiling()),
+        release: program.param("limiter-release", values::limiter_release()),
+      },
     };
 
     let signals = KiroSignals {
       lfo1: program.signal(),
       lfo2: program.signal(),
+      glfo1: program.global_signal(),
       eg1_normal: program.signal(),
       eg1_biased: program.signal(),
+      envf1: program.signal(),
       osc1: program.signal(),
       osc2: program.signal(),
       osc3: program.signal(),
       osc4: program.signal(),
+      mixer_left: program.signal(),
+      mixer_right: program.signal(),
+      mixer_send: program.signal(),
       filter1: program.signal(),
+      shaper: program.signal(),
       dca_left: program.signal(),
       dca_right: program.signal(),
+      modfx_left: program.signal(),
+      modfx_right: program.signal(),
+      delay_left: program.signal(),
+      delay_right: program.signal(),
+      reverb_left: program.signal(),
+      reverb_right: program.signal(),
+      eq_left: program.signal(),
+      eq_right: program.signal(),
+      comp_left: program.signal(),
+      comp_right: program.signal(),
+      master_left: program.signal(),
+      master_right: program.signal(),
+      limiter_left: program.signal(),
+      limiter_right: program.signal(),
     };
 
     let sources = KiroSources {
       lfo1: program.source("lfo1", signals.lfo1),
       lfo2: program.source("lfo2", signals.lfo2),
+      glfo1: program.source("glfo1", signals.glfo1),
       eg1_normal: program.source("eg1", signals.eg1_normal),
       eg1_biased: program.source("eg1-biased", signals.eg1_biased),
+      envf1: program.source("envf1", signals.envf1),
       osc1: program.source("osc1", signals.osc1),
       osc2: program.source("osc2", signals.osc2),
       osc3: program.source("osc3", signals.osc3),
       osc4: program.source("osc4", signals.osc4),
+      voice_bend: program.source("voice-bend", voice.pitch_bend),
+      voice_pressure: program.source("voice-pressure", voice.pressure),
+      voice_timbre: program.source("voice-timbre", voice.timbre),
     };
 
     let lfo1 = lfo::Block {
       inputs: lfo::Inputs {
         shape: params.lfo1.shape.out_signal_ref,
+        mode: params.lfo1.mode.out_signal_ref,
         rate: params.lfo1.rate.out_signal_ref,
         phase: params.lfo1.phase.out_signal_ref,
         depth: params.lfo1.depth.out_signal_ref,
+        delay: params.lfo1.delay.out_signal_ref,
+        fade_in: params.lfo1.fade_in.out_signal_ref,
+        sync: params.lfo1.sync.out_signal_ref,
+        division: params.lfo1.division.out_signal_ref,
       },
       output: signals.lfo1,
     };
@@ -213,13 +494,33 @@ impl KiroModule {
     let lfo2 = lfo::Block {
       inputs: lfo::Inputs {
         shape: params.lfo2.shape.out_signal_ref,
+        mode: params.lfo2.mode.out_signal_ref,
         rate: params.lfo2.rate.out_signal_ref,
         phase: params.lfo2.phase.out_signal_ref,
         depth: params.lfo2.depth.out_signal_ref,
+        delay: params.lfo2.delay.out_signal_ref,
+        fade_in: params.lfo2.fade_in.out_signal_ref,
+        sync: params.lfo2.sync.out_signal_ref,
+        division: params.lfo2.division.out_signal_ref,
       },
       output: signals.lfo2,
     };
 
+    let glfo1 = lfo::Block {
+      inputs: lfo::Inputs {
+        shape: params.glfo1.shape.out_signal_ref,
+        mode: params.glfo1.mode.out_signal_ref,
+        rate: params.glfo1.rate.out_signal_ref,
+        phase: params.glfo1.phase.out_signal_ref,
+        depth: params.glfo1.depth.out_signal_ref,
+        delay: params.glfo1.delay.out_signal_ref,
+        fade_in: params.glfo1.fade_in.out_signal_ref,
+        sync: params.glfo1.sync.out_signal_ref,
+        division: params.glfo1.division.out_signal_ref,
+      },
+      output: signals.glfo1,
+    };
+
     program.modulation(&params.filter1.freq, sources.lfo1, F::val(800));
     program.modulation(&params.filter1.freq, sources.eg1_normal, F::val(700));
     program.modulation(&params.filter1.q, sources.lfo2, F::val(0.09));
@@ -255,7 +556,9 @@ impl KiroModule {
         semitones: params.osc1.semitones.out_signal_ref,
         cents: params.osc1.cents.out_signal_ref,
         note_pitch: voice.note_pitch,
-        pitch_bend: params.pitch_bend.out_signal_ref,
+        pitch_bend: params.pitch_bend.bend.out_signal_ref,
+        pitch_bend_up: params.pitch_bend.up.out_signal_ref,
+        pitch_bend_down: params.pitch_bend.down.out_signal_ref,
         freq_mod: zero,
       },
       output: signals.osc1,
@@ -270,7 +573,9 @@ impl KiroModule {
         semitones: params.osc2.semitones.out_signal_ref,
         cents: params.osc2.cents.out_signal_ref,
         note_pitch: voice.note_pitch,
-        pitch_bend: params.pitch_bend.out_signal_ref,
+        pitch_bend: params.pitch_bend.bend.out_signal_ref,
+        pitch_bend_up: params.pitch_bend.up.out_signal_ref,
+        pitch_bend_down: params.pitch_bend.down.out_signal_ref,
         freq_mod: zero,
       },
       output: signals.osc2,
@@ -285,7 +590,9 @@ impl KiroModule {
         semitones: params.osc3.semitones.out_signal_ref,
         cents: params.osc3.cents.out_signal_ref,
         note_pitch: voice.note_pitch,
-        pitch_bend: params.pitch_bend.out_signal_ref,
+        pitch_bend: params.pitch_bend.bend.out_signal_ref,
+        pitch_bend_up: params.pitch_bend.up.out_signal_ref,
+        pitch_bend_down: params.pitch_bend.down.out_signal_ref,
         freq_mod: zero,
       },
       output: signals.osc3,
@@ -300,33 +607,94 @@ impl KiroModule {
         semitones: params.osc4.semitones.out_signal_ref,
         cents: params.osc4.cents.out_signal_ref,
         note_pitch: voice.note_pitch,
-        pitch_bend: params.pitch_bend.out_signal_ref,
+        pitch_bend: params.pitch_bend.bend.out_signal_ref,
+        pitch_bend_up: params.pitch_bend.up.out_signal_ref,
+        pitch_bend_down: params.pitch_bend.down.out_signal_ref,
         freq_mod: zero,
       },
       output: signals.osc4,
     };
 
-    let osc_mix = program.expr(|expr| {
-      let sum1 = expr.add_signals(osc1.output, osc2.output);
-      let sum2 = expr.add_signals(osc3.output, osc4.output);
-      expr.add(sum1, sum2)
-    });
+    let mixer = mixer::Block::new(
+      &[
+        mixer::ChannelInputs {
+          input: osc1.output,
+          level: params.mix1.level.out_signal_ref,
+          pan: params.mix1.pan.out_signal_ref,
+          send: params.mix1.send.out_signal_ref,
+          mute: params.mix1.mute.out_signal_ref,
+        },
+        mixer::ChannelInputs {
+          input: osc2.output,
+          level: params.mix2.level.out_signal_ref,
+          pan: params.mix2.pan.out_signal_ref,
+          send: params.mix2.send.out_signal_ref,
+          mute: params.mix2.mute.out_signal_ref,
+        },
+        mixer::ChannelInputs {
+          input: osc3.output,
+          level: params.mix3.level.out_signal_ref,
+          pan: params.mix3.pan.out_signal_ref,
+          send: params.mix3.send.out_signal_ref,
+          mute: params.mix3.mute.out_signal_ref,
+        },
+        mixer::ChannelInputs {
+          input: osc4.output,
+          level: params.mix4.level.out_signal_ref,
+          pan: params.mix4.pan.out_signal_ref,
+          send: params.mix4.send.out_signal_ref,
+          mute: params.mix4.mute.out_signal_ref,
+        },
+      ],
+      mixer::Outputs {
+        left: signals.mixer_left,
+        right: signals.mixer_right,
+        send: signals.mixer_send,
+      },
+    );
+
+    let envf1 = envelope_follower::Block {
+      inputs: envelope_follower::Inputs {
+        input: mixer.outputs.send,
+        detector: params.envf1.detector.out_signal_ref,
+        attack: params.envf1.attack.out_signal_ref,
+        release: params.envf1.release.out_signal_ref,
+      },
+      output: signals.envf1,
+    };
+
+    let filter1_params = filter::Params {
+      mode: params.filter1.mode.out_signal_ref,
+      freq: params.filter1.freq.out_signal_ref,
+      freq_mod: zero,
+      q: params.filter1.q.out_signal_ref,
+    };
 
     let filter1 = filter::Block {
-      input: osc_mix.output,
-      params: filter::Params {
-        mode: params.filter1.mode.out_signal_ref,
-        freq: params.filter1.freq.out_signal_ref,
-        freq_mod: zero,
-        q: params.filter1.q.out_signal_ref,
-      },
+      input: mixer.outputs.send,
+      params: filter1_params,
       output: signals.filter1,
     };
 
+    let shaper = waveshaper::Block {
+      inputs: waveshaper::Inputs {
+        input: filter1.output,
+        curve: params.shaper.curve.out_signal_ref,
+        drive: params.shaper.drive.out_signal_ref,
+        mix: params.shaper.mix.out_signal_ref,
+        oversampling: params.shaper.oversampling.out_signal_ref,
+      },
+      output: signals.shaper,
+    };
+
+    // the filtered send goes back to the center of the direct stereo pair
+    let voice_left = program.expr(|expr| expr.add_signals(shaper.output, mixer.outputs.left));
+    let voice_right = program.expr(|expr| expr.add_signals(shaper.output, mixer.outputs.right));
+
     let dca = dca::Block {
       inputs: dca::Inputs {
-        left: filter1.output,
-        right: filter1.output,
+        left: voice_left.output,
+        right: voice_right.output,
         velocity: voice.velocity,
         amplitude: params.dca.amplitude.out_signal_ref,
         amp_mod: zero,
@@ -340,17 +708,197 @@ impl KiroModule {
       },
     };
 
+    let modfx_slot = program.effect_slot(
+      params.modfx.on.out_signal_ref,
+      signals.modfx_left,
+      signals.modfx_right,
+    );
+
+    let modfx = modfx::Block {
+      inputs: modfx::Inputs {
+        left: modfx_slot.input_left,
+        right: modfx_slot.input_right,
+        mode: params.modfx.mode.out_signal_ref,
+        shape: params.modfx.shape.out_signal_ref,
+        rate: params.modfx.rate.out_signal_ref,
+        depth: params.modfx.depth.out_signal_ref,
+        feedback: params.modfx.feedback.out_signal_ref,
+        spread: params.modfx.spread.out_signal_ref,
+        mix: params.modfx.mix.out_signal_ref,
+      },
+      outputs: modfx::Outputs {
+        left: signals.modfx_left,
+        right: signals.modfx_right,
+      },
+    };
+
+    let delay_slot = program.effect_slot(
+      params.delay.on.out_signal_ref,
+      signals.delay_left,
+      signals.delay_right,
+    );
+
+    let delay = delay::Block {
+      inputs: delay::Inputs {
+        left: delay_slot.input_left,
+        right: delay_slot.input_right,
+        mode: params.delay.mode.out_signal_ref,
+        time_left: params.delay.time_left.out_signal_ref,
+        time_right: params.delay.time_right.out_signal_ref,
+        sync: params.delay.sync.out_signal_ref,
+        division_left: params.delay.division_left.out_signal_ref,
+        division_right: params.delay.division_right.out_signal_ref,
+        feedback: params.delay.feedback.out_signal_ref,
+        cross_feedback: params.delay.cross_feedback.out_signal_ref,
+        lowpass: params.delay.lowpass.out_signal_ref,
+        highpass: params.delay.highpass.out_signal_ref,
+        mix: params.delay.mix.out_signal_ref,
+      },
+      outputs: delay::Outputs {
+        left: signals.delay_left,
+        right: signals.delay_right,
+      },
+    };
+
+    let reverb_slot = program.effect_slot(
+      params.reverb.on.out_signal_ref,
+      signals.reverb_left,
+      signals.reverb_right,
+    );
+
+    let reverb = reverb::Block {
+      inputs: reverb::Inputs {
+        left: reverb_slot.input_left,
+        right: reverb_slot.input_right,
+        size: params.reverb.size.out_signal_ref,
+        decay: params.reverb.decay.out_signal_ref,
+        damping: params.reverb.damping.out_signal_ref,
+        predelay: params.reverb.predelay.out_signal_ref,
+        width: params.reverb.width.out_signal_ref,
+        mix: params.reverb.mix.out_signal_ref,
+      },
+      outputs: reverb::Outputs {
+        left: signals.reverb_left,
+        right: signals.reverb_right,
+      },
+    };
+
+    let eq_slot = program.effect_slot(
+      params.eq.on.out_signal_ref,
+      signals.eq_left,
+      signals.eq_right,
+    );
+
+    let eq = equalizer::Block {
+      inputs: equalizer::Inputs {
+        left: eq_slot.input_left,
+        right: eq_slot.input_right,
+        bands: [
+          equalizer::BandInputs {
+            frequency: params.eq.low_frequency.out_signal_ref,
+            gain: params.eq.low_gain.out_signal_ref,
+            q: params.eq.low_q.out_signal_ref,
+          },
+          equalizer::BandInputs {
+            frequency: params.eq.mid1_frequency.out_signal_ref,
+            gain: params.eq.mid1_gain.out_signal_ref,
+            q: params.eq.mid1_q.out_signal_ref,
+          },
+          equalizer::BandInputs {
+            frequency: params.eq.mid2_frequency.out_signal_ref,
+            gain: params.eq.mid2_gain.out_signal_ref,
+            q: params.eq.mid2_q.out_signal_ref,
+          },
+          equalizer::BandInputs {
+            frequency: params.eq.high_frequency.out_signal_ref,
+            gain: params.eq.high_gain.out_signal_ref,
+            q: params.eq.high_q.out_signal_ref,
+          },
+        ],
+      },
+      outputs: equalizer::Outputs {
+        left: signals.eq_left,
+        right: signals.eq_right,
+      },
+    };
+
+    let comp_slot = program.effect_slot(
+      params.comp.on.out_signal_ref,
+      signals.comp_left,
+      signals.comp_right,
+    );
+
+    let comp = compressor::Block {
+      inputs: compressor::Inputs {
+        left: comp_slot.input_left,
+        right: comp_slot.input_right,
+        threshold: params.comp.threshold.out_signal_ref,
+        ratio: params.comp.ratio.out_signal_ref,
+        attack: params.comp.attack.out_signal_ref,
+        release: params.comp.release.out_signal_ref,
+        knee: params.comp.knee.out_signal_ref,
+        makeup: params.comp.makeup.out_signal_ref,
+      },
+      outputs: compressor::Outputs {
+        left: signals.comp_left,
+        right: signals.comp_right,
+      },
+    };
+
+    let master_slot = program.effect_slot(one, signals.master_left, signals.master_right);
+
+    let master = dca::Block {
+      inputs: dca::Inputs {
+        left: master_slot.input_left,
+        right: master_slot.input_right,
+        velocity: one,
+        amplitude: params.master.amplitude.out_signal_ref,
+        amp_mod: zero,
+        eg_mod: one,
+        pan: params.master.pan.out_signal_ref,
+        pan_mod: zero,
+      },
+      outputs: dca::Outputs {
+        left: signals.master_left,
+        right: signals.master_right,
+      },
+    };
+
+    let limiter_slot = program.effect_slot(
+      params.limiter.on.out_signal_ref,
+      signals.limiter_left,
+      signals.limiter_right,
+    );
+
+    let limiter = limiter::Block {
+      inputs: limiter::Inputs {
+        left: limiter_slot.input_left,
+        right: limiter_slot.input_right,
+        ceiling: params.limiter.ceiling.out_signal_ref,
+        release: params.limiter.release.out_signal_ref,
+      },
+      outputs: limiter::Outputs {
+        left: signals.limiter_left,
+        right: signals.limiter_right,
+      },
+    };
+
     params.lfo1.add_param_blocks(program);
     program.block(Block::Lfo(lfo1));
 
     params.lfo2.add_param_blocks(program);
     program.block(Block::Lfo(lfo2));
 
+    params.glfo1.add_global_param_blocks(program);
+    program.global_block(Block::Lfo(glfo1));
+
     params.eg1.add_param_blocks(program);
     program.block(Block::EG(eg1));
 
     program.block(Block::Expr(eg1_dca_mod));
 
+    params.pitch_bend.add_param_blocks(program);
+
     params.osc1.add_param_blocks(program);
     program.block(Block::Osc(osc1));
 
@@ -363,14 +911,50 @@ impl KiroModule {
     params.osc4.add_param_blocks(program);
     program.block(Block::Osc(osc4));
 
-    program.block(Block::Expr(osc_mix));
+    params.mix1.add_param_blocks(program);
+    params.mix2.add_param_blocks(program);
+    params.mix3.add_param_blocks(program);
+    params.mix4.add_param_blocks(program);
+    program.block(Block::Mixer(mixer));
+
+    params.envf1.add_param_blocks(program);
+    program.block(Block::EnvelopeFollower(envf1));
 
     params.filter1.add_param_blocks(program);
     program.block(Block::Filter(filter1));
 
+    params.shaper.add_param_blocks(program);
+    program.block(Block::Waveshaper(shaper));
+
+    program.block(Block::Expr(voice_left));
+    program.block(Block::Expr(voice_right));
+
     params.dca.add_param_blocks(program);
     program.block(Block::DCA(dca));
 
+    params.modfx.add_effect_param_blocks(program);
+    params.delay.add_effect_param_blocks(program);
+    params.reverb.add_effect_param_blocks(program);
+    params.eq.add_effect_param_blocks(program);
+    params.comp.add_effect_param_blocks(program);
+    params.master.add_effect_param_blocks(program);
+    params.limiter.add_effect_param_blocks(program);
+
+    // The effects chain, in the order the slots are processed
+    let effect_slots = [
+      (modfx_slot, Block::ModFx(modfx)),
+      (delay_slot, Block::Delay(delay)),
+      (reverb_slot, Block::Reverb(reverb)),
+      (eq_slot, Block::Equalizer(eq)),
+      (comp_slot, Block::Compressor(comp)),
+      (master_slot, Block::DCA(master)),
+      (limiter_slot, Block::Limiter(limiter)),
+    ];
+
+    for (slot, block) in effect_slots.iter() {
+      program.effect(*slot, block.clone());
+    }
+
     KiroModule {
       signals,
       sources,
@@ -15,10 +15,44 @@ macro_rules! param_blocks {
           )*
         )?
       }
+
+      #[allow(dead_code)]
+      pub fn add_effect_param_blocks<F: Float>(&self, program: &mut ProgramBuilder<F>) {
+        $(
+          program.effect_param(self.$param_block.clone());
+        )*
+
+        $(
+          $(
+            self.$nested.add_effect_param_blocks(program)
+          )*
+        )?
+      }
+
+      #[allow(dead_code)]
+      pub fn add_global_param_blocks<F: Float>(&self, program: &mut ProgramBuilder<F>) {
+        $(
+          program.global_block(Block::Param(self.$param_block.clone()));
+        )*
+
+        $(
+          $(
+            self.$nested.add_global_param_blocks(program)
+          )*
+        )?
+      }
     }
   };
 }
 
+pub struct PitchBendParams {
+  pub bend: ParamBlock,
+  pub up: ParamBlock,
+  pub down: ParamBlock,
+}
+
+param_blocks!(PitchBendParams, bend, up, down);
+
 pub struct EnvGenParams {
   pub attack: ParamBlock,
   pub decay: ParamBlock,
@@ -44,12 +78,17 @@ param_blocks!(
 
 pub struct LfoParams {
   pub shape: ParamBlock,
+  pub mode: ParamBlock,
   pub rate: ParamBlock,
   pub phase: ParamBlock,
   pub depth: ParamBlock,
+  pub delay: ParamBlock,
+  pub fade_in: ParamBlock,
+  pub sync: ParamBlock,
+  pub division: ParamBlock,
 }
 
-param_blocks!(LfoParams, shape, rate, phase, depth);
+param_blocks!(LfoParams, shape, mode, rate, phase, depth, delay, fade_in, sync, division);
 
 pub struct OscParams {
   pub shape: ParamBlock,
@@ -75,3 +114,157 @@ pub struct DcaParams {
 }
 
 param_blocks!(DcaParams, amplitude, pan);
+
+pub struct DelayParams {
+  pub on: ParamBlock,
+  pub mode: ParamBlock,
+  pub time_left: ParamBlock,
+  pub time_right: ParamBlock,
+  pub sync: ParamBlock,
+  pub division_left: ParamBlock,
+  pub division_right: ParamBlock,
+  pub feedback: ParamBlock,
+  pub cross_feedback: ParamBlock,
+  pub lowpass: ParamBlock,
+  pub highpass: ParamBlock,
+  pub mix: ParamBlock,
+}
+
+param_blocks!(
+  DelayParams,
+  on,
+  mode,
+  time_left,
+  time_right,
+  sync,
+  division_left,
+  division_right,
+  feedback,
+  cross_feedback,
+  lowpass,
+  highpass,
+  mix
+);
+
+pub struct ReverbParams {
+  pub on: ParamBlock,
+  pub size: ParamBlock,
+  pub decay: ParamBlock,
+  pub damping: ParamBlock,
+  pub predelay: ParamBlock,
+  pub width: ParamBlock,
+  pub mix: ParamBlock,
+}
+
+param_blocks!(ReverbParams, on, size, decay, damping, predelay, width, mix);
+
+pub struct ModFxParams {
+  pub on: ParamBlock,
+  pub mode: ParamBlock,
+  pub shape: ParamBlock,
+  pub rate: ParamBlock,
+  pub depth: ParamBlock,
+  pub feedback: ParamBlock,
+  pub spread: ParamBlock,
+  pub mix: ParamBlock,
+}
+
+param_blocks!(
+  ModFxParams,
+  on,
+  mode,
+  shape,
+  rate,
+  depth,
+  feedback,
+  spread,
+  mix
+);
+
+pub struct WaveshaperParams {
+  pub curve: ParamBlock,
+  pub drive: ParamBlock,
+  pub mix: ParamBlock,
+  pub oversampling: ParamBlock,
+}
+
+param_blocks!(WaveshaperParams, curve, drive, mix, oversampling);
+
+pub struct CompressorParams {
+  pub on: ParamBlock,
+  pub threshold: ParamBlock,
+  pub ratio: ParamBlock,
+  pub attack: ParamBlock,
+  pub release: ParamBlock,
+  pub knee: ParamBlock,
+  pub makeup: ParamBlock,
+}
+
+param_blocks!(
+  CompressorParams,
+  on,
+  threshold,
+  ratio,
+  attack,
+  release,
+  knee,
+  makeup
+);
+
+pub struct LimiterParams {
+  pub on: ParamBlock,
+  pub ceiling: ParamBlock,
+  pub release: ParamBlock,
+}
+
+param_blocks!(LimiterParams, on, ceiling, release);
+
+pub struct EqualizerParams {
+  pub on: ParamBlock,
+  pub low_frequency: ParamBlock,
+  pub low_gain: ParamBlock,
+  pub low_q: ParamBlock,
+  pub mid1_frequency: ParamBlock,
+  pub mid1_gain: ParamBlock,
+  pub mid1_q: ParamBlock,
+  pub mid2_frequency: ParamBlock,
+  pub mid2_gain: ParamBlock,
+  pub mid2_q: ParamBlock,
+  pub high_frequency: ParamBlock,
+  pub high_gain: ParamBlock,
+  pub high_q: ParamBlock,
+}
+
+param_blocks!(
+  EqualizerParams,
+  on,
+  low_frequency,
+  low_gain,
+  low_q,
+  mid1_frequency,
+  mid1_gain,
+  mid1_q,
+  mid2_frequency,
+  mid2_gain,
+  mid2_q,
+  high_frequency,
+  high_gain,
+  high_q
+);
+
+pub struct MixerChannelParams {
+  pub level: ParamBlock,
+  pub pan: ParamBlock,
+  pub send: ParamBlock,
+  pub mute: ParamBlock,
+}
+
+param_blocks!(MixerChannelParams, level, pan, send, mute);
+
+pub struct EnvFollowerParams {
+  pub detector: ParamBlock,
+  pub attack: ParamBlock,
+  pub release: ParamBlock,
+}
+
+param_blocks!(EnvFollowerParams, detector, attack, release);
@@ -1,6 +1,9 @@
+use kiro_synth_dsp::effects::equalizer;
 use kiro_synth_dsp::filters::freq_control::FreqControl;
 use kiro_synth_dsp::float::Float;
+use kiro_synth_engine::program::blocks::{delay, reverb};
 use kiro_synth_engine::program::ParamValues;
+use kiro_synth_engine::transport::Division;
 use num_traits::ToPrimitive;
 
 pub fn pitch_bend<F: Float>() -> ParamValues<F> {
@@ -13,6 +16,17 @@ pub fn pitch_bend<F: Float>() -> ParamValues<F> {
   }
 }
 
+/// Range in semitones of the pitch bend, up to 4 octaves as some MPE controllers use
+pub fn pitch_bend_range<F: Float>() -> ParamValues<F> {
+  ParamValues {
+    initial_value: F::one(),
+    origin: F::zero(),
+    min: F::zero(),
+    max: F::val(48.0),
+    resolution: F::val(0.01),
+  }
+}
+
 pub fn adsr<F: Float, T: ToPrimitive>(value: T) -> ParamValues<F> {
   ParamValues {
     initial_value: F::val(value),
@@ -123,6 +137,210 @@ pub fn lfo_phase<F: Float>() -> ParamValues<F> {
   }
 }
 
+pub fn lfo_mode<F: Float>() -> ParamValues<F> {
+  ParamValues {
+    initial_value: F::one(),
+    origin: F::zero(),
+    min: F::zero(),
+    max: F::val(2.0),
+    resolution: F::one(),
+  }
+}
+
+pub fn lfo_time<F: Float>() -> ParamValues<F> {
+  ParamValues {
+    initial_value: F::zero(),
+    origin: F::zero(),
+    min: F::zero(),
+    max: F::val(10.0),
+    resolution: F::val(0.01),
+  }
+}
+
+pub fn lfo_division<F: Float>() -> ParamValues<F> {
+  enumeration(Division::count()).with_initial_value(F::val(Division::quarter_index()))
+}
+
+pub fn delay_time<F: Float, T: ToPrimitive>(value: T) -> ParamValues<F> {
+  ParamValues {
+    initial_value: F::val(value),
+    origin: F::zero(),
+    min: F::zero(),
+    max: F::val(delay::MAX_DELAY),
+    resolution: F::val(0.001),
+  }
+}
+
+pub fn delay_feedback<F: Float>() -> ParamValues<F> {
+  ParamValues {
+    initial_value: F::val(0.4),
+    origin: F::zero(),
+    min: F::zero(),
+    max: F::one(),
+    resolution: F::val(0.01),
+  }
+}
+
+pub fn modfx_feedback<F: Float>() -> ParamValues<F> {
+  ParamValues {
+    initial_value: F::zero(),
+    origin: F::zero(),
+    min: F::one().neg(),
+    max: F::one(),
+    resolution: F::val(0.01),
+  }
+}
+
+pub fn reverb_decay<F: Float>() -> ParamValues<F> {
+  ParamValues {
+    initial_value: F::val(2.0),
+    origin: F::zero(),
+    min: F::val(0.1),
+    max: F::val(20.0),
+    resolution: F::val(0.01),
+  }
+}
+
+pub fn reverb_predelay<F: Float>() -> ParamValues<F> {
+  ParamValues {
+    initial_value: F::val(0.02),
+    origin: F::zero(),
+    min: F::zero(),
+    max: F::val(reverb::MAX_PREDELAY),
+    resolution: F::val(0.001),
+  }
+}
+
+pub fn eq_frequency<F: Float>(band: usize) -> ParamValues<F> {
+  ParamValues {
+    initial_value: F::val(equalizer::BAND_FREQUENCIES[band]),
+    origin: F::val(20.0),
+    min: F::val(20.0),
+    max: F::val(20000.0),
+    resolution: F::one(),
+  }
+}
+
+pub fn eq_gain<F: Float>() -> ParamValues<F> {
+  ParamValues {
+    initial_value: F::zero(),
+    origin: F::zero(),
+    min: F::val(-18.0),
+    max: F::val(18.0),
+    resolution: F::val(0.1),
+  }
+}
+
+pub fn eq_q<F: Float>() -> ParamValues<F> {
+  ParamValues {
+    initial_value: F::val(0.707),
+    origin: F::val(0.1),
+    min: F::val(0.1),
+    max: F::val(10.0),
+    resolution: F::val(0.01),
+  }
+}
+
+pub fn comp_threshold<F: Float>() -> ParamValues<F> {
+  ParamValues {
+    initial_value: F::zero(),
+    origin: F::zero(),
+    min: F::val(-60.0),
+    max: F::zero(),
+    resolution: F::val(0.1),
+  }
+}
+
+pub fn comp_ratio<F: Float>() -> ParamValues<F> {
+  ParamValues {
+    initial_value: F::val(4.0),
+    origin: F::one(),
+    min: F::one(),
+    max: F::val(20.0),
+    resolution: F::val(0.1),
+  }
+}
+
+pub fn comp_attack<F: Float>() -> ParamValues<F> {
+  ParamValues {
+    initial_value: F::val(0.01),
+    origin: F::zero(),
+    min: F::val(0.0001),
+    max: F::one(),
+    resolution: F::val(0.0001),
+  }
+}
+
+pub fn comp_release<F: Float>() -> ParamValues<F> {
+  ParamValues {
+    initial_value: F::val(0.1),
+    origin: F::zero(),
+    min: F::val(0.01),
+    max: F::val(2.0),
+    resolution: F::val(0.001),
+  }
+}
+
+pub fn comp_knee<F: Float>() -> ParamValues<F> {
+  ParamValues {
+    initial_value: F::val(6.0),
+    origin: F::zero(),
+    min: F::zero(),
+    max: F::val(24.0),
+    resolution: F::val(0.1),
+  }
+}
+
+pub fn comp_makeup<F: Float>() -> ParamValues<F> {
+  ParamValues {
+    initial_value: F::zero(),
+    origin: F::zero(),
+    min: F::zero(),
+    max: F::val(24.0),
+    resolution: F::val(0.1),
+  }
+}
+
+pub fn limiter_ceiling<F: Float>() -> ParamValues<F> {
+  ParamValues {
+    initial_value: F::val(-0.3),
+    origin: F::zero(),
+    min: F::val(-24.0),
+    max: F::zero(),
+    resolution: F::val(0.1),
+  }
+}
+
+pub fn limiter_release<F: Float>() -> ParamValues<F> {
+  ParamValues {
+    initial_value: F::val(0.05),
+    origin: F::zero(),
+    min: F::val(0.01),
+    max: F::one(),
+    resolution: F::val(0.001),
+  }
+}
+
+pub fn envf_attack<F: Float>() -> ParamValues<F> {
+  ParamValues {
+    initial_value: F::val(0.01),
+    origin: F::zero(),
+    min: F::val(0.0001),
+    max: F::one(),
+    resolution: F::val(0.0001),
+  }
+}
+
+pub fn envf_release<F: Float>() -> ParamValues<F> {
+  ParamValues {
+    initial_value: F::val(0.1),
+    origin: F::zero(),
+    min: F::val(0.001),
+    max: F::val(2.0),
+    resolution: F::val(0.001),
+  }
+}
+
 pub fn filt_freq<F: Float>() -> ParamValues<F> {
   ParamValues {
     initial_value: FreqControl::default_frequency(),
@@ -0,0 +1,532 @@
+//! Microtuning with scales and keyboard mappings in the Scala formats.
+//!
+//! A scale (`.scl`) defines the pitches of the degrees as ratios from the first one,
+//! and a keyboard mapping (`.kbm`) defines which degree plays every key and the reference frequency.
+//! See http://www.huygens-fokker.org/scala/scl_format.html and
+//! http://www.huygens-fokker.org/scala/help.htm#mappings
+//!
+//! The engine only receives the resulting frequency of every key.
+
+use kiro_synth_engine::tuning::{KeyFrequencies, DEFAULT_REFERENCE_FREQUENCY, NUM_KEYS};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Error {
+  /// A line has an unexpected value. The error includes the line number starting from 1.
+  InvalidLine(usize),
+
+  /// The file ended before all the expected values were found
+  MissingData,
+
+  /// The scale does not have any pitch
+  EmptyScale,
+
+  /// The reference key of the keyboard mapping does not play any degree of the scale
+  UnmappedReference,
+}
+
+/// Lines of a Scala file that are not comments, together with their line number
+fn lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+  text
+    .lines()
+    .enumerate()
+    .map(|(index, line)| (index + 1, line))
+    .filter(|(_, line)| !line.starts_with('!'))
+}
+
+/// The first word of a line, the rest is ignored
+fn first_word(line: &str) -> &str {
+  line.split_whitespace().next().unwrap_or("")
+}
+
+fn parse_value<T: core::str::FromStr>(line: Option<(usize, &str)>) -> Result<T, Error> {
+  let (number, line) = line.ok_or(Error::MissingData)?;
+  first_word(line)
+    .parse()
+    .map_err(|_| Error::InvalidLine(number))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scale {
+  pub description: String,
+  /// The ratio from the first degree for the degrees from 1 to the length of the scale.
+  /// The last one is the period where the scale repeats (usually the octave).
+  pub ratios: Vec<f64>,
+}
+
+impl Default for Scale {
+  fn default() -> Self {
+    Scale::equal_temperament(12)
+  }
+}
+
+impl Scale {
+  /// A scale that divides the octave in equal steps
+  pub fn equal_temperament(divisions: usize) -> Self {
+    let divisions = divisions.max(1);
+    Scale {
+      description: format!("{} tone equal temperament", divisions),
+      ratios: (1..=divisions)
+        .map(|degree| 2.0f64.powf(degree as f64 / divisions as f64))
+        .collect(),
+    }
+  }
+
+  /// Parses the content of a `.scl` file
+  pub fn parse(text: &str) -> Result<Self, Error> {
+    let mut lines = lines(text);
+    let description = lines.next().ok_or(Error::MissingData)?.1.trim().to_string();
+
+    let mut lines = lines.filter(|(_, line)| !line.trim().is_empty());
+    let count: usize = parse_value(lines.next())?;
+    if count == 0 {
+      return Err(Error::EmptyScale);
+    }
+
+    let mut ratios = Vec::with_capacity(count);
+    for _ in 0..count {
+      let (number, line) = lines.next().ok_or(Error::MissingData)?;
+      let ratio = Self::parse_pitch(first_word(line)).ok_or(Error::InvalidLine(number))?;
+      ratios.push(ratio);
+    }
+
+    Ok(Scale {
+      description,
+      ratios,
+    })
+  }
+
+  /// A pitch with a period is in cents, otherwise it is a ratio or an integer
+  fn parse_pitch(pitch: &str) -> Option<f64> {
+    let ratio = if pitch.contains('.') {
+      let cents: f64 = pitch.parse().ok()?;
+      2.0f64.powf(cents / 1200.0)
+    } else if let Some(index) = pitch.find('/') {
+      let numerator: u64 = pitch[..index].parse().ok()?;
+      let denominator: u64 = pitch[index + 1..].parse().ok()?;
+      numerator as f64 / denominator as f64
+    } else {
+      pitch.parse::<u64>().ok()? as f64
+    };
+
+    Some(ratio).filter(|ratio| ratio.is_finite() && *ratio > 0.0)
+  }
+
+  /// Number of degrees before the scale repeats
+  pub fn len(&self) -> usize {
+    self.ratios.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.ratios.is_empty()
+  }
+
+  /// The ratio from the first degree for any degree, including the ones in other periods
+  pub fn ratio(&self, degree: i32) -> f64 {
+    let len = self.ratios.len() as i32;
+    let period = self.ratios[self.ratios.len() - 1];
+    let step = degree.rem_euclid(len);
+    let ratio = if step == 0 {
+      1.0
+    } else {
+      self.ratios[step as usize - 1]
+    };
+    ratio * period.powi(degree.div_euclid(len))
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyboardMapping {
+  /// First key that is retuned, the keys below are not mapped
+  pub first_key: u8,
+  /// Last key that is retuned, the keys above are not mapped
+  pub last_key: u8,
+  /// Key that plays the first degree of the scale
+  pub middle_key: u8,
+  /// Key for which the frequency is given
+  pub reference_key: u8,
+  /// Frequency of the reference key in Hz
+  pub reference_frequency: f64,
+  /// Degree of the scale that makes the formal octave of the mapping. Zero means the length of the scale.
+  pub octave_degree: usize,
+  /// The degree played by every key of the pattern starting from the middle key, or None if it is not mapped.
+  /// An empty pattern maps every key to the next degree.
+  pub degrees: Vec<Option<usize>>,
+}
+
+impl Default for KeyboardMapping {
+  fn default() -> Self {
+    KeyboardMapping {
+      first_key: 0,
+      last_key: (NUM_KEYS - 1) as u8,
+      middle_key: 60,
+      reference_key: 69,
+      reference_frequency: DEFAULT_REFERENCE_FREQUENCY,
+      octave_degree: 0,
+      degrees: Vec::new(),
+    }
+  }
+}
+
+impl KeyboardMapping {
+  /// Parses the content of a `.kbm` file
+  pub fn parse(text: &str) -> Result<Self, Error> {
+    let mut lines = lines(text).filter(|(_, line)| !line.trim().is_empty());
+
+    let size: usize = parse_value(lines.next())?;
+    let first_key = Self::parse_key(lines.next())?;
+    let last_key = Self::parse_key(lines.next())?;
+    let middle_key = Self::parse_key(lines.next())?;
+    let reference_key = Self::parse_key(lines.next())?;
+    let (number, line) = lines.next().ok_or(Error::MissingData)?;
+    let reference_frequency = first_word(line)
+      .parse::<f64>()
+      .ok()
+      .filter(|frequency| frequency.is_finite() && *frequency > 0.0)
+      .ok_or(Error::InvalidLine(number))?;
+    let octave_degree: usize = parse_value(lines.next())?;
+
+    // the keys without an entry at the end of the pattern are not mapped
+    let mut degrees = vec![None; size];
+    for (degree, (number, line)) in degrees.iter_mut().zip(lines) {
+      *degree = match first_word(line) {
+        "x" => None,
+        value => Some(value.parse().map_err(|_| Error::InvalidLine(number))?),
+      };
+    }
+
+    Ok(KeyboardMapping {
+      first_key,
+      last_key,
+      middle_key,
+      reference_key,
+      reference_frequency,
+      octave_degree,
+      degrees,
+    })
+  }
+
+  fn parse_key(line: Option<(usize, &str)>) -> Result<u8, Error> {
+    let number = line.map(|(number, _)| number).unwrap_or_default();
+    parse_value(line).and_then(|key: u8| {
+      if (key as usize) < NUM_KEYS {
+        Ok(key)
+      } else {
+        Err(Error::InvalidLine(number))
+      }
+    })
+  }
+
+  /// The degree of a scale with the given length played by a key, or None if it is not mapped
+  pub fn degree(&self, key: u8, scale_len: usize) -> Option<i32> {
+    if key < self.first_key || key > self.last_key {
+      return None;
+    }
+
+    let offset = key as i32 - self.middle_key as i32;
+    if self.degrees.is_empty() {
+      return Some(offset);
+    }
+
+    let size = self.degrees.len() as i32;
+    let octave_degree = if self.octave_degree == 0 {
+      scale_len
+    } else {
+      self.octave_degree
+    } as i32;
+
+    self.degrees[offset.rem_euclid(size) as usize]
+      .map(|degree| degree as i32 + offset.div_euclid(size) * octave_degree)
+  }
+}
+
+/// The frequency played by every key.
+///
+/// On top of the scale and the keyboard mapping, the reference frequency tunes A4 (master tune)
+/// scaling all the frequencies by its ratio to 440 Hz, and the transpose shifts all the frequencies
+/// by equal tempered semitones, no matter how many degrees the scale has.
+#[derive(Debug, Clone)]
+pub struct Tuning {
+  scale: Scale,
+  mapping: KeyboardMapping,
+  /// Degree of the scale played by the reference key of the mapping
+  reference_degree: i32,
+  reference_frequency: f64,
+  transpose: i8,
+  /// Frequencies given for single keys, they take precedence over the scale
+  key_frequencies: Vec<Option<f64>>,
+  frequencies: Vec<Option<f64>>,
+}
+
+impl Default for Tuning {
+  fn default() -> Self {
+    Tuning::new(Scale::default(), KeyboardMapping::default()).unwrap()
+  }
+}
+
+impl Tuning {
+  pub fn new(scale: Scale, mapping: KeyboardMapping) -> Result<Self, Error> {
+    if scale.is_empty() {
+      return Err(Error::EmptyScale);
+    }
+
+    let reference_degree = mapping
+      .degree(mapping.reference_key, scale.len())
+      .ok_or(Error::UnmappedReference)?;
+
+    let mut tuning = Tuning {
+      scale,
+      mapping,
+      reference_degree,
+      reference_frequency: DEFAULT_REFERENCE_FREQUENCY,
+      transpose: 0,
+      key_frequencies: vec![None; NUM_KEYS],
+      frequencies: vec![None; NUM_KEYS],
+    };
+    tuning.update();
+    Ok(tuning)
+  }
+
+  pub fn scale(&self) -> &Scale {
+    &self.scale
+  }
+
+  pub fn reference_frequency(&self) -> f64 {
+    self.reference_frequency
+  }
+
+  /// Frequency of A4 in Hz
+  pub fn set_reference_frequency(&mut self, frequency: f64) {
+    if frequency.is_finite() && frequency > 0.0 {
+      self.reference_frequency = frequency;
+      self.update();
+    }
+  }
+
+  pub fn transpose(&self) -> i8 {
+    self.transpose
+  }
+
+  /// Transpose in semitones
+  pub fn set_transpose(&mut self, semitones: i8) {
+    self.transpose = semitones;
+    self.update();
+  }
+
+  /// Changes the frequency in Hz of a single key, no matter the scale, until the whole octave is retuned
+  pub fn set_key_frequency(&mut self, key: u8, frequency: f64) {
+    let key = key as usize;
+    if key < NUM_KEYS && frequency.is_finite() && frequency > 0.0 {
+      self.key_frequencies[key] = Some(frequency);
+      self.frequencies[key] = Some(frequency * self.transpose_ratio());
+    }
+  }
+
+  /// Retunes the twelve notes of every octave, starting from C, by an offset in cents from the equal temperament.
+  /// It replaces the scale and the keyboard mapping, and the frequencies of the single keys.
+  pub fn set_octave_offsets(&mut self, offsets: &[f64; 12]) {
+    let cents = |degree: usize| degree as f64 * 100.0 + offsets[degree % 12] - offsets[0];
+    self.scale = Scale {
+      description: "Scale/octave tuning".to_string(),
+      ratios: (1..=12)
+        .map(|degree| 2.0f64.powf(cents(degree) / 1200.0))
+        .collect(),
+    };
+
+    let reference_offset = 2.0f64.powf(offsets[9] / 1200.0);
+    self.mapping = KeyboardMapping {
+      reference_frequency: DEFAULT_REFERENCE_FREQUENCY * reference_offset,
+      ..KeyboardMapping::default()
+    };
+    self.reference_degree = 9;
+
+    for frequency in self.key_frequencies.iter_mut() {
+      *frequency = None;
+    }
+
+    self.update();
+  }
+
+  /// The frequency in Hz for a key, or None if the key is not mapped
+  pub fn frequency(&self, key: u8) -> Option<f64> {
+    self.frequencies.get(key as usize).copied().flatten()
+  }
+
+  /// The frequencies of all the keys, as the engine needs them
+  pub fn key_frequencies(&self) -> KeyFrequencies {
+    let mut frequencies = [None; NUM_KEYS];
+    for (key, frequency) in frequencies.iter_mut().enumerate() {
+      *frequency = self.frequency(key as u8);
+    }
+    KeyFrequencies::new(frequencies)
+  }
+
+  fn transpose_ratio(&self) -> f64 {
+    2.0f64.powf(f64::from(self.transpose) / 12.0)
+  }
+
+  fn update(&mut self) {
+    let scale = &self.scale;
+    let mapping = &self.mapping;
+    let master_tune = self.reference_frequency / DEFAULT_REFERENCE_FREQUENCY;
+    let frequency_per_ratio =
+      master_tune * mapping.reference_frequency / scale.ratio(self.reference_degree);
+
+    for (key, frequency) in self.frequencies.iter_mut().enumerate() {
+      *frequency = mapping
+        .degree(key as u8, scale.len())
+        .map(|degree| scale.ratio(degree) * frequency_per_ratio);
+    }
+
+    for (frequency, key_frequency) in self.frequencies.iter_mut().zip(self.key_frequencies.iter()) {
+      if key_frequency.is_some() {
+        *frequency = *key_frequency;
+      }
+    }
+
+    let transpose_ratio = self.transpose_ratio();
+    for frequency in self.frequencies.iter_mut() {
+      *frequency = frequency.map(|frequency| frequency * transpose_ratio);
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use assert_approx_eq::assert_approx_eq;
+
+  const MEANTONE: &str = "! meanquar.scl
+!
+1/4-comma meantone scale. Pietro Aaron's temperament (1523)
+ 12
+!
+ 76.04900
+ 193.15686
+ 310.26471
+ 5/4
+ 503.42157
+ 579.47057
+ 696.57843
+ 25/16
+ 889.73529
+ 1006.84314
+ 1082.89214
+ 2/1
+";
+
+  const WHITE_KEYS: &str = "! white keys on a 7 notes scale
+12
+0
+127
+60
+69
+440.0
+7
+0
+x
+1
+x
+2
+3
+x
+4
+x
+5
+x
+6
+";
+
+  #[test]
+  fn tuning_default_is_equal_temperament() {
+    let tuning = Tuning::default();
+    assert_approx_eq!(tuning.frequency(69).unwrap(), 440.0);
+    assert_approx_eq!(tuning.frequency(60).unwrap(), 261.6256, 1e-4);
+    assert_approx_eq!(tuning.frequency(0).unwrap(), 8.1758, 1e-4);
+    assert_approx_eq!(tuning.frequency(127).unwrap(), 12543.854, 1e-3);
+  }
+
+  #[test]
+  fn tuning_reference_frequency_and_transpose() {
+    let mut tuning = Tuning::default();
+    tuning.set_reference_frequency(442.0);
+    assert_approx_eq!(tuning.frequency(69).unwrap(), 442.0);
+    assert_approx_eq!(tuning.frequency(81).unwrap(), 884.0);
+
+    tuning.set_transpose(-12);
+    assert_approx_eq!(tuning.frequency(81).unwrap(), 442.0);
+    assert_approx_eq!(
+      tuning.frequency(0).unwrap(),
+      442.0 / 440.0 * 8.1758 / 2.0,
+      1e-4
+    );
+  }
+
+  #[test]
+  fn tuning_transpose_in_semitones_for_any_scale() {
+    let mut tuning = Tuning::new(Scale::equal_temperament(7), KeyboardMapping::default()).unwrap();
+    tuning.set_key_frequency(60, 256.0);
+    let frequency = tuning.frequency(70).unwrap();
+    tuning.set_transpose(2);
+    let ratio = 2.0f64.powf(2.0 / 12.0);
+    assert_approx_eq!(tuning.frequency(70).unwrap(), frequency * ratio);
+    assert_approx_eq!(tuning.frequency(60).unwrap(), 256.0 * ratio);
+
+    let frequencies = tuning.key_frequencies();
+    assert_approx_eq!(frequencies.frequency(70).unwrap(), frequency * ratio);
+  }
+
+  #[test]
+  fn tuning_single_keys_and_octave_offsets() {
+    let mut tuning = Tuning::default();
+    tuning.set_key_frequency(60, 256.0);
+    tuning.set_reference_frequency(442.0);
+    assert_approx_eq!(tuning.frequency(60).unwrap(), 256.0);
+
+    let mut offsets = [0.0; 12];
+    offsets[0] = -10.0;
+    offsets[9] = 20.0;
+    tuning.set_octave_offsets(&offsets);
+    let equal =
+      |key: i32, cents: f64| 442.0 * 2.0f64.powf((key as f64 - 69.0) / 12.0 + cents / 1200.0);
+    assert_approx_eq!(tuning.frequency(60).unwrap(), equal(60, -10.0));
+    assert_approx_eq!(tuning.frequency(48).unwrap(), equal(48, -10.0));
+    assert_approx_eq!(tuning.frequency(69).unwrap(), equal(69, 20.0));
+    assert_approx_eq!(tuning.frequency(64).unwrap(), equal(64, 0.0));
+  }
+
+  #[test]
+  fn scale_parse() {
+    let scale = Scale::parse(MEANTONE).unwrap();
+    assert_eq!(
+      scale.description,
+      "1/4-comma meantone scale. Pietro Aaron's temperament (1523)"
+    );
+    assert_eq!(scale.len(), 12);
+    assert_approx_eq!(scale.ratio(4), 1.25);
+    assert_approx_eq!(scale.ratio(12), 2.0);
+    assert_approx_eq!(scale.ratio(-8), 0.625);
+
+    assert_eq!(Scale::parse("empty\n0\n"), Err(Error::EmptyScale));
+    assert_eq!(
+      Scale::parse("bad\n2\n3/2\nfoo\n"),
+      Err(Error::InvalidLine(4))
+    );
+    assert_eq!(Scale::parse("short\n2\n3/2\n"), Err(Error::MissingData));
+  }
+
+  #[test]
+  fn keyboard_mapping_parse() {
+    let mapping = KeyboardMapping::parse(WHITE_KEYS).unwrap();
+    assert_eq!(mapping.degrees.len(), 12);
+    assert_eq!(mapping.degree(60, 7), Some(0));
+    assert_eq!(mapping.degree(61, 7), None);
+    assert_eq!(mapping.degree(71, 7), Some(6));
+    assert_eq!(mapping.degree(72, 7), Some(7));
+    assert_eq!(mapping.degree(59, 7), Some(-1));
+
+    let tuning = Tuning::new(Scale::equal_temperament(7), mapping).unwrap();
+    assert_approx_eq!(tuning.frequency(69).unwrap(), 440.0);
+    assert_approx_eq!(tuning.frequency(81).unwrap(), 880.0);
+    assert_eq!(tuning.frequency(70), None);
+  }
+}
@@ -0,0 +1,46 @@
+use druid::{Data, Lens};
+
+use kiro_synth_dsp::float::Float;
+use kiro_synth_engine::program::Program;
+
+use crate::synth::program::params::CompressorParams;
+use crate::synth::SynthClientMutex;
+use crate::ui::data::Param;
+
+#[derive(Debug, Clone, Data, Lens)]
+pub struct Compressor {
+  pub on: Param,
+  pub threshold: Param,
+  pub ratio: Param,
+  pub attack: Param,
+  pub release: Param,
+  pub knee: Param,
+  pub makeup: Param,
+}
+
+impl Compressor {
+  pub fn new<'a, F: Float + 'static>(
+    program: &Program<'a, F>,
+    params: &CompressorParams,
+    synth_client: SynthClientMutex<f32>,
+  ) -> Self {
+    Compressor {
+      on: Param::new(program, &params.on, synth_client.clone()),
+      threshold: Param::new(program, &params.threshold, synth_client.clone()),
+      ratio: Param::new(program, &params.ratio, synth_client.clone()),
+      attack: Param::new(program, &params.attack, synth_client.clone()),
+      release: Param::new(program, &params.release, synth_client.clone()),
+      knee: Param::new(program, &params.knee, synth_client.clone()),
+      makeup: Param::new(program, &params.makeup, synth_client),
+    }
+  }
+
+  pub fn for_each_modulated_param(&mut self, apply: &impl Fn(&mut Param)) {
+    apply(&mut self.threshold);
+    apply(&mut self.ratio);
+    apply(&mut self.attack);
+    apply(&mut self.release);
+    apply(&mut self.knee);
+    apply(&mut self.makeup);
+  }
+}
@@ -0,0 +1,57 @@
+use druid::{Data, Lens};
+
+use kiro_synth_dsp::float::Float;
+use kiro_synth_engine::program::Program;
+
+use crate::synth::program::params::DelayParams;
+use crate::synth::SynthClientMutex;
+use crate::ui::data::Param;
+
+#[derive(Debug, Clone, Data, Lens)]
+pub struct Delay {
+  pub on: Param,
+  pub mode: Param,
+  pub time_left: Param,
+  pub time_right: Param,
+  pub sync: Param,
+  pub division_left: Param,
+  pub division_right: Param,
+  pub feedback: Param,
+  pub cross_feedback: Param,
+  pub lowpass: Param,
+  pub highpass: Param,
+  pub mix: Param,
+}
+
+impl Delay {
+  pub fn new<'a, F: Float + 'static>(
+    program: &Program<'a, F>,
+    params: &DelayParams,
+    synth_client: SynthClientMutex<f32>,
+  ) -> Self {
+    Delay {
+      on: Param::new(program, &params.on, synth_client.clone()),
+      mode: Param::new(program, &params.mode, synth_client.clone()),
+      time_left: Param::new(program, &params.time_left, synth_client.clone()),
+      time_right: Param::new(program, &params.time_right, synth_client.clone()),
+      sync: Param::new(program, &params.sync, synth_client.clone()),
+      division_left: Param::new(program, &params.division_left, synth_client.clone()),
+      division_right: Param::new(program, &params.division_right, synth_client.clone()),
+      feedback: Param::new(program, &params.feedback, synth_client.clone()),
+      cross_feedback: Param::new(program, &params.cross_feedback, synth_client.clone()),
+      lowpass: Param::new(program, &params.lowpass, synth_client.clone()),
+      highpass: Param::new(program, &params.highpass, synth_client.clone()),
+      mix: Param::new(program, &params.mix, synth_client),
+    }
+  }
+
+  pub fn for_each_modulated_param(&mut self, apply: &impl Fn(&mut Param)) {
+    apply(&mut self.time_left);
+    apply(&mut self.time_right);
+    apply(&mut self.feedback);
+    apply(&mut self.cross_feedback);
+    apply(&mut self.lowpass);
+    apply(&mut self.highpass);
+    apply(&mut self.mix);
+  }
+}
@@ -0,0 +1,64 @@
+use druid::{Data, Lens};
+
+use kiro_synth_dsp::float::Float;
+use kiro_synth_engine::program::Program;
+
+use crate::synth::program::params::EqualizerParams;
+use crate::synth::SynthClientMutex;
+use crate::ui::data::Param;
+
+#[derive(Debug, Clone, Data, Lens)]
+pub struct Equalizer {
+  pub on: Param,
+  pub low_frequency: Param,
+  pub low_gain: Param,
+  pub low_q: Param,
+  pub mid1_frequency: Param,
+  pub mid1_gain: Param,
+  pub mid1_q: Param,
+  pub mid2_frequency: Param,
+  pub mid2_gain: Param,
+  pub mid2_q: Param,
+  pub high_frequency: Param,
+  pub high_gain: Param,
+  pub high_q: Param,
+}
+
+impl Equalizer {
+  pub fn new<'a, F: Float + 'static>(
+    program: &Program<'a, F>,
+    params: &EqualizerParams,
+    synth_client: SynthClientMutex<f32>,
+  ) -> Self {
+    Equalizer {
+      on: Param::new(program, &params.on, synth_client.clone()),
+      low_frequency: Param::new(program, &params.low_frequency, synth_client.clone()),
+      low_gain: Param::new(program, &params.low_gain, synth_client.clone()),
+      low_q: Param::new(program, &params.low_q, synth_client.clone()),
+      mid1_frequency: Param::new(program, &params.mid1_frequency, synth_client.clone()),
+      mid1_gain: Param::new(program, &params.mid1_gain, synth_client.clone()),
+      mid1_q: Param::new(program, &params.mid1_q, synth_client.clone()),
+      mid2_frequency: Param::new(program, &params.mid2_frequency, synth_client.clone()),
+      mid2_gain: Param::new(program, &params.mid2_gain, synth_client.clone()),
+      mid2_q: Param::new(program, &params.mid2_q, synth_client.clone()),
+      high_frequency: Param::new(program, &params.high_frequency, synth_client.clone()),
+      high_gain: Param::new(program, &params.high_gain, synth_client.clone()),
+      high_q: Param::new(program, &params.high_q, synth_client),
+    }
+  }
+
+  pub fn for_each_modulated_param(&mut self, apply: &impl Fn(&mut Param)) {
+    apply(&mut self.low_frequency);
+    apply(&mut self.low_gain);
+    apply(&mut self.low_q);
+    apply(&mut self.mid1_frequency);
+    apply(&mut self.mid1_gain);
+    apply(&mut self.mid1_q);
+    apply(&mut self.mid2_frequency);
+    apply(&mut self.mid2_gain);
+    apply(&mut self.mid2_q);
+    apply(&mut self.high_frequency);
+    apply(&mut self.high_gain);
+    apply(&mut self.high_q);
+  }
+}
@@ -0,0 +1,34 @@
+use druid::{Data, Lens};
+
+use kiro_synth_dsp::float::Float;
+use kiro_synth_engine::program::Program;
+
+use crate::synth::program::params::LimiterParams;
+use crate::synth::SynthClientMutex;
+use crate::ui::data::Param;
+
+#[derive(Debug, Clone, Data, Lens)]
+pub struct Limiter {
+  pub on: Param,
+  pub ceiling: Param,
+  pub release: Param,
+}
+
+impl Limiter {
+  pub fn new<'a, F: Float + 'static>(
+    program: &Program<'a, F>,
+    params: &LimiterParams,
+    synth_client: SynthClientMutex<f32>,
+  ) -> Self {
+    Limiter {
+      on: Param::new(program, &params.on, synth_client.clone()),
+      ceiling: Param::new(program, &params.ceiling, synth_client.clone()),
+      release: Param::new(program, &params.release, synth_client),
+    }
+  }
+
+  pub fn for_each_modulated_param(&mut self, apply: &impl Fn(&mut Param)) {
+    apply(&mut self.ceiling);
+    apply(&mut self.release);
+  }
+}
@@ -0,0 +1,88 @@
+mod compressor;
+mod delay;
+mod equalizer;
+mod limiter;
+mod modfx;
+mod reverb;
+
+use druid::{Data, Lens};
+
+use kiro_synth_dsp::float::Float;
+use kiro_synth_engine::program::Program;
+
+use crate::synth::program::kiro::KiroModule;
+use crate::synth::{SynthClientMutex, SynthFeedback};
+use crate::ui::data::param::Param;
+use crate::ui::data::synth::Dca;
+
+pub use compressor::Compressor;
+pub use delay::Delay;
+pub use equalizer::Equalizer;
+pub use limiter::Limiter;
+pub use modfx::ModFx;
+pub use reverb::Reverb;
+
+#[derive(Debug, Clone, Data, Lens)]
+pub struct Effects {
+  pub effect_index: usize,
+
+  pub modfx: ModFx,
+
+  pub delay: Delay,
+
+  pub reverb: Reverb,
+
+  pub eq: Equalizer,
+
+  pub comp: Compressor,
+
+  pub master: Dca,
+
+  pub limiter: Limiter,
+}
+
+impl Effects {
+  pub fn new<'a, F: Float + 'static>(
+    program: &Program<'a, F>,
+    module: &KiroModule,
+    synth_client: SynthClientMutex<f32>,
+  ) -> Self {
+    let params = &module.params;
+
+    Effects {
+      effect_index: 0,
+
+      modfx: ModFx::new(program, &params.modfx, synth_client.clone()),
+
+      delay: Delay::new(program, &params.delay, synth_client.clone()),
+
+      reverb: Reverb::new(program, &params.reverb, synth_client.clone()),
+
+      eq: Equalizer::new(program, &params.eq, synth_client.clone()),
+
+      comp: Compressor::new(program, &params.comp, synth_client.clone()),
+
+      master: Dca::new(program, &params.master, synth_client.clone()),
+
+      limiter: Limiter::new(program, &params.limiter, synth_client),
+    }
+  }
+
+  pub fn update_feedback(&mut self, feedback: &SynthFeedback) {
+    self.for_each_modulated_param(|param| {
+      let param_index: usize = param.param_ref.into();
+      let modulation = feedback.modulations[param_index];
+      param.modulation.value = modulation as f64;
+    });
+  }
+
+  pub fn for_each_modulated_param(&mut self, apply: impl Fn(&mut Param)) {
+    self.modfx.for_each_modulated_param(&apply);
+    self.delay.for_each_modulated_param(&apply);
+    self.reverb.for_each_modulated_param(&apply);
+    self.eq.for_each_modulated_param(&apply);
+    self.comp.for_each_modulated_param(&apply);
+    self.master.for_each_modulated_param(&apply);
+    self.limiter.for_each_modulated_param(&apply);
+  }
+}
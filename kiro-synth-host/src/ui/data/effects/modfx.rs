@@ -0,0 +1,47 @@
+use druid::{Data, Lens};
+
+use kiro_synth_dsp::float::Float;
+use kiro_synth_engine::program::Program;
+
+use crate::synth::program::params::ModFxParams;
+use crate::synth::SynthClientMutex;
+use crate::ui::data::Param;
+
+#[derive(Debug, Clone, Data, Lens)]
+pub struct ModFx {
+  pub on: Param,
+  pub mode: Param,
+  pub shape: Param,
+  pub rate: Param,
+  pub depth: Param,
+  pub feedback: Param,
+  pub spread: Param,
+  pub mix: Param,
+}
+
+impl ModFx {
+  pub fn new<'a, F: Float + 'static>(
+    program: &Program<'a, F>,
+    params: &ModFxParams,
+    synth_client: SynthClientMutex<f32>,
+  ) -> Self {
+    ModFx {
+      on: Param::new(program, &params.on, synth_client.clone()),
+      mode: Param::new(program, &params.mode, synth_client.clone()),
+      shape: Param::new(program, &params.shape, synth_client.clone()),
+      rate: Param::new(program, &params.rate, synth_client.clone()),
+      depth: Param::new(program, &params.depth, synth_client.clone()),
+      feedback: Param::new(program, &params.feedback, synth_client.clone()).with_origin(0.0),
+      spread: Param::new(program, &params.spread, synth_client.clone()),
+      mix: Param::new(program, &params.mix, synth_client),
+    }
+  }
+
+  pub fn for_each_modulated_param(&mut self, apply: &impl Fn(&mut Param)) {
+    apply(&mut self.rate);
+    apply(&mut self.depth);
+    apply(&mut self.feedback);
+    apply(&mut self.spread);
+    apply(&mut self.mix);
+  }
+}
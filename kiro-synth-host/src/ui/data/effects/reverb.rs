@@ -0,0 +1,46 @@
+use druid::{Data, Lens};
+
+use kiro_synth_dsp::float::Float;
+use kiro_synth_engine::program::Program;
+
+use crate::synth::program::params::ReverbParams;
+use crate::synth::SynthClientMutex;
+use crate::ui::data::Param;
+
+#[derive(Debug, Clone, Data, Lens)]
+pub struct Reverb {
+  pub on: Param,
+  pub size: Param,
+  pub decay: Param,
+  pub damping: Param,
+  pub predelay: Param,
+  pub width: Param,
+  pub mix: Param,
+}
+
+impl Reverb {
+  pub fn new<'a, F: Float + 'static>(
+    program: &Program<'a, F>,
+    params: &ReverbParams,
+    synth_client: SynthClientMutex<f32>,
+  ) -> Self {
+    Reverb {
+      on: Param::new(program, &params.on, synth_client.clone()),
+      size: Param::new(program, &params.size, synth_client.clone()),
+      decay: Param::new(program, &params.decay, synth_client.clone()),
+      damping: Param::new(program, &params.damping, synth_client.clone()),
+      predelay: Param::new(program, &params.predelay, synth_client.clone()),
+      width: Param::new(program, &params.width, synth_client.clone()),
+      mix: Param::new(program, &params.mix, synth_client),
+    }
+  }
+
+  pub fn for_each_modulated_param(&mut self, apply: &impl Fn(&mut Param)) {
+    apply(&mut self.size);
+    apply(&mut self.decay);
+    apply(&mut self.damping);
+    apply(&mut self.predelay);
+    apply(&mut self.width);
+    apply(&mut self.mix);
+  }
+}
@@ -1,3 +1,4 @@
+pub mod effects;
 pub mod header;
 pub mod modulations;
 pub mod param;
@@ -11,6 +12,7 @@ use kiro_synth_engine::program::{ParamRef, Program, SourceRef};
 use crate::synth::program::kiro::KiroModule;
 use crate::synth::SynthClientMutex;
 
+use effects::Effects;
 use header::Header;
 use modulations::Modulations;
 pub use param::Param;
@@ -20,6 +22,7 @@ use synth::Synth;
 pub struct AppData {
   pub header: Header,
   pub synth: Synth,
+  pub effects: Effects,
   pub modulations: Modulations,
 
   #[data(ignore)]
@@ -35,6 +38,7 @@ impl AppData {
     AppData {
       header: Header::new(synth_client.clone()),
       synth: Synth::new(program, module, synth_client.clone()),
+      effects: Effects::new(program, module, synth_client.clone()),
       modulations: Modulations::new(program, module, synth_client.clone()),
       synth_client,
     }
@@ -43,7 +47,7 @@ impl AppData {
   pub fn with_init_modulations_config(mut self) -> Self {
     let total_amounts = self.modulations.get_total_amounts_by_param();
 
-    self.synth.for_each_modulated_param(move |param| {
+    self.for_each_modulated_param(move |param| {
       param.modulation.total_amount = total_amounts
         .get(&param.param_ref.into())
         .cloned()
@@ -57,6 +61,7 @@ impl AppData {
     if let Some(feedback) = self.synth_client.get_feedback().unwrap_or(None) {
       self.header.update_feedback(&feedback);
       self.synth.update_feedback(&feedback);
+      self.effects.update_feedback(&feedback);
     }
   }
 
@@ -66,7 +71,7 @@ impl AppData {
     let total_amounts = self.modulations.get_total_amounts_by_param();
     // println!("{:#?}\n{:#?}", config_amounts, total_amounts);
     let config_source = self.modulations.config_source;
-    self.synth.for_each_modulated_param(move |param| {
+    self.for_each_modulated_param(move |param| {
       let key: usize = param.param_ref.into();
       param.modulation.config_source = config_source;
       param.modulation.config_amount = config_amounts.get(&key).cloned().unwrap_or(0.0);
@@ -92,7 +97,7 @@ impl AppData {
       .is_some();
 
     // TODO optimize this block by providing a for_modulated_param(param_ref) at Synth
-    self.synth.for_each_modulated_param(move |param| {
+    self.for_each_modulated_param(move |param| {
       if param.param_ref == param_ref {
         if same_source {
           param.modulation.config_amount = config_amount;
@@ -104,7 +109,7 @@ impl AppData {
 
   pub fn stop_modulations_config(&mut self, source_ref: SourceRef) {
     self.modulations.stop_config(source_ref);
-    self.synth.for_each_modulated_param(move |param| {
+    self.for_each_modulated_param(move |param| {
       param.modulation.config_source = None;
       param.modulation.config_amount = 0.0;
     });
@@ -113,10 +118,15 @@ impl AppData {
   pub fn delete_modulation(&mut self, source_ref: SourceRef, param_ref: ParamRef) {
     self.modulations.delete_modulation(source_ref, param_ref);
     let total_amount = self.modulations.get_total_amounts_for_param(param_ref);
-    self.synth.for_each_modulated_param(move |param| {
+    self.for_each_modulated_param(move |param| {
       if param.param_ref == param_ref {
         param.modulation.total_amount = total_amount;
       }
     });
   }
+
+  fn for_each_modulated_param(&mut self, apply: impl Fn(&mut Param)) {
+    self.synth.for_each_modulated_param(&apply);
+    self.effects.for_each_modulated_param(&apply);
+  }
 }
@@ -0,0 +1,48 @@
+use druid::{Data, Lens};
+
+use kiro_synth_dsp::float::Float;
+use kiro_synth_engine::program::Program;
+
+use crate::synth::program::params::EnvFollowerParams;
+use crate::synth::SynthClientMutex;
+use crate::ui::data::{Param, Synth};
+
+pub struct EnvFollowerFromSynth;
+
+impl Lens<Synth, EnvFollower> for EnvFollowerFromSynth {
+  fn with<V, F: FnOnce(&EnvFollower) -> V>(&self, data: &Synth, f: F) -> V {
+    let index = data.mod_index - data.eg.len() - data.lfo.len() - data.global_lfo.len();
+    f(&data.env_follower[index])
+  }
+
+  fn with_mut<V, F: FnOnce(&mut EnvFollower) -> V>(&self, data: &mut Synth, f: F) -> V {
+    let index = data.mod_index - data.eg.len() - data.lfo.len() - data.global_lfo.len();
+    f(&mut data.env_follower[index])
+  }
+}
+
+#[derive(Debug, Clone, Data, Lens)]
+pub struct EnvFollower {
+  pub detector: Param,
+  pub attack: Param,
+  pub release: Param,
+}
+
+impl EnvFollower {
+  pub fn new<'a, F: Float + 'static>(
+    program: &Program<'a, F>,
+    params: &EnvFollowerParams,
+    synth_client: SynthClientMutex<f32>,
+  ) -> Self {
+    EnvFollower {
+      detector: Param::new(program, &params.detector, synth_client.clone()),
+      attack: Param::new(program, &params.attack, synth_client.clone()),
+      release: Param::new(program, &params.release, synth_client),
+    }
+  }
+
+  pub fn for_each_modulated_param(&mut self, apply: &impl Fn(&mut Param)) {
+    apply(&mut self.attack);
+    apply(&mut self.release);
+  }
+}
@@ -19,12 +19,30 @@ impl Lens<Synth, Lfo> for LfoFromSynth {
   }
 }
 
+pub struct GlobalLfoFromSynth;
+
+impl Lens<Synth, Lfo> for GlobalLfoFromSynth {
+  fn with<V, F: FnOnce(&Lfo) -> V>(&self, data: &Synth, f: F) -> V {
+    f(&data.global_lfo[data.mod_index - data.eg.len() - data.lfo.len()])
+  }
+
+  fn with_mut<V, F: FnOnce(&mut Lfo) -> V>(&self, data: &mut Synth, f: F) -> V {
+    let index = data.mod_index - data.eg.len() - data.lfo.len();
+    f(&mut data.global_lfo[index])
+  }
+}
+
 #[derive(Debug, Clone, Data, Lens)]
 pub struct Lfo {
   pub shape: Param,
+  pub mode: Param,
   pub rate: Param,
   pub phase: Param,
   pub depth: Param,
+  pub delay: Param,
+  pub fade_in: Param,
+  pub sync: Param,
+  pub division: Param,
 }
 
 impl Lfo {
@@ -35,9 +53,14 @@ impl Lfo {
   ) -> Self {
     Lfo {
       shape: Param::new(program, &params.shape, synth_client.clone()),
+      mode: Param::new(program, &params.mode, synth_client.clone()),
       rate: Param::new(program, &params.rate, synth_client.clone()),
       phase: Param::new(program, &params.phase, synth_client.clone()),
-      depth: Param::new(program, &params.depth, synth_client),
+      depth: Param::new(program, &params.depth, synth_client.clone()),
+      delay: Param::new(program, &params.delay, synth_client.clone()),
+      fade_in: Param::new(program, &params.fade_in, synth_client.clone()),
+      sync: Param::new(program, &params.sync, synth_client.clone()),
+      division: Param::new(program, &params.division, synth_client),
     }
   }
 
@@ -45,5 +68,7 @@ impl Lfo {
     apply(&mut self.rate);
     apply(&mut self.phase);
     apply(&mut self.depth);
+    apply(&mut self.delay);
+    apply(&mut self.fade_in);
   }
 }
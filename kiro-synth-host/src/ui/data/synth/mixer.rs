@@ -0,0 +1,51 @@
+use druid::{Data, Lens};
+
+use kiro_synth_dsp::float::Float;
+use kiro_synth_engine::program::Program;
+
+use crate::synth::program::params::MixerChannelParams;
+use crate::synth::SynthClientMutex;
+use crate::ui::data::{Param, Synth};
+
+/// The mixer channel of the selected oscillator
+pub struct MixerChannelFromSynth;
+
+impl Lens<Synth, MixerChannel> for MixerChannelFromSynth {
+  fn with<V, F: FnOnce(&MixerChannel) -> V>(&self, data: &Synth, f: F) -> V {
+    f(&data.mixer[data.osc_index])
+  }
+
+  fn with_mut<V, F: FnOnce(&mut MixerChannel) -> V>(&self, data: &mut Synth, f: F) -> V {
+    f(&mut data.mixer[data.osc_index])
+  }
+}
+
+#[derive(Debug, Clone, Data, Lens)]
+pub struct MixerChannel {
+  pub level: Param,
+  pub pan: Param,
+  pub send: Param,
+  pub mute: Param,
+}
+
+impl MixerChannel {
+  pub fn new<'a, F: Float + 'static>(
+    program: &Program<'a, F>,
+    params: &MixerChannelParams,
+    synth_client: SynthClientMutex<f32>,
+  ) -> Self {
+    MixerChannel {
+      level: Param::new(program, &params.level, synth_client.clone()),
+      pan: Param::new(program, &params.pan, synth_client.clone()).with_origin(0.0),
+      send: Param::new(program, &params.send, synth_client.clone()),
+      mute: Param::new(program, &params.mute, synth_client),
+    }
+  }
+
+  pub fn for_each_modulated_param(&mut self, apply: &impl Fn(&mut Param)) {
+    apply(&mut self.level);
+    apply(&mut self.pan);
+    apply(&mut self.send);
+    apply(&mut self.mute);
+  }
+}
@@ -1,8 +1,11 @@
 mod dca;
 mod eg;
+mod envelope_follower;
 mod filter;
 mod lfo;
+mod mixer;
 mod oscillators;
+mod waveshaper;
 
 use druid::im::{vector, Vector};
 use druid::{Data, Lens};
@@ -16,9 +19,12 @@ use crate::ui::data::param::Param;
 
 pub use dca::Dca;
 pub use eg::{EgFromSynth, EnvGen};
+pub use envelope_follower::{EnvFollower, EnvFollowerFromSynth};
 pub use filter::{Filter, FilterFromSynth};
-pub use lfo::{Lfo, LfoFromSynth};
+pub use lfo::{GlobalLfoFromSynth, Lfo, LfoFromSynth};
+pub use mixer::{MixerChannel, MixerChannelFromSynth};
 pub use oscillators::{Osc, OscFromSynth};
+pub use waveshaper::Waveshaper;
 
 pub struct ZeroIndex;
 
@@ -37,15 +43,23 @@ pub struct Synth {
   pub osc: Vector<Osc>,
   pub osc_index: usize,
 
+  pub mixer: Vector<MixerChannel>,
+
   pub mod_index: usize,
 
   pub eg: Vector<EnvGen>,
 
   pub lfo: Vector<Lfo>,
 
+  pub global_lfo: Vector<Lfo>,
+
+  pub env_follower: Vector<EnvFollower>,
+
   pub filter: Vector<Filter>,
   pub filter_index: usize,
 
+  pub shaper: Waveshaper,
+
   pub dca: Dca,
 
   #[data(ignore)]
@@ -69,6 +83,13 @@ impl Synth {
       ],
       osc_index: 0,
 
+      mixer: vector![
+        MixerChannel::new(program, &params.mix1, synth_client.clone()),
+        MixerChannel::new(program, &params.mix2, synth_client.clone()),
+        MixerChannel::new(program, &params.mix3, synth_client.clone()),
+        MixerChannel::new(program, &params.mix4, synth_client.clone()),
+      ],
+
       mod_index: 0,
 
       eg: vector![EnvGen::new(program, &params.eg1, synth_client.clone()),],
@@ -78,9 +99,19 @@ impl Synth {
         Lfo::new(program, &params.lfo2, synth_client.clone()),
       ],
 
+      global_lfo: vector![Lfo::new(program, &params.glfo1, synth_client.clone()),],
+
+      env_follower: vector![EnvFollower::new(
+        program,
+        &params.envf1,
+        synth_client.clone()
+      ),],
+
       filter: vector![Filter::new(program, &params.filter1, synth_client.clone()),],
       filter_index: 0,
 
+      shaper: Waveshaper::new(program, &params.shaper, synth_client.clone()),
+
       dca: Dca::new(program, &params.dca, synth_client.clone()),
 
       synth_client,
@@ -101,15 +132,25 @@ impl<'a> Synth {
     for osc in self.osc.iter_mut() {
       osc.for_each_modulated_param(&apply);
     }
+    for channel in self.mixer.iter_mut() {
+      channel.for_each_modulated_param(&apply);
+    }
     for eg in self.eg.iter_mut() {
       eg.for_each_modulated_param(&apply);
     }
     for lfo in self.lfo.iter_mut() {
       lfo.for_each_modulated_param(&apply);
     }
+    for lfo in self.global_lfo.iter_mut() {
+      lfo.for_each_modulated_param(&apply);
+    }
+    for env_follower in self.env_follower.iter_mut() {
+      env_follower.for_each_modulated_param(&apply);
+    }
     for filter in self.filter.iter_mut() {
       filter.for_each_modulated_param(&apply);
     }
+    self.shaper.for_each_modulated_param(&apply);
     self.dca.for_each_modulated_param(&apply);
   }
 }
@@ -0,0 +1,36 @@
+use druid::{Data, Lens};
+
+use kiro_synth_dsp::float::Float;
+use kiro_synth_engine::program::Program;
+
+use crate::synth::program::params::WaveshaperParams;
+use crate::synth::SynthClientMutex;
+use crate::ui::data::Param;
+
+#[derive(Debug, Clone, Data, Lens)]
+pub struct Waveshaper {
+  pub curve: Param,
+  pub drive: Param,
+  pub mix: Param,
+  pub oversampling: Param,
+}
+
+impl Waveshaper {
+  pub fn new<'a, F: Float + 'static>(
+    program: &Program<'a, F>,
+    params: &WaveshaperParams,
+    synth_client: SynthClientMutex<f32>,
+  ) -> Self {
+    Waveshaper {
+      curve: Param::new(program, &params.curve, synth_client.clone()),
+      drive: Param::new(program, &params.drive, synth_client.clone()),
+      mix: Param::new(program, &params.mix, synth_client.clone()),
+      oversampling: Param::new(program, &params.oversampling, synth_client),
+    }
+  }
+
+  pub fn for_each_modulated_param(&mut self, apply: &impl Fn(&mut Param)) {
+    apply(&mut self.drive);
+    apply(&mut self.mix);
+  }
+}
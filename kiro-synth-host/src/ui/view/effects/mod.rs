@@ -1 +1,206 @@
+use std::sync::{Arc, Mutex};
 
+use druid::widget::{CrossAxisAlignment, Flex, WidgetExt};
+use druid::{Env, Widget};
+
+use kiro_synth_dsp::float::Float;
+use kiro_synth_engine::program::blocks::modfx;
+use kiro_synth_engine::transport::Division;
+
+use crate::synth::SynthClient;
+use crate::ui::data::effects::{Compressor, Delay, Effects, Equalizer, Limiter, ModFx, Reverb};
+use crate::ui::data::synth::Dca;
+use crate::ui::view::{build_knob_enum, build_knob_value, build_switcher, build_tabs};
+
+/// The effects in the same order they are chained
+const EFFECTS: [&str; 6] = ["MOD", "DELAY", "REVERB", "EQ", "COMP", "MASTER"];
+
+pub fn build<F: Float + 'static>(synth_client: Arc<Mutex<SynthClient<F>>>) -> impl Widget<Effects> {
+  let tabs =
+    build_tabs(EFFECTS.len(), |index| EFFECTS[index].to_string()).lens(Effects::effect_index);
+
+  let effect = build_switcher(
+    tabs,
+    |data: &Effects, _env: &Env| data.effect_index,
+    move |index: &usize, _data: &Effects, _env: &Env| match *index {
+      0 => Box::new(build_modfx_view(synth_client.clone()).lens(Effects::modfx)),
+      1 => Box::new(build_delay_view().lens(Effects::delay)),
+      2 => Box::new(build_reverb_view().lens(Effects::reverb)),
+      3 => Box::new(build_equalizer_view().lens(Effects::eq)),
+      4 => Box::new(build_compressor_view().lens(Effects::comp)),
+      _ => Box::new(
+        Flex::column()
+          .with_child(build_master_view().lens(Effects::master))
+          .with_spacer(10.0)
+          .with_child(build_limiter_view().lens(Effects::limiter)),
+      ),
+    },
+  );
+
+  Flex::column()
+    .with_child(effect.fix_height(228.0).padding(4.0))
+    .cross_axis_alignment(CrossAxisAlignment::Start)
+}
+
+fn on_off(index: usize) -> String {
+  if index == 0 { "off" } else { "on" }.to_string()
+}
+
+fn build_modfx_view<F: Float + 'static>(
+  synth_client: Arc<Mutex<SynthClient<F>>>,
+) -> impl Widget<ModFx> {
+  let mode_fn = |index: usize| {
+    match modfx::Mode::from(index as f64) {
+      Some(modfx::Mode::Chorus) => "chorus",
+      Some(modfx::Mode::Flanger) => "flanger",
+      Some(modfx::Mode::Phaser) => "phaser",
+      None => "",
+    }
+    .to_string()
+  };
+
+  let shape_fn = move |index: usize| {
+    synth_client
+      .lock()
+      .unwrap()
+      .lfo_waveforms()
+      .name(index)
+      .to_string()
+  };
+
+  let row1 = Flex::row()
+    .with_child(build_knob_enum("On", on_off).lens(ModFx::on))
+    .with_child(build_knob_enum("Mode", mode_fn).lens(ModFx::mode))
+    .with_child(build_knob_enum("Shape", shape_fn).lens(ModFx::shape))
+    .with_child(build_knob_value("Rate", " Hz").lens(ModFx::rate))
+    .with_child(build_knob_value("Depth", "").lens(ModFx::depth))
+    .with_flex_spacer(1.0);
+
+  let row2 = Flex::row()
+    .with_child(build_knob_value("Feedback", "").lens(ModFx::feedback))
+    .with_child(build_knob_value("Spread", "").lens(ModFx::spread))
+    .with_child(build_knob_value("Mix", "").lens(ModFx::mix))
+    .with_flex_spacer(1.0);
+
+  Flex::column()
+    .with_child(row1)
+    .with_spacer(10.0)
+    .with_child(row2)
+}
+
+fn build_delay_view() -> impl Widget<Delay> {
+  let mode_fn = |index: usize| if index == 0 { "stereo" } else { "ping-pong" }.to_string();
+
+  let sync_fn = |index: usize| if index == 0 { "off" } else { "on" }.to_string();
+
+  let division_fn = |index: usize| {
+    Division::from(index as f64)
+      .map(|division| division.name)
+      .unwrap_or_default()
+      .to_string()
+  };
+
+  let row1 = Flex::row()
+    .with_child(build_knob_enum("On", on_off).lens(Delay::on))
+    .with_child(build_knob_enum("Mode", mode_fn).lens(Delay::mode))
+    .with_child(build_knob_value("Time L", " s").lens(Delay::time_left))
+    .with_child(build_knob_value("Time R", " s").lens(Delay::time_right))
+    .with_child(build_knob_enum("Sync", sync_fn).lens(Delay::sync))
+    .with_child(build_knob_enum("Div L", division_fn).lens(Delay::division_left))
+    .with_child(build_knob_enum("Div R", division_fn).lens(Delay::division_right))
+    .with_flex_spacer(1.0);
+
+  let row2 = Flex::row()
+    .with_child(build_knob_value("Feedback", "").lens(Delay::feedback))
+    .with_child(build_knob_value("Cross", "").lens(Delay::cross_feedback))
+    .with_child(build_knob_value("Low Pass", " Hz").lens(Delay::lowpass))
+    .with_child(build_knob_value("High Pass", " Hz").lens(Delay::highpass))
+    .with_child(build_knob_value("Mix", "").lens(Delay::mix))
+    .with_flex_spacer(1.0);
+
+  Flex::column()
+    .with_child(row1)
+    .with_spacer(10.0)
+    .with_child(row2)
+}
+
+fn build_reverb_view() -> impl Widget<Reverb> {
+  let row1 = Flex::row()
+    .with_child(build_knob_enum("On", on_off).lens(Reverb::on))
+    .with_child(build_knob_value("Size", "").lens(Reverb::size))
+    .with_child(build_knob_value("Decay", " s").lens(Reverb::decay))
+    .with_child(build_knob_value("Damping", "").lens(Reverb::damping))
+    .with_flex_spacer(1.0);
+
+  let row2 = Flex::row()
+    .with_child(build_knob_value("Pre-delay", " s").lens(Reverb::predelay))
+    .with_child(build_knob_value("Width", "").lens(Reverb::width))
+    .with_child(build_knob_value("Mix", "").lens(Reverb::mix))
+    .with_flex_spacer(1.0);
+
+  Flex::column()
+    .with_child(row1)
+    .with_spacer(10.0)
+    .with_child(row2)
+}
+
+fn build_equalizer_view() -> impl Widget<Equalizer> {
+  let row1 = Flex::row()
+    .with_child(build_knob_enum("On", on_off).lens(Equalizer::on))
+    .with_child(build_knob_value("Low", " Hz").lens(Equalizer::low_frequency))
+    .with_child(build_knob_value("Gain", " dB").lens(Equalizer::low_gain))
+    .with_child(build_knob_value("Q", "").lens(Equalizer::low_q))
+    .with_child(build_knob_value("Mid 1", " Hz").lens(Equalizer::mid1_frequency))
+    .with_child(build_knob_value("Gain", " dB").lens(Equalizer::mid1_gain))
+    .with_child(build_knob_value("Q", "").lens(Equalizer::mid1_q))
+    .with_flex_spacer(1.0);
+
+  let row2 = Flex::row()
+    .with_child(build_knob_value("Mid 2", " Hz").lens(Equalizer::mid2_frequency))
+    .with_child(build_knob_value("Gain", " dB").lens(Equalizer::mid2_gain))
+    .with_child(build_knob_value("Q", "").lens(Equalizer::mid2_q))
+    .with_child(build_knob_value("High", " Hz").lens(Equalizer::high_frequency))
+    .with_child(build_knob_value("Gain", " dB").lens(Equalizer::high_gain))
+    .with_child(build_knob_value("Q", "").lens(Equalizer::high_q))
+    .with_flex_spacer(1.0);
+
+  Flex::column()
+    .with_child(row1)
+    .with_spacer(10.0)
+    .with_child(row2)
+}
+
+fn build_compressor_view() -> impl Widget<Compressor> {
+  let row1 = Flex::row()
+    .with_child(build_knob_enum("On", on_off).lens(Compressor::on))
+    .with_child(build_knob_value("Threshold", " dB").lens(Compressor::threshold))
+    .with_child(build_knob_value("Ratio", "").lens(Compressor::ratio))
+    .with_child(build_knob_value("Knee", " dB").lens(Compressor::knee))
+    .with_flex_spacer(1.0);
+
+  let row2 = Flex::row()
+    .with_child(build_knob_value("Attack", " s").lens(Compressor::attack))
+    .with_child(build_knob_value("Release", " s").lens(Compressor::release))
+    .with_child(build_knob_value("Makeup", " dB").lens(Compressor::makeup))
+    .with_flex_spacer(1.0);
+
+  Flex::column()
+    .with_child(row1)
+    .with_spacer(10.0)
+    .with_child(row2)
+}
+
+fn build_master_view() -> impl Widget<Dca> {
+  Flex::row()
+    .with_child(build_knob_value("Amplitude", " dB").lens(Dca::amplitude))
+    .with_child(build_knob_value("Balance", "").lens(Dca::pan))
+    .with_flex_spacer(1.0)
+}
+
+fn build_limiter_view() -> impl Widget<Limiter> {
+  Flex::row()
+    .with_child(build_knob_enum("On", on_off).lens(Limiter::on))
+    .with_child(build_knob_value("Ceiling", " dB").lens(Limiter::ceiling))
+    .with_child(build_knob_value("Release", " s").lens(Limiter::release))
+    .with_flex_spacer(1.0)
+}
@@ -112,7 +112,7 @@ pub fn build<F: Float + 'static>(
     move |view: &SelectedView, data: &AppData, _env: &Env| match view {
       SelectedView::Presets => build_presets_view(),
       SelectedView::Synth => build_synth_view(&data, synth_client.clone()),
-      SelectedView::Effects => build_effects_view(),
+      SelectedView::Effects => build_effects_view(synth_client.clone()),
     },
   );
 
@@ -150,8 +150,10 @@ fn build_synth_view<F: Float + 'static>(
     .boxed()
 }
 
-fn build_effects_view() -> Box<dyn Widget<AppData>> {
-  let effects = Label::new("TODO: Effects");
+fn build_effects_view<F: Float + 'static>(
+  synth_client: Arc<Mutex<SynthClient<F>>>,
+) -> Box<dyn Widget<AppData>> {
+  let effects = effects::build(synth_client).lens(AppData::effects);
 
   let modulations = ModulationsView::build()
     .lens(AppData::modulations)
@@ -2,6 +2,7 @@ use druid::widget::{Flex, WidgetExt};
 use druid::{Env, Widget};
 
 use crate::ui::data::synth::{Filter, FilterFromSynth, Synth};
+use crate::ui::view::synth::waveshaper::build_waveshaper_view;
 use crate::ui::view::{build_knob_value, build_switcher, build_tabs};
 
 pub struct FiltersView;
@@ -9,14 +10,25 @@ pub struct FiltersView;
 impl FiltersView {
   pub fn build(synth_data: &Synth) -> impl Widget<Synth> {
     let filter_len = synth_data.filter.len();
-    let tabs =
-      build_tabs(filter_len, |index| format!("FILTER{}", index + 1)).lens(Synth::filter_index);
+    // The waveshaper goes after the filters
+    let tabs = build_tabs(filter_len + 1, move |index| {
+      if index < filter_len {
+        format!("FILTER{}", index + 1)
+      } else {
+        "SHAPER".to_string()
+      }
+    })
+    .lens(Synth::filter_index);
 
     build_switcher(
       tabs,
       |data: &Synth, _env: &Env| data.filter_index,
-      move |_index: &usize, _data: &Synth, _env: &Env| {
-        Box::new(build_filter_view().lens(FilterFromSynth))
+      move |index: &usize, _data: &Synth, _env: &Env| {
+        if *index < filter_len {
+          Box::new(build_filter_view().lens(FilterFromSynth))
+        } else {
+          Box::new(build_waveshaper_view().lens(Synth::shaper))
+        }
       },
     )
   }
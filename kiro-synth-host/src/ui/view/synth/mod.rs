@@ -17,6 +17,7 @@ mod dca;
 mod filters;
 mod modulators;
 mod oscillators;
+mod waveshaper;
 
 pub fn build<F: Float + 'static>(
   synth_data: &Synth,
@@ -27,7 +28,7 @@ pub fn build<F: Float + 'static>(
   Flex::column()
     .with_child(
       OscillatorsView::build(synth_data, synth_client.clone())
-        .fix_height(height)
+        .fix_height(height * 2.0)
         .padding(4.0),
     )
     .with_child(
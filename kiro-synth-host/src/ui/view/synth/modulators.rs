@@ -1,12 +1,16 @@
 use std::sync::{Arc, Mutex};
 
-use druid::widget::{Flex, SizedBox, WidgetExt};
+use druid::widget::{Flex, WidgetExt};
 use druid::{Env, Widget};
 
 use kiro_synth_dsp::float::Float;
+use kiro_synth_engine::transport::Division;
 
 use crate::synth::SynthClient;
-use crate::ui::data::synth::{EgFromSynth, EnvGen, Lfo, LfoFromSynth, Synth};
+use crate::ui::data::synth::{
+  EgFromSynth, EnvFollower, EnvFollowerFromSynth, EnvGen, GlobalLfoFromSynth, Lfo, LfoFromSynth,
+  Synth,
+};
 use crate::ui::view::{build_knob_enum, build_knob_value, build_switcher, build_tabs};
 
 pub struct ModulatorsView;
@@ -17,12 +21,19 @@ impl ModulatorsView {
     synth_client: Arc<Mutex<SynthClient<F>>>,
   ) -> impl Widget<Synth> {
     let eg_len = synth_data.eg.len();
-    let tabs_len = eg_len + synth_data.lfo.len();
+    let lfo_len = synth_data.lfo.len();
+    let global_lfo_len = synth_data.global_lfo.len();
+    let lfos_len = eg_len + lfo_len + global_lfo_len;
+    let tabs_len = lfos_len + synth_data.env_follower.len();
     let tab_title = move |index| {
       if index < eg_len {
         format!("EG{}", index + 1)
-      } else {
+      } else if index < eg_len + lfo_len {
         format!("LFO{}", index - eg_len + 1)
+      } else if index < lfos_len {
+        format!("GLFO{}", index - eg_len - lfo_len + 1)
+      } else {
+        format!("ENVF{}", index - lfos_len + 1)
       }
     };
 
@@ -34,8 +45,12 @@ impl ModulatorsView {
       move |index: &usize, _data: &Synth, _env: &Env| {
         if *index < eg_len {
           Box::new(build_eg_view().lens(EgFromSynth))
-        } else {
+        } else if *index < eg_len + lfo_len {
           Box::new(build_lfo_view(synth_client.clone()).lens(LfoFromSynth))
+        } else if *index < lfos_len {
+          Box::new(build_lfo_view(synth_client.clone()).lens(GlobalLfoFromSynth))
+        } else {
+          Box::new(build_env_follower_view().lens(EnvFollowerFromSynth))
         }
       },
     )
@@ -61,6 +76,16 @@ fn build_eg_view() -> impl Widget<EnvGen> {
     .with_child(row2)
 }
 
+fn build_env_follower_view() -> impl Widget<EnvFollower> {
+  let detector_fn = |index: usize| if index == 0 { "peak" } else { "rms" }.to_string();
+
+  Flex::row()
+    .with_child(build_knob_enum("Detector", detector_fn).lens(EnvFollower::detector))
+    .with_child(build_knob_value("Attack", " s").lens(EnvFollower::attack))
+    .with_child(build_knob_value("Release", " s").lens(EnvFollower::release))
+    .with_flex_spacer(1.0)
+}
+
 fn build_lfo_view<F: Float + 'static>(
   synth_client: Arc<Mutex<SynthClient<F>>>,
 ) -> impl Widget<Lfo> {
@@ -81,7 +106,34 @@ fn build_lfo_view<F: Float + 'static>(
     .with_child(build_knob_value("Depth", "").lens(Lfo::depth))
     .with_flex_spacer(1.0);
 
-  let row2 = SizedBox::<Lfo>::empty().height(86.0);
+  let mode_fn = |index: usize| {
+    match index {
+      0 => "free",
+      1 => "retrig",
+      _ => "1-shot",
+    }
+    .to_string()
+  };
 
-  Flex::column().with_child(row1).with_child(row2)
+  let sync_fn = |index: usize| if index == 0 { "off" } else { "on" }.to_string();
+
+  let division_fn = |index: usize| {
+    Division::from(index as f64)
+      .map(|division| division.name)
+      .unwrap_or_default()
+      .to_string()
+  };
+
+  let row2 = Flex::row()
+    .with_child(build_knob_enum("Mode", mode_fn).lens(Lfo::mode))
+    .with_child(build_knob_enum("Sync", sync_fn).lens(Lfo::sync))
+    .with_child(build_knob_enum("Division", division_fn).lens(Lfo::division))
+    .with_child(build_knob_value("Delay", " s").lens(Lfo::delay))
+    .with_child(build_knob_value("Fade", " s").lens(Lfo::fade_in))
+    .with_flex_spacer(1.0);
+
+  Flex::column()
+    .with_child(row1)
+    .with_spacer(10.0)
+    .with_child(row2)
 }
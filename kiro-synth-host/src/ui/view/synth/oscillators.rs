@@ -6,7 +6,7 @@ use druid::{Env, Widget};
 use kiro_synth_dsp::float::Float;
 
 use crate::synth::SynthClient;
-use crate::ui::data::synth::{Osc, OscFromSynth, Synth};
+use crate::ui::data::synth::{MixerChannel, MixerChannelFromSynth, Osc, OscFromSynth, Synth};
 use crate::ui::view::{build_knob_enum, build_knob_value, build_switcher, build_tabs};
 
 pub struct OscillatorsView;
@@ -23,7 +23,12 @@ impl OscillatorsView {
       tabs,
       |data: &Synth, _env: &Env| data.osc_index,
       move |_index: &usize, _data: &Synth, _env: &Env| {
-        Box::new(build_osc_view(synth_client.clone()).lens(OscFromSynth))
+        Box::new(
+          Flex::column()
+            .with_child(build_osc_view(synth_client.clone()).lens(OscFromSynth))
+            .with_spacer(10.0)
+            .with_child(build_mixer_channel_view().lens(MixerChannelFromSynth)),
+        )
       },
     )
   }
@@ -50,3 +55,14 @@ fn build_osc_view<F: Float + 'static>(
     .with_child(build_knob_value("Amplitude", "").lens(Osc::amplitude))
     .with_flex_spacer(1.0)
 }
+
+fn build_mixer_channel_view() -> impl Widget<MixerChannel> {
+  let mute_fn = |index: usize| if index == 0 { "off" } else { "on" }.to_string();
+
+  Flex::row()
+    .with_child(build_knob_value("Level", "").lens(MixerChannel::level))
+    .with_child(build_knob_value("Pan", "").lens(MixerChannel::pan))
+    .with_child(build_knob_value("Filter", "").lens(MixerChannel::send))
+    .with_child(build_knob_enum("Mute", mute_fn).lens(MixerChannel::mute))
+    .with_flex_spacer(1.0)
+}
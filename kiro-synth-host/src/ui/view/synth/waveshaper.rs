@@ -0,0 +1,35 @@
+use druid::widget::{Flex, WidgetExt};
+use druid::Widget;
+
+use kiro_synth_dsp::effects::waveshaper::{Curve, Oversampling};
+
+use crate::ui::data::synth::Waveshaper;
+use crate::ui::view::{build_knob_enum, build_knob_value};
+
+pub fn build_waveshaper_view() -> impl Widget<Waveshaper> {
+  let curve_fn = |index: usize| {
+    match Curve::from(index as f64) {
+      Some(Curve::SoftClip) => "soft",
+      Some(Curve::HardClip) => "hard",
+      Some(Curve::Foldback) => "fold",
+      Some(Curve::BitCrush) => "crush",
+      Some(Curve::RateReduction) => "rate",
+      None => "",
+    }
+    .to_string()
+  };
+
+  let oversampling_fn = |index: usize| match Oversampling::from(index as f64) {
+    Some(oversampling) if oversampling != Oversampling::None => {
+      format!("{}x", oversampling.factor())
+    }
+    _ => "off".to_string(),
+  };
+
+  Flex::row()
+    .with_child(build_knob_enum("Curve", curve_fn).lens(Waveshaper::curve))
+    .with_child(build_knob_value("Drive", "").lens(Waveshaper::drive))
+    .with_child(build_knob_value("Mix", "").lens(Waveshaper::mix))
+    .with_child(build_knob_enum("Oversample", oversampling_fn).lens(Waveshaper::oversampling))
+    .with_flex_spacer(1.0)
+}